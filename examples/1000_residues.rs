@@ -44,38 +44,22 @@ fn print_residues<T: FieldElement>(start_at: usize, count: usize) {
     .bold();
     println!("{message}",);
 
-    let mut found_count = 0;
-    let mut x = start_at;
-    while found_count < count {
-        let element = T::from_usize(x);
-        match element.legendre() {
-            1 => {
-                // number is a residue
-                // return number and roots
-                let low_root = element.sqrt();
-                let high_root = -low_root.clone();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let residues =
+        scalarff::functions::scan_residues::<T, _>(|_, _| true, start_at as u64..u64::MAX, threads);
+    for (_, element, low_root) in residues.take(count) {
+        let high_root = -low_root.clone();
 
-                assert_eq!(element, low_root.clone() * low_root.clone());
-                assert_eq!(element, high_root.clone() * high_root.clone());
-                assert_eq!(-element.clone(), low_root.clone() * high_root.clone());
+        assert_eq!(element, low_root.clone() * low_root.clone());
+        assert_eq!(element, high_root.clone() * high_root.clone());
+        assert_eq!(-element.clone(), low_root.clone() * high_root.clone());
 
-                println!(
-                    "    -{}_{} = {} * {}",
-                    element.lower60_string().red().bold(),
-                    T::name_str().green().bold(),
-                    low_root.lower60_string(),
-                    high_root.lower60_string(),
-                );
-                found_count += 1;
-            }
-            -1 => {
-                // number is a non-residue (no roots in field)
-            }
-            0 => {
-                // number is 0, skip
-            }
-            _ => unreachable!(),
-        }
-        x += 1;
+        println!(
+            "    -{}_{} = {} * {}",
+            element.lower60_string().red().bold(),
+            T::name_str().green().bold(),
+            low_root.lower60_string(),
+            high_root.lower60_string(),
+        );
     }
 }
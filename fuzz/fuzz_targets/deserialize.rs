@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scalarff_fuzz::for_each_field;
+
+fuzz_target!(|data: &[u8]| {
+    for_each_field!(deserialize, data);
+});
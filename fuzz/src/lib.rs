@@ -0,0 +1,57 @@
+//! Checks shared by the fuzz targets. None of these
+//! should panic for any input.
+//!
+//! Run a target with `cargo fuzz run <target>` from the repository root.
+//! Fields are enabled through the features of this crate.
+//!
+use scalarff::FieldElement;
+
+/// `from_str` returns an error for invalid input. Parsed values
+/// round trip through `serialize`.
+pub fn from_str<T: FieldElement>(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(v) = T::from_str(s) {
+        assert!(T::from_str(&v.serialize()).ok() == Some(v));
+    }
+}
+
+/// `deserialize` accepts every string `from_str` accepts
+/// and produces the same element.
+pub fn deserialize<T: FieldElement>(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(v) = T::from_str(s) {
+        assert!(T::deserialize(s) == v);
+    }
+    // canonical strings parse to the same element
+    if let Ok(v) = T::try_deserialize(s) {
+        assert!(T::from_str(s).ok() == Some(v));
+    }
+}
+
+/// `from_bytes_le` accepts any input of at most `byte_len` bytes.
+/// Decoded values round trip through `to_bytes_le`.
+pub fn from_bytes_le<T: FieldElement>(data: &[u8]) {
+    let bytes = &data[..data.len().min(T::byte_len())];
+    let v = T::from_bytes_le(bytes);
+    assert!(T::from_bytes_le(&v.to_bytes_le()) == v);
+    assert!(T::try_from_bytes_le(&v.to_bytes_le()) == Ok(v));
+    // any length is accepted without panicking
+    let _ = T::try_from_bytes_le(data);
+}
+
+/// Run a check for every field enabled through features.
+#[macro_export]
+macro_rules! for_each_field {
+    ( $check: ident, $data: expr ) => {
+        #[cfg(feature = "alt_bn128")]
+        $crate::$check::<scalarff::Bn128FieldElement>($data);
+        #[cfg(feature = "curve25519")]
+        $crate::$check::<scalarff::Curve25519FieldElement>($data);
+        #[cfg(feature = "oxfoi")]
+        $crate::$check::<scalarff::OxfoiFieldElement>($data);
+    };
+}
@@ -0,0 +1,228 @@
+//! Addition chains for fixed exponents.
+//!
+//! Exponents like `p - 2` (inversion) and `(p + 1)/4` (square roots
+//! when `p = 3 mod 4`) are the same for every call in a field. An
+//! `AdditionChain` is built once from the exponent using a sliding
+//! window and then evaluated with field multiplications only, without
+//! converting the element to a `BigUint`.
+//!
+//! `inverse_chain` and the square root chains build the chains for a
+//! field lazily and cache them for the lifetime of the program. They
+//! are used by `FieldElement::inv_fermat` and `FieldElement::sqrt`.
+//!
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use super::BigUint;
+use super::FieldElement;
+
+/// Window size used for exponents of 64 bits or more.
+const WINDOW: u64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Square,
+    /// Multiply by `x^(2i + 1)` from the window table.
+    Mul(usize),
+}
+
+/// A precomputed sequence of squarings and multiplications
+/// computing `x^e` for a fixed `e`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdditionChain {
+    /// Index of the first window in the table, `None` if `e = 0`.
+    start: Option<usize>,
+    /// Number of odd powers `x^1, x^3, ...` to precompute.
+    table_len: usize,
+    steps: Vec<Step>,
+}
+
+impl AdditionChain {
+    pub fn new(e: &BigUint) -> Self {
+        let bits = e.bits();
+        if bits == 0 {
+            return AdditionChain {
+                start: None,
+                table_len: 0,
+                steps: vec![],
+            };
+        }
+        let window = if bits < 64 { 2 } else { WINDOW };
+        // the lowest set bit j in the window ending at bit i, and
+        // the table index of the odd value of bits i..=j
+        let window_at = |i: u64| -> (u64, usize) {
+            let mut j = i.saturating_sub(window - 1);
+            while !e.bit(j) {
+                j += 1;
+            }
+            let v = (j..=i)
+                .rev()
+                .fold(0, |acc, b| (acc << 1) | usize::from(e.bit(b)));
+            (j, v >> 1)
+        };
+        let (j, first) = window_at(bits - 1);
+        let mut table_len = first + 1;
+        let mut steps = vec![];
+        // scan the bits below the first window
+        let mut i = j;
+        while i > 0 {
+            i -= 1;
+            if !e.bit(i) {
+                steps.push(Step::Square);
+                continue;
+            }
+            let (j, v) = window_at(i);
+            steps.extend(std::iter::repeat_n(Step::Square, (i - j + 1) as usize));
+            steps.push(Step::Mul(v));
+            table_len = table_len.max(v + 1);
+            i = j;
+        }
+        AdditionChain {
+            start: Some(first),
+            table_len,
+            steps,
+        }
+    }
+
+    /// The number of precomputed powers plus the number of steps,
+    /// roughly the number of field multiplications needed to evaluate
+    /// the chain.
+    pub fn len(&self) -> usize {
+        self.table_len + self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compute `x^e`.
+    pub fn apply<T: FieldElement>(&self, x: &T) -> T {
+        let start = match self.start {
+            Some(start) => start,
+            None => return T::one(),
+        };
+        let mut table = Vec::with_capacity(self.table_len);
+        table.push(x.clone());
+        if self.table_len > 1 {
+            let square = x.clone() * x.clone();
+            for k in 1..self.table_len {
+                table.push(table[k - 1].clone() * square.clone());
+            }
+        }
+        let mut acc = table[start].clone();
+        for step in &self.steps {
+            match step {
+                Step::Square => acc = acc.clone() * acc,
+                Step::Mul(i) => acc *= table[*i].clone(),
+            }
+        }
+        acc
+    }
+}
+
+type ChainCache = RwLock<Option<HashMap<(TypeId, &'static str), Arc<AdditionChain>>>>;
+
+static CHAINS: ChainCache = RwLock::new(None);
+
+fn cached<T: FieldElement>(kind: &'static str, e: impl FnOnce() -> BigUint) -> Arc<AdditionChain> {
+    let key = (TypeId::of::<T>(), kind);
+    if let Some(chain) = CHAINS.read().unwrap().as_ref().and_then(|m| m.get(&key)) {
+        return chain.clone();
+    }
+    let chain = Arc::new(AdditionChain::new(&e()));
+    CHAINS
+        .write()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .entry(key)
+        .or_insert(chain)
+        .clone()
+}
+
+/// The chain for `p - 2`, i.e. inversion by Fermat's little theorem.
+pub fn inverse_chain<T: FieldElement>() -> Arc<AdditionChain> {
+    cached::<T>("inverse", || T::prime() - 2_u32)
+}
+
+/// The chain for `(p + 1)/4`, a square root of quadratic residues
+/// when `p = 3 mod 4`. Returns `None` for other moduli.
+pub fn sqrt_chain<T: FieldElement>() -> Option<Arc<AdditionChain>> {
+    let p = T::prime();
+    if !(p.bit(0) && p.bit(1)) {
+        return None;
+    }
+    Some(cached::<T>("sqrt", || (p + 1_u32) >> 2))
+}
+
+/// The chain for `(p - 5)/8`, used by Atkin's square root when
+/// `p = 5 mod 8`. Returns `None` for other moduli.
+pub fn atkin_chain<T: FieldElement>() -> Option<Arc<AdditionChain>> {
+    let p = T::prime();
+    if !(p.bit(0) && !p.bit(1) && p.bit(2)) {
+        return None;
+    }
+    Some(cached::<T>("atkin", || (p - 5_u32) >> 3))
+}
+
+/// The chain for `(q - 1)/2` where `p - 1 = q 2^s` with `q` odd,
+/// the exponentiation in Tonelli–Shanks.
+pub fn tonelli_shanks_chain<T: FieldElement>() -> Arc<AdditionChain> {
+    cached::<T>("tonelli_shanks", || {
+        let q = (T::prime() - 1_u32) >> T::two_adicity();
+        q >> 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1019FieldElement, 1019_u128, "f1019");
+
+    fn test_chains<T: FieldElement>() {
+        let p = T::prime();
+        let x = -T::from(12345);
+        let exponents = [0_u64, 1, 2, 3, 7, 16, 255, 256, 1 << 40, u64::MAX]
+            .map(BigUint::from)
+            .into_iter()
+            .chain([&p - 2_u32, &p - 1_u32, &p >> 1]);
+        for e in exponents {
+            let expected = T::from_biguint(&x.to_biguint().modpow(&e, &p));
+            assert_eq!(AdditionChain::new(&e).apply(&x), expected, "exponent {e}");
+        }
+        assert_eq!(inverse_chain::<T>().apply(&x) * x, T::one());
+        // the chain is cached
+        assert!(Arc::ptr_eq(&inverse_chain::<T>(), &inverse_chain::<T>()));
+    }
+
+    #[test]
+    fn chains_scalar_ring() {
+        test_chains::<F1019FieldElement>();
+        // 1019 = 3 mod 4
+        let chain = sqrt_chain::<F1019FieldElement>().unwrap();
+        let x = F1019FieldElement::from(777);
+        let square = x * x;
+        let root = chain.apply(&square);
+        assert_eq!(root * root, square);
+        assert!(atkin_chain::<F1019FieldElement>().is_none());
+        assert!(AdditionChain::new(&BigUint::from(0_u32)).is_empty());
+        assert_eq!(AdditionChain::new(&BigUint::from(1_u32)).len(), 1);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn chains_bn128() {
+        test_chains::<crate::Bn128FieldElement>();
+        assert!(sqrt_chain::<crate::Bn128FieldElement>().is_none());
+        // a 254 bit exponent costs far fewer than 2 * 254 multiplications
+        assert!(inverse_chain::<crate::Bn128FieldElement>().len() < 320);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn chains_foi() {
+        test_chains::<crate::OxfoiFieldElement>();
+    }
+}
@@ -10,29 +10,156 @@ use std::ops::Neg;
 use std::ops::Sub;
 use std::ops::SubAssign;
 
+use ark_bn254::Fq;
 use ark_bn254::Fr;
 use ark_ff::biginteger::BigInt;
 use ark_ff::BigInteger;
+use ark_ff::FftField;
+use ark_ff::Field;
 use ark_ff::PrimeField;
 use ark_std::str::FromStr;
 use num_bigint::BigUint;
 
+use super::curve::CurveInfo;
 use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq)]
 pub struct Bn128FieldElement(Fr);
 
+// hash the canonical integer, not the montgomery form
+impl Hash for Bn128FieldElement {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.into_bigint().0.hash(state);
+    }
+}
+
+impl Bn128FieldElement {
+    /// Parameters of the alt_bn128 (aka bn254) curve. The elements
+    /// of this field are scalars of the curve, coordinates of points
+    /// are in the base field.
+    pub fn curve_info() -> CurveInfo {
+        CurveInfo {
+            name: "alt_bn128",
+            base_field_modulus: Fq::MODULUS.into(),
+            scalar_field_modulus: Fr::MODULUS.into(),
+            a: BigUint::from(0_u32),
+            b: BigUint::from(3_u32),
+            cofactor: BigUint::from(1_u32),
+            embedding_degree: 12,
+        }
+    }
+
+    /// Build an element from the little endian 64 bit limbs of its
+    /// canonical integer representation. Returns `None` if the value
+    /// is not less than the modulus.
+    pub fn from_bigint_limbs(limbs: [u64; 4]) -> Option<Self> {
+        Fr::from_bigint(BigInt::new(limbs)).map(Bn128FieldElement)
+    }
+
+    /// The little endian 64 bit limbs of the canonical integer
+    /// representation of the element.
+    pub fn to_bigint_limbs(&self) -> [u64; 4] {
+        self.0.into_bigint().0
+    }
+
+    /// The little endian limbs of the Montgomery form `x * 2^256 mod r`.
+    /// This is the in-memory layout of gnark's `fr.Element` and of
+    /// arkworks' `Fr`.
+    pub fn to_montgomery_limbs(&self) -> [u64; 4] {
+        (self.0).0 .0
+    }
+
+    /// Build an element from Montgomery form limbs, e.g. a gnark
+    /// `fr.Element`. Returns `None` if the limbs are not reduced.
+    pub fn from_montgomery_limbs(limbs: [u64; 4]) -> Option<Self> {
+        let v = BigInt::new(limbs);
+        if v >= Fr::MODULUS {
+            return None;
+        }
+        Some(Bn128FieldElement(Fr::new_unchecked(v)))
+    }
+
+    /// Hex encoding of the 32 byte big endian standard form, as
+    /// produced by gnark's `fr.Element.Bytes()` and accepted by
+    /// `SetString("0x...")`.
+    pub fn to_gnark_hex(&self) -> String {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        format!("0x{hex}")
+    }
+
+    /// Parse a `0x` prefixed big endian hex string in standard form.
+    /// Returns `None` for invalid hex or values not less than r.
+    pub fn from_gnark_hex(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix("0x")?;
+        if hex.is_empty() || hex.len() > 64 {
+            return None;
+        }
+        let mut limbs = [0_u64; 4];
+        for (i, chunk) in hex.as_bytes().rchunks(16).enumerate() {
+            limbs[i] = u64::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        }
+        Self::from_bigint_limbs(limbs)
+    }
+}
+
+impl FixedByteLen for Bn128FieldElement {
+    const BYTE_LEN: usize = 32;
+}
+
 impl FieldElement for Bn128FieldElement {
     fn name_str() -> &'static str {
         "alt_bn128"
     }
 
+    fn backend_str() -> &'static str {
+        "ark-bn254 0.4"
+    }
+
+    fn modpow_bytes(&self, exp_le: &[u8]) -> Self {
+        Bn128FieldElement(self.0.pow(super::le_bytes_to_limbs(exp_le)))
+    }
+
+    fn is_small_field() -> bool {
+        false
+    }
+
+    fn fits_in_u64() -> bool {
+        false
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    fn two_adicity() -> u32 {
+        Fr::TWO_ADICITY
+    }
+
+    fn generator() -> Self {
+        Bn128FieldElement(Fr::GENERATOR)
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        if k > Fr::TWO_ADICITY {
+            panic!("alt_bn128 does not contain a subgroup of order 2^{k}");
+        }
+        let mut g = Fr::TWO_ADIC_ROOT_OF_UNITY;
+        for _ in k..Fr::TWO_ADICITY {
+            g *= g;
+        }
+        Bn128FieldElement(g)
+    }
+
     fn prime() -> num_bigint::BigUint {
         Fr::MODULUS.into()
     }
 
     fn byte_len() -> usize {
-        32
+        Self::BYTE_LEN
     }
 
     // why does arkworks serialize 0 to an empty string?
@@ -47,7 +174,7 @@ impl FieldElement for Bn128FieldElement {
     }
 
     fn deserialize(str: &str) -> Self {
-        Self(Fr::from_str(str).unwrap())
+        Self::from_str(str).unwrap()
     }
 
     fn to_bytes_le(&self) -> Vec<u8> {
@@ -60,7 +187,41 @@ impl FieldElement for Bn128FieldElement {
     }
 
     fn from_bytes_le(bytes: &[u8]) -> Self {
-        Self(Fr::from_str(&BigUint::from_bytes_le(bytes).to_string()).unwrap())
+        crate::assert_byte_len::<Self>(bytes);
+        Self(Fr::from_le_bytes_mod_order(bytes))
+    }
+}
+
+#[cfg(feature = "interop")]
+impl ark_serialize::CanonicalSerialize for Bn128FieldElement {
+    fn serialize_with_mode<W: ark_serialize::Write>(
+        &self,
+        writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), ark_serialize::SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+#[cfg(feature = "interop")]
+impl ark_serialize::Valid for Bn128FieldElement {
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.0.check()
+    }
+}
+
+#[cfg(feature = "interop")]
+impl ark_serialize::CanonicalDeserialize for Bn128FieldElement {
+    fn deserialize_with_mode<R: ark_serialize::Read>(
+        reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, ark_serialize::SerializationError> {
+        Fr::deserialize_with_mode(reader, compress, validate).map(Bn128FieldElement)
     }
 }
 
@@ -80,7 +241,23 @@ impl FromStr for Bn128FieldElement {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Bn128FieldElement(Fr::from_str(s).unwrap()))
+        if s.starts_with('+') {
+            return Err(());
+        }
+        // arkworks rejects leading zeroes, accept them
+        // for consistency with the other implementations
+        let trimmed = s.trim_start_matches('0');
+        if trimmed.is_empty() && !s.is_empty() {
+            Ok(Self::zero())
+        } else {
+            Ok(Bn128FieldElement(Fr::from_str(trimmed)?))
+        }
+    }
+}
+
+impl Default for Bn128FieldElement {
+    fn default() -> Self {
+        Self::zero()
     }
 }
 
@@ -94,7 +271,10 @@ impl Add for Bn128FieldElement {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Bn128FieldElement(self.0 + other.0)
+        let out = Bn128FieldElement(self.0 + other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+        out
     }
 }
 
@@ -102,7 +282,10 @@ impl Sub for Bn128FieldElement {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Bn128FieldElement(self.0 - other.0)
+        let out = Bn128FieldElement(self.0 - other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+        out
     }
 }
 
@@ -110,7 +293,10 @@ impl Mul for Bn128FieldElement {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        Bn128FieldElement(self.0 * other.0)
+        let out = Bn128FieldElement(self.0 * other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+        out
     }
 }
 
@@ -119,7 +305,10 @@ impl Div for Bn128FieldElement {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        Bn128FieldElement(self.0 / other.0)
+        let out = Bn128FieldElement(self.0 / other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+        out
     }
 }
 
@@ -145,6 +334,78 @@ impl Neg for Bn128FieldElement {
     type Output = Self;
 
     fn neg(self) -> Self {
-        Bn128FieldElement(-self.0)
+        let out = Bn128FieldElement(-self.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_info() {
+        let info = Bn128FieldElement::curve_info();
+        assert_eq!(info.scalar_field_modulus, Bn128FieldElement::prime());
+        assert_ne!(info.base_field_modulus, info.scalar_field_modulus);
+        // r | q^k - 1 for k = 12 and no smaller k
+        let r = &info.scalar_field_modulus;
+        let q_mod_r = &info.base_field_modulus % r;
+        let mut q_pow = BigUint::from(1_u32);
+        for k in 1..=info.embedding_degree {
+            q_pow = (q_pow * &q_mod_r) % r;
+            assert_eq!(q_pow == BigUint::from(1_u32), k == info.embedding_degree);
+        }
+    }
+
+    #[test]
+    fn gnark_representations() {
+        // R mod r, the Montgomery form of one
+        let one_mont = [
+            0xac96341c4ffffffb,
+            0x36fc76959f60cd29,
+            0x666ea36f7879462e,
+            0x0e0a77c19a07df2f,
+        ];
+        let one = Bn128FieldElement::one();
+        assert_eq!(one.to_montgomery_limbs(), one_mont);
+        assert_eq!(
+            Bn128FieldElement::from_montgomery_limbs(one_mont),
+            Some(one)
+        );
+        assert_eq!(
+            Bn128FieldElement::from_montgomery_limbs(Fr::MODULUS.0),
+            None
+        );
+        assert_eq!(
+            one.to_gnark_hex(),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        let minus_one = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000";
+        assert_eq!((-one).to_gnark_hex(), minus_one);
+        assert_eq!(Bn128FieldElement::from_gnark_hex(minus_one), Some(-one));
+        assert_eq!(Bn128FieldElement::from_gnark_hex("0x1"), Some(one));
+        let modulus = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+        for invalid in ["", "1", "0x", "0xg", modulus] {
+            assert_eq!(Bn128FieldElement::from_gnark_hex(invalid), None);
+        }
+    }
+
+    #[test]
+    fn bigint_limbs() {
+        let modulus = Fr::MODULUS.0;
+        for v in [
+            Bn128FieldElement::zero(),
+            Bn128FieldElement::from(u64::MAX),
+            -Bn128FieldElement::one(),
+        ] {
+            let limbs = v.to_bigint_limbs();
+            assert_eq!(Bn128FieldElement::from_bigint_limbs(limbs), Some(v));
+            assert_eq!(BigUint::from(BigInt::new(limbs)), v.to_biguint());
+        }
+        assert_eq!(Bn128FieldElement::from_bigint_limbs(modulus), None);
+        assert_eq!(Bn128FieldElement::from_bigint_limbs([u64::MAX; 4]), None);
     }
 }
@@ -0,0 +1,258 @@
+//! The BabyBear prime field, `p = 15 * 2^27 + 1 = 2^31 - 2^27 + 1`.
+//! Requires the `babybear` feature.
+//!
+//! Elements are canonical `u32` values, products are computed in a
+//! `u64` and reduced by a constant divisor (which the compiler turns
+//! into a multiplication). `p - 1 = 2^27 * 15` so NTTs of length up
+//! to `2^27` are supported. `31` generates the multiplicative group.
+//!
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+
+use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub struct BabyBearFieldElement(u32);
+
+impl BabyBearFieldElement {
+    /// The modulus `15 * 2^27 + 1`.
+    pub const P: u32 = 0x78000001;
+
+    /// A generator of the multiplicative group.
+    pub const GENERATOR: u32 = 31;
+
+    /// The canonical integer representation.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    fn reduce(v: u64) -> u32 {
+        (v % u64::from(Self::P)) as u32
+    }
+}
+
+impl FixedByteLen for BabyBearFieldElement {
+    const BYTE_LEN: usize = 4;
+}
+
+impl FieldElement for BabyBearFieldElement {
+    fn name_str() -> &'static str {
+        "babybear"
+    }
+
+    fn backend_str() -> &'static str {
+        "native-u64"
+    }
+
+    fn zero() -> Self {
+        BabyBearFieldElement(0)
+    }
+
+    fn one() -> Self {
+        BabyBearFieldElement(1)
+    }
+
+    fn byte_len() -> usize {
+        Self::BYTE_LEN
+    }
+
+    fn prime() -> BigUint {
+        BigUint::from(Self::P)
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    fn inv_fermat(&self) -> Option<Self> {
+        if self.0 == 0 {
+            return None;
+        }
+        Some(self.pow_u64(u64::from(Self::P - 2)))
+    }
+
+    fn two_adicity() -> u32 {
+        27
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        if k > 27 {
+            panic!("babybear does not contain a subgroup of order 2^{k}");
+        }
+        // GENERATOR^15 has order 2^27
+        BabyBearFieldElement(Self::GENERATOR).pow_u64(15 << (27 - k))
+    }
+
+    fn serialize(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn deserialize(str: &str) -> Self {
+        Self::from_str(str).unwrap()
+    }
+
+    fn to_bytes_le(&self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Self {
+        crate::assert_byte_len::<Self>(bytes);
+        let mut padded = [0_u8; 8];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        Self::from(u64::from_le_bytes(padded))
+    }
+}
+
+impl Display for BabyBearFieldElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for BabyBearFieldElement {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
+        let v = BigUint::from_str(s).map_err(|_| ())?;
+        Ok(Self::from_biguint(&v))
+    }
+}
+
+impl From<u64> for BabyBearFieldElement {
+    fn from(value: u64) -> Self {
+        BabyBearFieldElement(Self::reduce(value))
+    }
+}
+
+impl Add for BabyBearFieldElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        // both < 2^31, the sum doesn't overflow
+        let v = self.0 + other.0;
+        let out = BabyBearFieldElement(if v >= Self::P { v - Self::P } else { v });
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+        out
+    }
+}
+
+impl Sub for BabyBearFieldElement {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let out = if self.0 >= other.0 {
+            BabyBearFieldElement(self.0 - other.0)
+        } else {
+            BabyBearFieldElement(self.0 + (Self::P - other.0))
+        };
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+        out
+    }
+}
+
+impl Mul for BabyBearFieldElement {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let out = BabyBearFieldElement(Self::reduce(u64::from(self.0) * u64::from(other.0)));
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+        out
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div for BabyBearFieldElement {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let out = match other.inv_fermat() {
+            Some(inv) => self * inv,
+            None => panic!("Division by zero"),
+        };
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+        out
+    }
+}
+
+impl AddAssign for BabyBearFieldElement {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl MulAssign for BabyBearFieldElement {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl SubAssign for BabyBearFieldElement {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for BabyBearFieldElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let out = if self.0 == 0 {
+            self
+        } else {
+            BabyBearFieldElement(Self::P - self.0)
+        };
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F = BabyBearFieldElement;
+
+    #[test]
+    fn modulus() {
+        assert_eq!(F::prime(), BigUint::from(15_u32 << 27) + 1_u32);
+        assert!(crate::model_check::check::<F>(31, 5_000).is_ok());
+        assert_eq!(F::from(u64::from(F::P)), F::zero());
+        assert_eq!(F::from(u64::MAX).value(), (u64::MAX % 0x78000001) as u32);
+        assert_eq!(-F::one() * -F::one(), F::one());
+    }
+
+    #[test]
+    fn roots_of_unity() {
+        let g = F::two_adic_generator(27);
+        assert_eq!(g.pow_u64(1 << 26), -F::one());
+        assert_eq!(g.pow_u64(1 << 27), F::one());
+        assert_eq!(F::two_adic_generator(1), -F::one());
+        // 31 generates the whole group, so it's a non-residue
+        assert_eq!(F::from(u64::from(F::GENERATOR)).legendre(), -1);
+        let x = F::from(31337);
+        let root = (x * x).sqrt();
+        assert!(root == x || root == -x);
+    }
+}
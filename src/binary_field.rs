@@ -0,0 +1,434 @@
+//! Binary extension fields `GF(2^k)` for `k` in 8, 16, 32, 64, 128.
+//! Requires the `binary_field` feature.
+//!
+//! Elements are polynomials over `GF(2)` of degree `< k`, stored as
+//! the bits of an integer (bit `i` is the coefficient of `x^i`) and
+//! multiplied modulo a fixed irreducible polynomial:
+//!   - `Binary8FieldElement` - `x^8 + x^4 + x^3 + x + 1` (AES)
+//!   - `Binary16FieldElement` - `x^16 + x^5 + x^3 + x + 1`
+//!   - `Binary32FieldElement` - `x^32 + x^7 + x^3 + x^2 + 1`
+//!   - `Binary64FieldElement` - `x^64 + x^4 + x^3 + x + 1`
+//!   - `Binary128FieldElement` - `x^128 + x^7 + x^2 + x + 1` (GCM, without
+//!     the bit reflection)
+//!
+//! Multiplication uses the `pclmulqdq` instruction on x86_64 when the
+//! CPU supports it, and a portable carryless multiplication otherwise.
+//!
+//! The characteristic is 2: addition and subtraction are xor, `-x == x`
+//! and `1 + 1 == 0`. `prime()` returns the field order `2^k` (which is
+//! not prime) so the canonical bijection with the integers `[0, 2^k)`
+//! is used by `from_biguint`, `From<u64>`, serialization and sampling.
+//! `From<u64>` maps the bits of the integer to coefficients, it is not
+//! the ring map from the integers (`from(2)` is `x`, not `1 + 1`).
+//!
+//! `characteristic()` is 2, so the generic algorithms specialize:
+//! every element is a square, `sqrt` is the inverse of the Frobenius
+//! map `x -> x^2` (which is linear) and `legendre` is 1 for every
+//! non-zero element. `div_pow2` panics.
+//!
+use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
+
+/// Carryless multiplication of two 64 bit polynomials.
+pub fn clmul64(a: u64, b: u64) -> u128 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::arch::is_x86_feature_detected!("pclmulqdq") {
+            // safety: the required cpu feature is available
+            return unsafe { clmul64_pclmul(a, b) };
+        }
+    }
+    clmul64_portable(a, b)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq", enable = "sse2")]
+unsafe fn clmul64_pclmul(a: u64, b: u64) -> u128 {
+    use std::arch::x86_64::*;
+    let a = _mm_set_epi64x(0, a as i64);
+    let b = _mm_set_epi64x(0, b as i64);
+    let r = _mm_clmulepi64_si128(a, b, 0);
+    let lo = _mm_cvtsi128_si64(r) as u64;
+    let hi = _mm_cvtsi128_si64(_mm_unpackhi_epi64(r, r)) as u64;
+    (u128::from(hi) << 64) | u128::from(lo)
+}
+
+/// Carryless multiplication without platform specific instructions.
+pub fn clmul64_portable(a: u64, b: u64) -> u128 {
+    let a = u128::from(a);
+    let mut out = 0_u128;
+    for i in 0..64 {
+        // mask instead of branching on the bits of b
+        let bit = u128::from((b >> i) & 1);
+        out ^= (a << i) & bit.wrapping_neg();
+    }
+    out
+}
+
+/// Carryless multiplication of two 128 bit polynomials, returns
+/// the `(high, low)` halves of the 256 bit product.
+pub fn clmul128(a: u128, b: u128) -> (u128, u128) {
+    let (a_hi, a_lo) = ((a >> 64) as u64, a as u64);
+    let (b_hi, b_lo) = ((b >> 64) as u64, b as u64);
+    let lo = clmul64(a_lo, b_lo);
+    let hi = clmul64(a_hi, b_hi);
+    // karatsuba: (a_hi + a_lo)(b_hi + b_lo) - hi - lo
+    let mid = clmul64(a_hi ^ a_lo, b_hi ^ b_lo) ^ hi ^ lo;
+    (hi ^ (mid >> 64), lo ^ (mid << 64))
+}
+
+/// Reduce the 256 bit product `(hi, lo)` modulo `x^k + r(x)` where
+/// `r` has degree less than 64.
+fn reduce(mut hi: u128, mut lo: u128, k: u32, r: u128) -> u128 {
+    let mask = if k == 128 { u128::MAX } else { (1 << k) - 1 };
+    loop {
+        // the coefficients of x^k and above
+        let top = if k == 128 {
+            hi
+        } else {
+            (lo >> k) | (hi << (128 - k))
+        };
+        if top == 0 {
+            return lo & mask;
+        }
+        // x^k = r(x)
+        let (r_hi, r_lo) = clmul128(top, r);
+        hi = r_hi;
+        lo = (lo & mask) ^ r_lo;
+    }
+}
+
+macro_rules! binary_field {
+    ( $name: ident, $bits: literal, $reduction: literal, $name_str: expr ) => {
+        #[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, std::hash::Hash, Default)]
+        pub struct $name(u128);
+
+        impl $name {
+            /// The low terms `r(x)` of the modulus `x^k + r(x)`.
+            pub const REDUCTION: u128 = $reduction;
+
+            const MASK: u128 = u128::MAX >> (128 - $bits);
+
+            /// Build an element from its coefficient bits. Bits at
+            /// or above `k` are ignored.
+            pub fn from_bits(v: u128) -> Self {
+                $name(v & Self::MASK)
+            }
+
+            /// The coefficient bits, bit `i` is the coefficient of `x^i`.
+            pub fn to_bits(&self) -> u128 {
+                self.0
+            }
+        }
+
+        impl FixedByteLen for $name {
+            const BYTE_LEN: usize = $bits / 8;
+        }
+
+        const _: () = assert!($name::BYTE_LEN * 8 == $bits);
+
+        impl FieldElement for $name {
+            fn name_str() -> &'static str {
+                $name_str
+            }
+
+            fn backend_str() -> &'static str {
+                "native-clmul"
+            }
+
+            fn zero() -> Self {
+                $name(0)
+            }
+
+            fn one() -> Self {
+                $name(1)
+            }
+
+            fn byte_len() -> usize {
+                Self::BYTE_LEN
+            }
+
+            fn prime() -> num_bigint::BigUint {
+                num_bigint::BigUint::from(1_u32) << $bits
+            }
+
+            fn characteristic() -> num_bigint::BigUint {
+                num_bigint::BigUint::from(2_u32)
+            }
+
+            fn inv_strategy() -> InvStrategy {
+                InvStrategy::Fermat
+            }
+
+            fn serialize(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn deserialize(str: &str) -> Self {
+                <Self as std::str::FromStr>::from_str(str).unwrap()
+            }
+
+            fn to_bytes_le(&self) -> Vec<u8> {
+                self.0.to_le_bytes()[..$bits / 8].to_vec()
+            }
+
+            fn from_bytes_le(bytes: &[u8]) -> Self {
+                $crate::assert_byte_len::<Self>(bytes);
+                let mut padded = [0_u8; 16];
+                padded[..bytes.len()].copy_from_slice(bytes);
+                $name(u128::from_le_bytes(padded))
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.starts_with('+') {
+                    return Err(());
+                }
+                s.parse::<u128>().map(Self::from_bits).map_err(|_| ())
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                Self::from_bits(u128::from(value))
+            }
+        }
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl std::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $name(self.0 ^ other.0)
+            }
+        }
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl std::ops::Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $name(self.0 ^ other.0)
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                let (hi, lo) = clmul128(self.0, other.0);
+                $name(reduce(hi, lo, $bits, Self::REDUCTION))
+            }
+        }
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl std::ops::Div for $name {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                if other.0 == 0 {
+                    panic!("Division by zero");
+                }
+                // x^(2^k - 2) = x^2 * x^4 * ... * x^(2^(k-1))
+                let mut inv = $name(1);
+                let mut t = other;
+                for _ in 1..$bits {
+                    t = t * t;
+                    inv *= t;
+                }
+                self * inv
+            }
+        }
+
+        impl std::ops::AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl std::ops::MulAssign for $name {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl std::ops::SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl std::ops::Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+binary_field!(Binary8FieldElement, 8, 0x1b, "gf2_8");
+binary_field!(Binary16FieldElement, 16, 0x2b, "gf2_16");
+binary_field!(Binary32FieldElement, 32, 0x8d, "gf2_32");
+binary_field!(Binary64FieldElement, 64, 0x1b, "gf2_64");
+binary_field!(Binary128FieldElement, 128, 0x87, "gf2_128");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shift and add multiplication, one bit at a time.
+    fn slow_mul(a: u128, b: u128, k: u32, r: u128) -> u128 {
+        let top = 1_u128 << (k - 1);
+        let mask = u128::MAX >> (128 - k);
+        let (mut a, mut out) = (a, 0_u128);
+        for i in 0..k {
+            if (b >> i) & 1 == 1 {
+                out ^= a;
+            }
+            let carry = a & top != 0;
+            a = (a << 1) & mask;
+            if carry {
+                a ^= r;
+            }
+        }
+        out
+    }
+
+    fn xorshift(x: &mut u128) -> u128 {
+        *x ^= *x << 23;
+        *x ^= *x >> 17;
+        *x ^= *x << 5;
+        *x
+    }
+
+    #[test]
+    fn carryless_mul() {
+        let mut x = 0x9e3779b97f4a7c15f39cc0605cedc834_u128;
+        for _ in 0..1000 {
+            let (a, b) = (xorshift(&mut x) as u64, xorshift(&mut x) as u64);
+            assert_eq!(clmul64(a, b), clmul64_portable(a, b));
+        }
+        assert_eq!(clmul64(0b11, 0b11), 0b101);
+        assert_eq!(clmul128(1 << 127, 1 << 127), (1 << 126, 0));
+    }
+
+    #[test]
+    fn aes_field() {
+        // FIPS 197 section 4.2
+        let mul = |a, b| Binary8FieldElement::from(a) * Binary8FieldElement::from(b);
+        assert_eq!(mul(0x57, 0x83), Binary8FieldElement::from(0xc1));
+        assert_eq!(mul(0x57, 0x13), Binary8FieldElement::from(0xfe));
+        assert_eq!(mul(0x53, 0xca), Binary8FieldElement::one());
+        assert_eq!(
+            Binary8FieldElement::one() / Binary8FieldElement::from(0x53),
+            Binary8FieldElement::from(0xca)
+        );
+    }
+
+    fn test_binary_field<T: FieldElement>(k: u32, r: u128, bits: fn(&T) -> u128) {
+        assert_eq!(T::prime(), num_bigint::BigUint::from(1_u32) << k);
+        assert_eq!(T::byte_len() as u32, k / 8);
+        assert_eq!(T::one() + T::one(), T::zero());
+        let mut x = 0x2545f4914f6cdd1d9e3779b97f4a7c15_u128;
+        let mask = u128::MAX >> (128 - k);
+        for _ in 0..200 {
+            let (a_bits, b_bits) = (xorshift(&mut x) & mask, xorshift(&mut x) & mask);
+            let a = T::from_bytes_le(&a_bits.to_le_bytes()[..T::byte_len()]);
+            let b = T::from_bytes_le(&b_bits.to_le_bytes()[..T::byte_len()]);
+            assert_eq!(
+                bits(&(a.clone() * b.clone())),
+                slow_mul(a_bits, b_bits, k, r)
+            );
+            assert_eq!(bits(&(a.clone() + b.clone())), a_bits ^ b_bits);
+            assert_eq!(-a.clone(), a);
+            if b != T::zero() {
+                assert_eq!((a.clone() / b.clone()) * b.clone(), a);
+            }
+            let root = a.sqrt();
+            assert_eq!(root.clone() * root.clone(), a);
+            assert_eq!(a.sqrt_pair(), Some((root.clone(), root)));
+            assert_eq!(T::from_biguint(&a.to_biguint()), a);
+            assert_eq!(T::deserialize(&a.serialize()), a);
+        }
+        assert_eq!(T::zero().legendre(), 0);
+        assert_eq!(T::one().sqrt(), T::one());
+    }
+
+    #[test]
+    fn binary_fields() {
+        test_binary_field::<Binary8FieldElement>(8, 0x1b, |v| v.to_bits());
+        test_binary_field::<Binary16FieldElement>(16, 0x2b, |v| v.to_bits());
+        test_binary_field::<Binary32FieldElement>(32, 0x8d, |v| v.to_bits());
+        test_binary_field::<Binary64FieldElement>(64, 0x1b, |v| v.to_bits());
+        test_binary_field::<Binary128FieldElement>(128, 0x87, |v| v.to_bits());
+    }
+
+    fn poly_gcd(mut a: u128, mut b: u128) -> u128 {
+        while b != 0 {
+            while a != 0 && a.leading_zeros() <= b.leading_zeros() {
+                a ^= b << (b.leading_zeros() - a.leading_zeros());
+            }
+            (a, b) = (b, a);
+        }
+        a
+    }
+
+    fn test_irreducible<T: FieldElement>(k: u32, r: u128, bits: fn(&T) -> u128) {
+        // f is irreducible iff x^(2^k) = x mod f and
+        // gcd(x^(2^(k/2)) - x, f) = 1, for k a power of 2
+        let x = T::from(2);
+        let frobenius = |n: u32| (0..n).fold(x.clone(), |acc, _| acc.clone() * acc);
+        assert_eq!(frobenius(k), x);
+        let h = bits(&(frobenius(k / 2) - x.clone()));
+        assert_eq!(poly_gcd((1 << k) | r, h), 1);
+    }
+
+    #[test]
+    fn irreducible_moduli() {
+        test_irreducible::<Binary8FieldElement>(8, 0x1b, |v| v.to_bits());
+        test_irreducible::<Binary16FieldElement>(16, 0x2b, |v| v.to_bits());
+        test_irreducible::<Binary32FieldElement>(32, 0x8d, |v| v.to_bits());
+        test_irreducible::<Binary64FieldElement>(64, 0x1b, |v| v.to_bits());
+    }
+
+    #[test]
+    fn characteristic_two() {
+        type F = Binary8FieldElement;
+        assert_eq!(F::characteristic(), num_bigint::BigUint::from(2_u32));
+        let x = F::from(0x57);
+        assert_eq!(x.mul_pow2(0), x);
+        assert_eq!(x.mul_pow2(3), F::zero());
+        assert_eq!(x.inv_euclid(), Some(F::one() / x));
+        assert_eq!(F::zero().inv_euclid(), None);
+        assert_eq!(x.legendre(), 1);
+        // 255 = 3 * 5 * 17
+        let w = F::root_of_unity(255).unwrap();
+        assert_eq!(w.pow_u64(255), F::one());
+        assert!([3, 5, 17].iter().all(|q| w.pow_u64(255 / q) != F::one()));
+        assert!(F::root_of_unity(2).is_none());
+        assert!(!F::is_permutation_exponent(3));
+        assert_eq!(F::smallest_permutation_exponent(), 2);
+        assert!(std::panic::catch_unwind(|| x.div_pow2(1)).is_err());
+    }
+
+    #[test]
+    fn multiplicative_order() {
+        // the multiplicative group has order 2^k - 1
+        let g = Binary16FieldElement::from(3);
+        assert_eq!(g.pow_u64(65535), Binary16FieldElement::one());
+        let g = Binary32FieldElement::from(0xdeadbeef);
+        assert_eq!(g.pow_u64(u32::MAX.into()), Binary32FieldElement::one());
+    }
+}
@@ -0,0 +1,217 @@
+//! Ordering of elements by their canonical integer lift.
+//!
+//! `FieldElement` doesn't implement `Ord`, field elements have no
+//! natural order. For set-like operations any consistent order works,
+//! these functions order elements by their lift into `[0, p)`.
+//!
+//! `CanonicalKey` wraps an element with its precomputed
+//! `canonical_hash_u64` for use as a key in large hash maps and sets.
+//! Combined with `CanonicalBuildHasher` the element bytes are hashed
+//! once, when the key is built.
+//!
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use super::FieldElement;
+
+/// Compare two elements by their canonical integer lift.
+pub fn cmp_canonical<T: FieldElement>(a: &T, b: &T) -> Ordering {
+    canonical_key(a).cmp(&canonical_key(b))
+}
+
+/// Sort elements by their canonical integer lift. The key of
+/// each element is computed once.
+pub fn sort_canonical<T: FieldElement>(values: &mut [T]) {
+    values.sort_by_cached_key(canonical_key);
+}
+
+/// Sort elements and remove duplicates.
+pub fn dedup_canonical<T: FieldElement>(values: &mut Vec<T>) {
+    sort_canonical(values);
+    values.dedup();
+}
+
+/// Binary search a slice sorted with `sort_canonical`. Same return
+/// semantics as `slice::binary_search`.
+pub fn binary_search_canonical<T: FieldElement>(values: &[T], target: &T) -> Result<usize, usize> {
+    let key = canonical_key(target);
+    values.binary_search_by(|v| canonical_key(v).cmp(&key))
+}
+
+/// An element with its canonical hash computed once.
+#[derive(Debug, Clone)]
+pub struct CanonicalKey<T: FieldElement> {
+    value: T,
+    hash: u64,
+}
+
+impl<T: FieldElement> CanonicalKey<T> {
+    pub fn new(value: T) -> Self {
+        let hash = value.canonical_hash_u64();
+        CanonicalKey { value, hash }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// The precomputed `canonical_hash_u64` of the element.
+    pub fn hash_u64(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl<T: FieldElement> From<T> for CanonicalKey<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: FieldElement> PartialEq for CanonicalKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.value == other.value
+    }
+}
+
+// field equality is an equivalence relation
+impl<T: FieldElement> Eq for CanonicalKey<T> {}
+
+impl<T: FieldElement> Hash for CanonicalKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// A `BuildHasher` that passes the precomputed hash of a
+/// `CanonicalKey` through unchanged. Only use it with
+/// `CanonicalKey` keys.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CanonicalBuildHasher;
+
+impl BuildHasher for CanonicalBuildHasher {
+    type Hasher = CanonicalHasher;
+
+    fn build_hasher(&self) -> CanonicalHasher {
+        CanonicalHasher(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CanonicalHasher(u64);
+
+impl Hasher for CanonicalHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        // not reached by CanonicalKey, fold in anything else
+        for b in bytes {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.0 = v;
+    }
+}
+
+pub type CanonicalHashMap<T, V> = HashMap<CanonicalKey<T>, V, CanonicalBuildHasher>;
+pub type CanonicalHashSet<T> = HashSet<CanonicalKey<T>, CanonicalBuildHasher>;
+
+/// A key whose lexicographic order matches the order of the lift:
+/// the significant byte length followed by the big endian bytes.
+fn canonical_key<T: FieldElement>(v: &T) -> (usize, Vec<u8>) {
+    let mut bytes = v.to_bytes_le();
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    bytes.truncate(len);
+    bytes.reverse();
+    (len, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    fn test_ordering<T: FieldElement>() {
+        let mut values = (0..200_u64)
+            .map(|i| T::from(i * 7919 % 300))
+            .chain([-T::one(), T::from(256), T::from(255)])
+            .collect::<Vec<_>>();
+        let mut expected = values.iter().map(|v| v.to_biguint()).collect::<Vec<_>>();
+        expected.sort();
+        sort_canonical(&mut values);
+        assert_eq!(
+            values.iter().map(|v| v.to_biguint()).collect::<Vec<_>>(),
+            expected
+        );
+        expected.dedup();
+        dedup_canonical(&mut values);
+        assert_eq!(values.len(), expected.len());
+        for (i, v) in values.iter().enumerate() {
+            assert_eq!(binary_search_canonical(&values, v), Ok(i));
+        }
+        assert_eq!(cmp_canonical(&T::from(255), &T::from(256)), Ordering::Less);
+        assert_eq!(cmp_canonical(&-T::one(), &T::zero()), Ordering::Greater);
+    }
+
+    #[test]
+    fn canonical_keys() {
+        let mut counts = CanonicalHashMap::<F1009FieldElement, usize>::default();
+        for i in 0..5000_u64 {
+            *counts.entry(F1009FieldElement::from(i).into()).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 1009);
+        let key = CanonicalKey::new(F1009FieldElement::from(3));
+        assert_eq!(counts[&key], 5);
+        assert_eq!(
+            key.hash_u64(),
+            F1009FieldElement::from(3).canonical_hash_u64()
+        );
+        // equal elements built differently collide
+        let same = CanonicalKey::new(F1009FieldElement::from(1012));
+        assert_eq!(key, same);
+        let set = [key, same]
+            .into_iter()
+            .collect::<CanonicalHashSet<F1009FieldElement>>();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn ordering_scalar_ring() {
+        test_ordering::<F1009FieldElement>();
+        let values = [1, 5, 9].map(F1009FieldElement::from);
+        assert_eq!(
+            binary_search_canonical(&values, &F1009FieldElement::from(6)),
+            Err(2)
+        );
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn ordering_bn128() {
+        test_ordering::<crate::Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn ordering_curve25519() {
+        test_ordering::<crate::Curve25519FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn ordering_foi() {
+        test_ordering::<crate::OxfoiFieldElement>();
+    }
+}
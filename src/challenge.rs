@@ -0,0 +1,328 @@
+//! Verifier challenges with a guaranteed amount of soundness.
+//!
+//! A challenge sampled from a 64 bit field (e.g. oxfoi) gives at most
+//! 64 bits of soundness per check, often much less after union bounds.
+//! `ChallengeField` picks the smallest extension `F_p^d`, `d <= 3`,
+//! with at least the requested number of bits and samples challenges
+//! there. Large fields use `d = 1` and challenges are plain base field
+//! elements. Protocol code handles both cases with the same
+//! `Challenge` type.
+//!
+//! The extension is `F_p[x] / f(x)` for a fixed irreducible monic `f`
+//! found by a deterministic search, so both sides of a protocol agree
+//! on it. Only the ring operations a verifier needs (add, sub, mul,
+//! evaluating base field polynomials) are provided.
+//!
+use super::polynomial::Polynomial;
+use super::FieldElement;
+
+/// The soundness, in bits, targeted by `ChallengeField::new`.
+pub const DEFAULT_CHALLENGE_BITS: u32 = 100;
+
+/// The largest extension degree `ChallengeField` will use.
+pub const MAX_CHALLENGE_DEGREE: usize = 3;
+
+/// A challenge in `F_p^d`, `d` coefficients of a polynomial in the
+/// extension generator, lowest degree first. Only meaningful with the
+/// `ChallengeField` that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Challenge<T: FieldElement> {
+    coefficients: Vec<T>,
+}
+
+impl<T: FieldElement> Challenge<T> {
+    /// The coefficients, exactly `degree` of them.
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// The challenge as a base field element, `None` if it has
+    /// non-zero extension coefficients.
+    pub fn as_base(&self) -> Option<&T> {
+        if self.coefficients[1..].iter().any(|c| c != &T::zero()) {
+            return None;
+        }
+        Some(&self.coefficients[0])
+    }
+}
+
+/// The field challenges are sampled from, `F_p` or an extension of
+/// degree 2 or 3. See the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeField<T: FieldElement> {
+    modulus: Polynomial<T>,
+}
+
+impl<T: FieldElement> Default for ChallengeField<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: FieldElement> ChallengeField<T> {
+    /// A challenge field with `DEFAULT_CHALLENGE_BITS` of soundness.
+    /// Panics if this needs an extension above `MAX_CHALLENGE_DEGREE`,
+    /// see `with_soundness`.
+    pub fn new() -> Self {
+        Self::with_soundness(DEFAULT_CHALLENGE_BITS).unwrap_or_else(|| {
+            panic!(
+                "field {} is too small for {DEFAULT_CHALLENGE_BITS} bit challenges",
+                T::name_str()
+            )
+        })
+    }
+
+    /// The smallest extension with at least `2^bits` elements. Returns
+    /// `None` if that needs a degree above `MAX_CHALLENGE_DEGREE`.
+    pub fn with_soundness(bits: u32) -> Option<Self> {
+        // p >= 2^(bits(p) - 1)
+        let per_degree = (T::prime().bits() - 1).max(1);
+        let degree = u64::from(bits).div_ceil(per_degree).max(1) as usize;
+        if degree > MAX_CHALLENGE_DEGREE {
+            return None;
+        }
+        Some(Self::with_degree(degree))
+    }
+
+    /// The extension of exactly `degree`, `1 <= degree <= 3`.
+    pub fn with_degree(degree: usize) -> Self {
+        assert!(
+            (1..=MAX_CHALLENGE_DEGREE).contains(&degree),
+            "challenge degree must be between 1 and {MAX_CHALLENGE_DEGREE}: {degree}"
+        );
+        let x = Polynomial::monomial(T::one(), 1);
+        if degree == 1 {
+            return ChallengeField { modulus: x };
+        }
+        // a polynomial of degree 2 or 3 is irreducible iff it has no
+        // roots. Try x^d - c, then x^d + x + c, for c = 1, 2, ...
+        let x_d = Polynomial::monomial(T::one(), degree);
+        for c in 1..=u16::MAX {
+            let c = Polynomial::new(vec![T::from(u64::from(c))]);
+            for modulus in [x_d.clone() - c.clone(), x_d.clone() + x.clone() + c] {
+                let field = ChallengeField { modulus };
+                if !field.modulus_has_root() {
+                    return field;
+                }
+            }
+        }
+        unreachable!("no irreducible polynomial of degree {degree} found")
+    }
+
+    /// The extension degree `d`.
+    pub fn degree(&self) -> usize {
+        self.modulus.degree()
+    }
+
+    /// The irreducible monic polynomial defining the extension,
+    /// `x` when `degree` is 1.
+    pub fn modulus(&self) -> &Polynomial<T> {
+        &self.modulus
+    }
+
+    /// The number of bits of soundness a single uniform challenge
+    /// gives against a single bad event, `floor(log2(p^d))`.
+    pub fn soundness_bits(&self) -> u64 {
+        T::prime().pow(self.degree() as u32).bits() - 1
+    }
+
+    /// Embed a base field element.
+    pub fn from_base(&self, v: T) -> Challenge<T> {
+        self.from_coefficients(vec![v])
+    }
+
+    /// A challenge from at most `degree` coefficients, lowest first.
+    pub fn from_coefficients(&self, mut coefficients: Vec<T>) -> Challenge<T> {
+        assert!(
+            coefficients.len() <= self.degree(),
+            "too many coefficients for a degree {} challenge",
+            self.degree()
+        );
+        coefficients.resize(self.degree(), T::zero());
+        Challenge { coefficients }
+    }
+
+    /// Sample a uniformly random challenge. Requires the `random`
+    /// feature.
+    #[cfg(feature = "random")]
+    pub fn sample<R: rand::Rng>(&self, src: &mut R) -> Challenge<T> {
+        let coefficients = (0..self.degree()).map(|_| T::sample_uniform(src)).collect();
+        Challenge { coefficients }
+    }
+
+    pub fn add(&self, a: &Challenge<T>, b: &Challenge<T>) -> Challenge<T> {
+        self.reduce(self.lift(a) + self.lift(b))
+    }
+
+    pub fn sub(&self, a: &Challenge<T>, b: &Challenge<T>) -> Challenge<T> {
+        self.reduce(self.lift(a) - self.lift(b))
+    }
+
+    pub fn mul(&self, a: &Challenge<T>, b: &Challenge<T>) -> Challenge<T> {
+        self.reduce(self.lift(a) * self.lift(b))
+    }
+
+    /// Multiply by a base field element.
+    pub fn mul_base(&self, a: &Challenge<T>, v: &T) -> Challenge<T> {
+        self.reduce(self.lift(a).mul_scalar(v))
+    }
+
+    /// Evaluate a polynomial with base field coefficients at a
+    /// challenge using Horner's method.
+    pub fn eval(&self, p: &Polynomial<T>, x: &Challenge<T>) -> Challenge<T> {
+        let mut out = self.from_base(T::zero());
+        for c in p.coefficients().iter().rev() {
+            out = self.mul(&out, x);
+            out.coefficients[0] += c.clone();
+        }
+        out
+    }
+
+    fn lift(&self, a: &Challenge<T>) -> Polynomial<T> {
+        assert_eq!(
+            a.coefficients.len(),
+            self.degree(),
+            "challenge is from a different challenge field"
+        );
+        Polynomial::new(a.coefficients.clone())
+    }
+
+    fn reduce(&self, p: Polynomial<T>) -> Challenge<T> {
+        let (_, r) = p.div_rem_monic(&self.modulus);
+        self.from_coefficients(r.coefficients().to_vec())
+    }
+
+    /// Whether the modulus has a root in the base field, i.e. shares
+    /// a factor with `x^q - x` where `q` is the base field size.
+    fn modulus_has_root(&self) -> bool {
+        let x = self.from_coefficients(vec![T::zero(), T::one()]);
+        let q = T::prime();
+        let mut x_q = self.from_base(T::one());
+        for i in (0..q.bits()).rev() {
+            x_q = self.mul(&x_q, &x_q);
+            if q.bit(i) {
+                x_q = self.mul(&x_q, &x);
+            }
+        }
+        let mut a = self.modulus.clone();
+        let mut b = self.lift(&self.sub(&x_q, &x));
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        a.degree() > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+    scalar_ring!(F11FieldElement, 11_u128, "f11");
+    scalar_ring!(Goldilocks, 18446744069414584321_u128, "goldilocks");
+
+    fn irreducible<T: FieldElement>(field: &ChallengeField<T>) -> bool {
+        let p = T::prime().to_u64_digits()[0];
+        (0..p).all(|v| field.modulus().eval(&T::from(v)) != T::zero())
+    }
+
+    #[test]
+    fn degree_selection() {
+        assert_eq!(ChallengeField::<Goldilocks>::new().degree(), 2);
+        assert_eq!(
+            ChallengeField::<Goldilocks>::with_soundness(128)
+                .unwrap()
+                .degree(),
+            3
+        );
+        assert_eq!(
+            ChallengeField::<Goldilocks>::with_soundness(60)
+                .unwrap()
+                .degree(),
+            1
+        );
+        assert_eq!(ChallengeField::<Goldilocks>::new().soundness_bits(), 127);
+        assert_eq!(
+            ChallengeField::<F13FieldElement>::with_soundness(9)
+                .unwrap()
+                .degree(),
+            3
+        );
+        assert_eq!(ChallengeField::<F13FieldElement>::with_soundness(10), None);
+    }
+
+    #[test]
+    fn irreducible_moduli() {
+        for degree in 2..=3 {
+            assert!(irreducible(
+                &ChallengeField::<F13FieldElement>::with_degree(degree)
+            ));
+            // 11 = 2 mod 3, every element is a cube and x^3 - c always has a root
+            let f11 = ChallengeField::<F11FieldElement>::with_degree(degree);
+            assert!(irreducible(&f11));
+            assert_eq!(f11.degree(), degree);
+        }
+        let f11 = ChallengeField::<F11FieldElement>::with_degree(3);
+        assert_eq!(f11.modulus().coefficients()[1], F11FieldElement::one());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let field = ChallengeField::<F13FieldElement>::with_degree(3);
+        let a = field.from_coefficients(
+            vec![3_u64, 1, 4]
+                .into_iter()
+                .map(F13FieldElement::from)
+                .collect(),
+        );
+        let b = field.from_coefficients(
+            vec![1_u64, 5, 9]
+                .into_iter()
+                .map(F13FieldElement::from)
+                .collect(),
+        );
+        let c = field.from_base(F13FieldElement::from(7));
+        assert_eq!(field.mul(&a, &b), field.mul(&b, &a));
+        assert_eq!(
+            field.mul(&a, &field.add(&b, &c)),
+            field.add(&field.mul(&a, &b), &field.mul(&a, &c))
+        );
+        assert_eq!(field.sub(&field.add(&a, &b), &b), a);
+        assert_eq!(
+            field.mul(&a, &c),
+            field.mul_base(&a, &F13FieldElement::from(7))
+        );
+        assert_eq!(c.as_base(), Some(&F13FieldElement::from(7)));
+        assert_eq!(a.as_base(), None);
+        // the multiplicative group has order 13^3 - 1
+        let mut power = field.from_base(F13FieldElement::one());
+        for _ in 0..13_u32.pow(3) - 1 {
+            power = field.mul(&power, &a);
+        }
+        assert_eq!(power, field.from_base(F13FieldElement::one()));
+        // eval agrees with the base field at base field points
+        let p = Polynomial::new(
+            vec![2_u64, 0, 5, 1]
+                .into_iter()
+                .map(F13FieldElement::from)
+                .collect(),
+        );
+        assert_eq!(
+            field.eval(&p, &c),
+            field.from_base(p.eval(&F13FieldElement::from(7)))
+        );
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn sampling() {
+        let mut rng = rand::thread_rng();
+        let field = ChallengeField::<Goldilocks>::new();
+        let a = field.sample(&mut rng);
+        assert_eq!(a.coefficients().len(), 2);
+        let base = ChallengeField::<Goldilocks>::with_degree(1);
+        assert!(base.sample(&mut rng).as_base().is_some());
+    }
+}
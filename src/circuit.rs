@@ -0,0 +1,306 @@
+//! Layered arithmetic circuits for GKR style protocols.
+//!
+//! A `LayeredCircuit` has a layer of inputs followed by layers of
+//! fan-in 2 `Add` and `Mul` gates. Every gate reads two wires of the
+//! layer directly below it. `layers[0]` reads the inputs and the last
+//! layer is the output.
+//!
+//! `evaluate` returns the values of every layer. Layer `k` has
+//! `layer_vars(k)` variables, its values are the evaluations of a
+//! `MultilinearPoly` padded with zeroes to a power of two.
+//!
+//! The wiring predicates of gate layer `k` are the multilinear
+//! extensions of
+//!
+//!   `add_k(z, x, y) = 1` iff gate `z` is `Add` with inputs `x`, `y`
+//!
+//! and `mul_k` likewise, where `z` indexes the gates of the layer and
+//! `x`, `y` the wires below. Then for the layer values `V_k`
+//!
+//!   `V_(k+1)(z) = sum_(x, y) add_k(z, x, y) (V_k(x) + V_k(y))
+//!                          + mul_k(z, x, y) V_k(x) V_k(y)`
+//!
+//! `wiring_predicate` evaluates them at any point in `O(n)` for a
+//! layer of `n` gates, from the sparse list of gates and three
+//! `eq_table`s.
+//!
+use super::error::CircuitError;
+use super::hypercube::eq_table;
+use super::multilinear::MultilinearPoly;
+use super::FieldElement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GateKind {
+    Add,
+    Mul,
+}
+
+/// A gate reading wires `left` and `right` of the layer below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub kind: GateKind,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Gate {
+    pub fn add(left: usize, right: usize) -> Self {
+        Gate {
+            kind: GateKind::Add,
+            left,
+            right,
+        }
+    }
+
+    pub fn mul(left: usize, right: usize) -> Self {
+        Gate {
+            kind: GateKind::Mul,
+            left,
+            right,
+        }
+    }
+
+    fn apply<T: FieldElement>(&self, below: &[T]) -> T {
+        let (l, r) = (below[self.left].clone(), below[self.right].clone());
+        match self.kind {
+            GateKind::Add => l + r,
+            GateKind::Mul => l * r,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayeredCircuit {
+    num_inputs: usize,
+    layers: Vec<Vec<Gate>>,
+}
+
+impl LayeredCircuit {
+    /// A circuit over `num_inputs` inputs. Returns an error if a layer
+    /// is empty or a gate reads a wire outside the layer below.
+    pub fn new(num_inputs: usize, layers: Vec<Vec<Gate>>) -> Result<Self, CircuitError> {
+        let mut width = num_inputs;
+        for (layer, gates) in layers.iter().enumerate() {
+            if gates.is_empty() {
+                return Err(CircuitError::EmptyLayer { layer });
+            }
+            for (gate, g) in gates.iter().enumerate() {
+                let wire = g.left.max(g.right);
+                if wire >= width {
+                    return Err(CircuitError::WireOutOfRange {
+                        layer,
+                        gate,
+                        wire,
+                        width,
+                    });
+                }
+            }
+            width = gates.len();
+        }
+        Ok(LayeredCircuit { num_inputs, layers })
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// The gate layers, `layers()[0]` reads the inputs.
+    pub fn layers(&self) -> &[Vec<Gate>] {
+        &self.layers
+    }
+
+    /// The number of gate layers.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The number of wires of layer `k`, the inputs are layer 0 and
+    /// gate layer `k - 1` produces layer `k`.
+    pub fn width(&self, k: usize) -> usize {
+        match k {
+            0 => self.num_inputs,
+            _ => self.layers[k - 1].len(),
+        }
+    }
+
+    /// The number of variables of the multilinear extension of layer
+    /// `k`, `ceil(log2(width))`.
+    pub fn layer_vars(&self, k: usize) -> usize {
+        self.width(k).next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// The values of every layer, the inputs first and the outputs last.
+    pub fn evaluate<T: FieldElement>(&self, inputs: &[T]) -> Result<Vec<Vec<T>>, CircuitError> {
+        if inputs.len() != self.num_inputs {
+            return Err(CircuitError::InputLength {
+                len: inputs.len(),
+                expected: self.num_inputs,
+            });
+        }
+        let mut values = Vec::with_capacity(self.depth() + 1);
+        values.push(inputs.to_vec());
+        for gates in &self.layers {
+            let below = values.last().unwrap();
+            let next = gates.iter().map(|g| g.apply(below)).collect::<Vec<_>>();
+            values.push(next);
+        }
+        Ok(values)
+    }
+
+    /// The values of the output layer.
+    pub fn outputs<T: FieldElement>(&self, inputs: &[T]) -> Result<Vec<T>, CircuitError> {
+        Ok(self.evaluate(inputs)?.pop().unwrap())
+    }
+
+    /// The multilinear extension of layer `k` from its values, padded
+    /// with zeroes to `2^layer_vars(k)` evaluations.
+    pub fn layer_mle<T: FieldElement>(&self, k: usize, values: &[T]) -> MultilinearPoly<T> {
+        assert_eq!(values.len(), self.width(k), "layer {k} width mismatch");
+        MultilinearPoly::from_fn(self.layer_vars(k), |b| {
+            values.get(b).cloned().unwrap_or_else(T::zero)
+        })
+    }
+
+    /// The `(z, x, y)` entries where the predicate of `kind` for gate
+    /// layer `k` is 1.
+    pub fn wiring(
+        &self,
+        k: usize,
+        kind: GateKind,
+    ) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.layers[k]
+            .iter()
+            .enumerate()
+            .filter(move |(_, g)| g.kind == kind)
+            .map(|(z, g)| (z, g.left, g.right))
+    }
+
+    /// The multilinear extension of the `kind` predicate of gate layer
+    /// `k` at `(z, x, y)`. `z` has `layer_vars(k + 1)` coordinates,
+    /// `x` and `y` have `layer_vars(k)`. Panics otherwise.
+    pub fn wiring_predicate<T: FieldElement>(
+        &self,
+        k: usize,
+        kind: GateKind,
+        z: &[T],
+        x: &[T],
+        y: &[T],
+    ) -> T {
+        assert_eq!(z.len(), self.layer_vars(k + 1), "gate point length");
+        assert_eq!(x.len(), self.layer_vars(k), "left wire point length");
+        assert_eq!(y.len(), self.layer_vars(k), "right wire point length");
+        let (eq_z, eq_x, eq_y) = (eq_table(z), eq_table(x), eq_table(y));
+        self.wiring(k, kind).fold(T::zero(), |acc, (g, l, r)| {
+            acc + eq_z[g].clone() * eq_x[l].clone() * eq_y[r].clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    type F = F1009FieldElement;
+
+    fn values(v: &[u64]) -> Vec<F> {
+        v.iter().map(|v| F::from(*v)).collect()
+    }
+
+    fn bits(b: usize, n: usize) -> Vec<F> {
+        (0..n).map(|i| F::from(((b >> i) & 1) as u64)).collect()
+    }
+
+    /// (a + b) (c d), a^2 + d and a b c d
+    fn circuit() -> LayeredCircuit {
+        LayeredCircuit::new(
+            4,
+            vec![
+                vec![Gate::add(0, 1), Gate::mul(2, 3), Gate::mul(0, 0)],
+                vec![Gate::mul(0, 1), Gate::add(2, 1), Gate::mul(1, 1)],
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn evaluate() {
+        let c = circuit();
+        assert_eq!((c.depth(), c.width(1), c.layer_vars(1)), (2, 3, 2));
+        let layers = c.evaluate(&values(&[2, 3, 4, 5])).unwrap();
+        assert_eq!(layers[1], values(&[5, 20, 4]));
+        assert_eq!(
+            c.outputs(&values(&[2, 3, 4, 5])).unwrap(),
+            values(&[100, 24, 400])
+        );
+        assert_eq!(
+            c.evaluate(&values(&[1, 2])),
+            Err(CircuitError::InputLength {
+                len: 2,
+                expected: 4
+            })
+        );
+        assert_eq!(
+            LayeredCircuit::new(2, vec![vec![Gate::add(0, 1)], vec![Gate::mul(0, 1)]]),
+            Err(CircuitError::WireOutOfRange {
+                layer: 1,
+                gate: 0,
+                wire: 1,
+                width: 1
+            })
+        );
+        assert_eq!(
+            LayeredCircuit::new(2, vec![vec![]]),
+            Err(CircuitError::EmptyLayer { layer: 0 })
+        );
+    }
+
+    #[test]
+    fn wiring_predicates() {
+        let c = circuit();
+        assert_eq!(
+            c.wiring(0, GateKind::Mul).collect::<Vec<_>>(),
+            vec![(1, 2, 3), (2, 0, 0)]
+        );
+        // on the hypercube the predicate is the indicator of the wiring
+        for z in 0..4 {
+            for x in 0..4 {
+                for y in 0..4 {
+                    let expected = c.layers()[0]
+                        .get(z)
+                        .is_some_and(|g| g.kind == GateKind::Add && (g.left, g.right) == (x, y));
+                    assert_eq!(
+                        c.wiring_predicate(0, GateKind::Add, &bits(z, 2), &bits(x, 2), &bits(y, 2)),
+                        F::from(u64::from(expected))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gkr_layer_identity() {
+        // V_(k+1)(z) at a random z from the sum over the wires below
+        let c = circuit();
+        let layers = c.evaluate(&values(&[7, 11, 13, 17])).unwrap();
+        for k in 0..c.depth() {
+            let below = c.layer_mle(k, &layers[k]);
+            let above = c.layer_mle(k + 1, &layers[k + 1]);
+            let z = (0..c.layer_vars(k + 1))
+                .map(|i| F::from(100 + 37 * i as u64))
+                .collect::<Vec<_>>();
+            let n = c.layer_vars(k);
+            let mut sum = F::zero();
+            for x in 0..1 << n {
+                for y in 0..1 << n {
+                    let (x, y) = (bits(x, n), bits(y, n));
+                    let (vx, vy) = (below.evaluate(&x), below.evaluate(&y));
+                    sum += c.wiring_predicate(k, GateKind::Add, &z, &x, &y) * (vx + vy)
+                        + c.wiring_predicate(k, GateKind::Mul, &z, &x, &y) * vx * vy;
+                }
+            }
+            assert_eq!(sum, above.evaluate(&z));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Factorials, binomial coefficients and Stirling numbers
+//! modulo the field prime.
+//!
+//! `FactorialTable` precomputes `i!` and `1/i!` for `i` in `0..=n`
+//! with a single field inversion. Lookups are then constant time.
+//!
+use super::BigUint;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorialTable<T: FieldElement> {
+    factorials: Vec<T>,
+    inv_factorials: Vec<T>,
+}
+
+impl<T: FieldElement> FactorialTable<T> {
+    /// Build tables for `0..=n`. Panics if `n` is at least the
+    /// characteristic, as `n!` is then zero and has no inverse.
+    pub fn new(n: usize) -> Self {
+        if BigUint::from(n) >= T::characteristic() {
+            panic!(
+                "factorial table size {n} exceeds the characteristic of {}",
+                T::name_str()
+            );
+        }
+        let mut factorials = Vec::with_capacity(n + 1);
+        factorials.push(T::one());
+        for i in 1..=n {
+            factorials.push(factorials[i - 1].clone() * T::from_usize(i));
+        }
+        let mut inv_factorials = vec![T::one(); n + 1];
+        inv_factorials[n] = T::one() / factorials[n].clone();
+        // 1/(i-1)! = i/i!
+        for i in (1..=n).rev() {
+            inv_factorials[i - 1] = inv_factorials[i].clone() * T::from_usize(i);
+        }
+        FactorialTable {
+            factorials,
+            inv_factorials,
+        }
+    }
+
+    /// The largest `n` in the table.
+    pub fn max(&self) -> usize {
+        self.factorials.len() - 1
+    }
+
+    pub fn factorial(&self, n: usize) -> T {
+        self.factorials[n].clone()
+    }
+
+    pub fn inv_factorial(&self, n: usize) -> T {
+        self.inv_factorials[n].clone()
+    }
+
+    /// The binomial coefficient `n choose k`, zero if `k > n`.
+    pub fn binomial(&self, n: usize, k: usize) -> T {
+        if k > n {
+            return T::zero();
+        }
+        self.factorials[n].clone()
+            * self.inv_factorials[k].clone()
+            * self.inv_factorials[n - k].clone()
+    }
+
+    /// Stirling number of the second kind, the number of ways to
+    /// partition `n` items into `k` non-empty sets. Only `k` needs
+    /// to be in the table.
+    pub fn stirling2(&self, n: usize, k: usize) -> T {
+        // S(n, k) = 1/k! sum_i (-1)^i C(k, i) (k - i)^n
+        let mut sum = T::zero();
+        for i in 0..=k {
+            let term = self.binomial(k, i) * T::from_usize(k - i).pow_u64(n as u64);
+            if i % 2 == 0 {
+                sum += term;
+            } else {
+                sum -= term;
+            }
+        }
+        sum * self.inv_factorial(k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F101FieldElement, 101_u128, "f101");
+
+    #[test]
+    fn factorials_and_binomials() {
+        let table = FactorialTable::<F101FieldElement>::new(100);
+        assert_eq!(table.max(), 100);
+        assert_eq!(table.factorial(5), F101FieldElement::from(120));
+        // wilson's theorem
+        assert_eq!(table.factorial(100), -F101FieldElement::one());
+        for n in 0..=100 {
+            assert_eq!(
+                table.factorial(n) * table.inv_factorial(n),
+                F101FieldElement::one()
+            );
+        }
+        assert_eq!(table.binomial(10, 3), F101FieldElement::from(120));
+        assert_eq!(table.binomial(3, 10), F101FieldElement::zero());
+        // pascal's rule
+        for n in 1..=100 {
+            for k in 1..n {
+                assert_eq!(
+                    table.binomial(n, k),
+                    table.binomial(n - 1, k - 1) + table.binomial(n - 1, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stirling_numbers() {
+        let table = FactorialTable::<F101FieldElement>::new(10);
+        // S(5, k) = 0 1 15 25 10 1
+        let expected = [0, 1, 15, 25, 10, 1];
+        for (k, s) in expected.iter().enumerate() {
+            assert_eq!(table.stirling2(5, k), F101FieldElement::from(*s));
+        }
+        assert_eq!(table.stirling2(0, 0), F101FieldElement::one());
+        assert_eq!(table.stirling2(3, 5), F101FieldElement::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn table_too_large() {
+        FactorialTable::<F101FieldElement>::new(101);
+    }
+
+    #[cfg(feature = "binary_field")]
+    #[test]
+    #[should_panic(expected = "exceeds the characteristic")]
+    fn table_too_large_binary() {
+        // 256 elements but 2! = 0
+        FactorialTable::<crate::binary_field::Binary8FieldElement>::new(2);
+    }
+}
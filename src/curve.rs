@@ -0,0 +1,30 @@
+//! Metadata describing the elliptic curve a scalar field
+//! belongs to.
+//!
+//! The scalar field `Fr` of a curve is the field implemented by the
+//! corresponding `FieldElement`. Point coordinates live in a different
+//! field, the base field `Fq`. Keeping the two moduli (and the
+//! pairing parameters) in a type avoids mixing them up.
+//!
+use num_bigint::BigUint;
+
+/// A short Weierstrass curve `y^2 = x^3 + a*x + b` over the base field
+/// `Fq` with a prime order subgroup of order `r`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveInfo {
+    pub name: &'static str,
+    /// The modulus `q` of the base field. Point coordinates are in `Fq`.
+    pub base_field_modulus: BigUint,
+    /// The modulus `r` of the scalar field. This is the order of the
+    /// prime subgroup, and the prime of the corresponding `FieldElement`.
+    pub scalar_field_modulus: BigUint,
+    /// Curve coefficient `a`, an element of `Fq`.
+    pub a: BigUint,
+    /// Curve coefficient `b`, an element of `Fq`.
+    pub b: BigUint,
+    /// Number of curve points divided by `r`.
+    pub cofactor: BigUint,
+    /// The smallest `k` such that `r` divides `q^k - 1`. Pairings
+    /// map into an extension of degree `k` over `Fq`.
+    pub embedding_degree: u32,
+}
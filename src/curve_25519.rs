@@ -12,19 +12,91 @@ use std::ops::SubAssign;
 use std::str::FromStr;
 
 use curve25519_dalek::scalar::Scalar;
+use ff::Field;
 use ff::PrimeField;
 use num_bigint::BigUint;
 
 use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq)]
 pub struct Curve25519FieldElement(Scalar);
 
+// scalars are always reduced so the bytes are canonical
+impl Hash for Curve25519FieldElement {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.as_bytes().hash(state);
+    }
+}
+
+impl Curve25519FieldElement {
+    /// Decode a canonical little endian encoding. Returns `None`
+    /// if the encoded value is not less than the group order. Use
+    /// this for untrusted input, e.g. bytes received over the network.
+    pub fn from_canonical_bytes(bytes: [u8; 32]) -> Option<Self> {
+        Option::from(Scalar::from_canonical_bytes(bytes)).map(Curve25519FieldElement)
+    }
+
+    /// Decode a little endian encoding, reducing the value
+    /// modulo the group order.
+    pub fn from_bytes_mod_order(bytes: [u8; 32]) -> Self {
+        Curve25519FieldElement(Scalar::from_bytes_mod_order(bytes))
+    }
+}
+
+impl FixedByteLen for Curve25519FieldElement {
+    const BYTE_LEN: usize = 32;
+}
+
 impl FieldElement for Curve25519FieldElement {
     fn name_str() -> &'static str {
         "curve25519"
     }
 
+    fn backend_str() -> &'static str {
+        "curve25519-dalek 4"
+    }
+
+    fn modpow_bytes(&self, exp_le: &[u8]) -> Self {
+        Curve25519FieldElement(self.0.pow_vartime(super::le_bytes_to_limbs(exp_le)))
+    }
+
+    fn is_small_field() -> bool {
+        false
+    }
+
+    fn fits_in_u64() -> bool {
+        false
+    }
+
+    fn prime() -> BigUint {
+        BigUint::from_bytes_le((-Scalar::ONE).as_bytes()) + 1_u32
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    fn two_adicity() -> u32 {
+        Scalar::S
+    }
+
+    fn generator() -> Self {
+        Curve25519FieldElement(Scalar::MULTIPLICATIVE_GENERATOR)
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        if k > Scalar::S {
+            panic!("curve25519 does not contain a subgroup of order 2^{k}");
+        }
+        let mut g = Scalar::ROOT_OF_UNITY;
+        for _ in k..Scalar::S {
+            g *= g;
+        }
+        Curve25519FieldElement(g)
+    }
+
     fn serialize(&self) -> String {
         self.clone().to_string()
     }
@@ -34,7 +106,7 @@ impl FieldElement for Curve25519FieldElement {
     }
 
     fn byte_len() -> usize {
-        32
+        Self::BYTE_LEN
     }
 
     fn to_bytes_le(&self) -> Vec<u8> {
@@ -44,16 +116,12 @@ impl FieldElement for Curve25519FieldElement {
     fn from_bytes_le(bytes: &[u8]) -> Self {
         // 32 is hard coded/typed in the curve25519_dalek library
         const BYTES_SIZE: usize = 32;
+        crate::assert_byte_len::<Self>(bytes);
         let mut new_bytes: [u8; BYTES_SIZE] = [0; BYTES_SIZE];
-        if bytes.len() > BYTES_SIZE {
-            panic!("incorrect number of bytes passed to Curve25519FieldElement: expected {BYTES_SIZE} got {}", bytes.len());
-        }
-        for x in 0..BYTES_SIZE {
-            if x < bytes.len() {
-                new_bytes[x] = bytes[x];
-            }
-        }
-        Self(Scalar::from_bytes_mod_order(new_bytes))
+        new_bytes[..bytes.len()].copy_from_slice(bytes);
+        // canonical encodings (e.g. from to_bytes_le) skip the reduction
+        Self::from_canonical_bytes(new_bytes)
+            .unwrap_or_else(|| Self::from_bytes_mod_order(new_bytes))
     }
 }
 
@@ -73,20 +141,31 @@ impl FromStr for Curve25519FieldElement {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
         // The curve25519_dalek implementation of from_str_vartime
         // does not accept leading zeroes. In the other implementations we _do_
         // accept leading zeroes so we sanitize the string here as needed
         let trimmed = s.trim_start_matches('0');
-        if trimmed.is_empty() {
+        if s.is_empty() {
+            Err(())
+        } else if trimmed.is_empty() {
             Ok(Self::zero())
         } else {
-            Ok(Curve25519FieldElement(
-                Scalar::from_str_vartime(trimmed).unwrap(),
-            ))
+            Scalar::from_str_vartime(trimmed)
+                .map(Curve25519FieldElement)
+                .ok_or(())
         }
     }
 }
 
+impl Default for Curve25519FieldElement {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
 impl From<u64> for Curve25519FieldElement {
     fn from(value: u64) -> Self {
         Curve25519FieldElement(Scalar::from(value))
@@ -97,7 +176,10 @@ impl Add for Curve25519FieldElement {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Curve25519FieldElement(self.0 + other.0)
+        let out = Curve25519FieldElement(self.0 + other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+        out
     }
 }
 
@@ -105,7 +187,10 @@ impl Sub for Curve25519FieldElement {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Curve25519FieldElement(self.0 - other.0)
+        let out = Curve25519FieldElement(self.0 - other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+        out
     }
 }
 
@@ -113,7 +198,10 @@ impl Mul for Curve25519FieldElement {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        Curve25519FieldElement(self.0 * other.0)
+        let out = Curve25519FieldElement(self.0 * other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+        out
     }
 }
 
@@ -122,7 +210,10 @@ impl Div for Curve25519FieldElement {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        Curve25519FieldElement(self.0 * other.0.invert())
+        let out = Curve25519FieldElement(self.0 * other.0.invert());
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+        out
     }
 }
 
@@ -148,6 +239,35 @@ impl Neg for Curve25519FieldElement {
     type Output = Self;
 
     fn neg(self) -> Self {
-        Curve25519FieldElement(-self.0)
+        let out = Curve25519FieldElement(-self.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_bytes() {
+        let v = -Curve25519FieldElement::one();
+        let bytes: [u8; 32] = v.to_bytes_le().try_into().unwrap();
+        assert_eq!(Curve25519FieldElement::from_canonical_bytes(bytes), Some(v));
+        // the group order itself is not a canonical encoding
+        let order: [u8; 32] = Curve25519FieldElement::prime()
+            .to_bytes_le()
+            .try_into()
+            .unwrap();
+        assert_eq!(Curve25519FieldElement::from_canonical_bytes(order), None);
+        assert_eq!(
+            Curve25519FieldElement::from_bytes_mod_order(order),
+            Curve25519FieldElement::zero()
+        );
+        assert_eq!(
+            Curve25519FieldElement::from_bytes_le(&order),
+            Curve25519FieldElement::zero()
+        );
     }
 }
@@ -5,6 +5,11 @@
 /// Expects `FieldElement` to be in scope
 ///
 /// This macro is intended for testing and educational purposes.
+///
+/// Multiplication reduces the 128 bit product with Barrett reduction
+/// using a per-type constant `BARRETT_MU = floor((2^128 - 1) / modulus)`,
+/// so no division is performed. `reduce_u128` exposes the reduction for
+/// benchmarking against other strategies.
 #[macro_export]
 macro_rules! scalar_ring {
     ( $name: ident, $modulus: literal, $name_str: expr ) => {
@@ -14,11 +19,69 @@ macro_rules! scalar_ring {
         #[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, std::hash::Hash)]
         pub struct $name(u128);
 
+        #[allow(dead_code)]
+        impl $name {
+            /// The Barrett constant `floor((2^128 - 1) / modulus)`.
+            pub const BARRETT_MU: u128 = u128::MAX / $modulus;
+
+            /// Reduce `x` modulo the modulus using the precomputed
+            /// Barrett constant. The quotient estimate is at most 2 less
+            /// than the true quotient, corrected by subtraction.
+            pub fn reduce_u128(x: u128) -> u128 {
+                // high 128 bits of the 256 bit product x * mu
+                let (x_hi, x_lo) = (x >> 64, x & u128::from(u64::MAX));
+                let (mu_hi, mu_lo) = (
+                    Self::BARRETT_MU >> 64,
+                    Self::BARRETT_MU & u128::from(u64::MAX),
+                );
+                let lo_lo = x_lo * mu_lo;
+                let hi_lo = x_hi * mu_lo;
+                let lo_hi = x_lo * mu_hi;
+                let cross =
+                    (lo_lo >> 64) + (hi_lo & u128::from(u64::MAX)) + (lo_hi & u128::from(u64::MAX));
+                let q = x_hi * mu_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+                let mut r = x - q * $modulus;
+                while r >= $modulus {
+                    r -= $modulus;
+                }
+                r
+            }
+        }
+
+        impl $crate::FixedByteLen for $name {
+            // bytes needed for the largest element, modulus - 1
+            const BYTE_LEN: usize = {
+                let bits = 128 - (($modulus as u128) - 1).leading_zeros();
+                if bits == 0 {
+                    1
+                } else {
+                    bits.div_ceil(8) as usize
+                }
+            };
+        }
+
+        const _: () = assert!(
+            ($modulus as u128) > 1 && ($modulus as u128) <= 1 << 64,
+            "scalar_ring modulus must be in (1, 2^64]"
+        );
+        const _: () = assert!(
+            <$name as $crate::FixedByteLen>::BYTE_LEN
+                == $crate::modulus_byte_len(&[
+                    $modulus as u128 as u64,
+                    ($modulus as u128 >> 64) as u64
+                ]),
+            "BYTE_LEN does not match the modulus"
+        );
+
         impl FieldElement for $name {
             fn name_str() -> &'static str {
                 $name_str
             }
 
+            fn backend_str() -> &'static str {
+                "native-u128"
+            }
+
             fn zero() -> Self {
                 $name(0)
             }
@@ -27,8 +90,16 @@ macro_rules! scalar_ring {
                 $name(1)
             }
 
+            fn is_small_field() -> bool {
+                true
+            }
+
+            fn fits_in_u64() -> bool {
+                true
+            }
+
             fn byte_len() -> usize {
-                8
+                <Self as $crate::FixedByteLen>::BYTE_LEN
             }
 
             fn serialize(&self) -> String {
@@ -36,19 +107,18 @@ macro_rules! scalar_ring {
             }
 
             fn deserialize(str: &str) -> Self {
-                $name(str.parse::<u128>().unwrap())
+                <Self as std::str::FromStr>::from_str(str).unwrap()
             }
 
             fn to_bytes_le(&self) -> Vec<u8> {
-                self.0.to_le_bytes().to_vec()
+                self.0.to_le_bytes()[..<Self as $crate::FixedByteLen>::BYTE_LEN].to_vec()
             }
 
             fn from_bytes_le(bytes: &[u8]) -> Self {
-                let mut padded_bytes = bytes.to_vec();
-                if bytes.len() < 16 {
-                    padded_bytes.resize(16, 0);
-                }
-                $name(u128::from_le_bytes(padded_bytes.try_into().unwrap()) % $modulus)
+                $crate::assert_byte_len::<Self>(bytes);
+                let mut padded = [0_u8; 16];
+                padded[..bytes.len()].copy_from_slice(bytes);
+                $name(u128::from_le_bytes(padded) % $modulus)
             }
         }
 
@@ -62,7 +132,20 @@ macro_rules! scalar_ring {
             type Err = ();
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                Ok($name(s.parse::<u128>().unwrap() % $modulus))
+                // u128::from_str and BigUint::from_str accept a leading +,
+                // canonical decimal strings don't have one
+                if s.starts_with('+') {
+                    return Err(());
+                }
+                s.parse::<u128>()
+                    .map(|v| $name(v % $modulus))
+                    .map_err(|_| ())
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name(0)
             }
         }
 
@@ -92,17 +175,17 @@ macro_rules! scalar_ring {
             type Output = Self;
 
             fn mul(self, other: Self) -> Self {
-                $name((self.0 * other.0) % $modulus)
+                $name(Self::reduce_u128(self.0 * other.0))
             }
         }
 
+        #[allow(clippy::suspicious_arithmetic_impl)]
         impl std::ops::Div for $name {
             type Output = Self;
 
             fn div(self, other: Self) -> Self {
-                let other_inv = other.to_biguint().modinv(&Self::prime());
-                if let Some(inv) = other_inv {
-                    $name((self.0 * u128::try_from(inv).unwrap()) % $modulus)
+                if let Some(inv) = other.inv_euclid() {
+                    self * inv
                 } else {
                     panic!("Division by zero");
                 }
@@ -160,4 +243,28 @@ mod tests {
             assert_eq!(x_e + x_e, F13FieldElement((x + x) % 13));
         }
     }
+
+    // the largest prime below 2^64
+    scalar_ring!(F64FieldElement, 18446744073709551557_u128, "f64");
+    scalar_ring!(Z1024RingElement, 1024_u128, "z1024");
+
+    #[test]
+    fn barrett_reduction() {
+        let mut x = 0x9e3779b97f4a7c15_u128;
+        for _ in 0..10_000 {
+            // xorshift over the full u128 range
+            x ^= x << 23;
+            x ^= x >> 17;
+            x ^= x << 5;
+            assert_eq!(F13FieldElement::reduce_u128(x), x % 13);
+            assert_eq!(F64FieldElement::reduce_u128(x), x % 18446744073709551557);
+            assert_eq!(Z1024RingElement::reduce_u128(x), x % 1024);
+        }
+        for x in [0, 1, 12, 13, u128::MAX, u128::MAX - 1] {
+            assert_eq!(F13FieldElement::reduce_u128(x), x % 13);
+            assert_eq!(F64FieldElement::reduce_u128(x), x % 18446744073709551557);
+        }
+        let neg_one = -F64FieldElement::one();
+        assert_eq!(neg_one * neg_one, F64FieldElement::one());
+    }
 }
@@ -0,0 +1,159 @@
+//! Human readable decimal formatting for large elements.
+//!
+//! `FieldElement::display_with` returns a wrapper implementing
+//! `Display` that groups digits and optionally elides the middle of
+//! long numbers, e.g. the largest bn128 element (77 digits) prints as
+//! `21_888_242_871...495_616` with `DisplayOptions::elided(11, 6)`.
+//! Unlike `lower60_string` the head and tail are both kept, so two
+//! elements that differ only in their low bits are still
+//! distinguishable. The plain `Display` impls are unaffected.
+//!
+use std::fmt::Display;
+
+use super::FieldElement;
+
+/// How `DisplayWith` formats the decimal digits of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Digits per group counted from the right, `0` disables grouping.
+    pub group_size: usize,
+    /// Inserted between groups.
+    pub separator: char,
+    /// Keep the first `head` and last `tail` digits and replace the
+    /// rest with `...`. Numbers with at most `head + tail` digits are
+    /// printed in full.
+    pub elide: Option<(usize, usize)>,
+}
+
+impl DisplayOptions {
+    /// Groups of 3 separated by `_`, like a Rust integer literal.
+    pub const GROUPED: DisplayOptions = DisplayOptions {
+        group_size: 3,
+        separator: '_',
+        elide: None,
+    };
+
+    /// Grouped, keeping `head` leading and `tail` trailing digits.
+    pub const fn elided(head: usize, tail: usize) -> Self {
+        DisplayOptions {
+            elide: Some((head, tail)),
+            ..Self::GROUPED
+        }
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self::GROUPED
+    }
+}
+
+/// An element paired with `DisplayOptions`, see
+/// `FieldElement::display_with`.
+#[derive(Debug, Clone)]
+pub struct DisplayWith<'a, T: FieldElement> {
+    element: &'a T,
+    options: DisplayOptions,
+}
+
+impl<'a, T: FieldElement> DisplayWith<'a, T> {
+    pub fn new(element: &'a T, options: DisplayOptions) -> Self {
+        DisplayWith { element, options }
+    }
+}
+
+impl<T: FieldElement> Display for DisplayWith<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            format_digits(&self.element.serialize(), &self.options)
+        )
+    }
+}
+
+/// Group and elide a string of decimal digits.
+fn format_digits(digits: &str, options: &DisplayOptions) -> String {
+    let digits = digits.as_bytes();
+    let len = digits.len();
+    // separators stay aligned to the full number when eliding
+    let (head, tail) = match options.elide {
+        Some((head, tail)) if head + tail < len => (0..head, Some(len - tail..len)),
+        _ => (0..len, None),
+    };
+    let mut out = String::with_capacity(len + len / 2);
+    for (i, segment) in std::iter::once(head).chain(tail).enumerate() {
+        if i > 0 {
+            out.push_str("...");
+        }
+        let start = segment.start;
+        for j in segment {
+            let grouped = options.group_size > 0 && (len - j).is_multiple_of(options.group_size);
+            if j > start && grouped {
+                out.push(options.separator);
+            }
+            out.push(digits[j] as char);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(Goldilocks, 18446744069414584321_u128, "goldilocks");
+
+    #[test]
+    fn grouping_and_eliding() {
+        let x = Goldilocks::from(1234567);
+        assert_eq!(
+            x.display_with(DisplayOptions::GROUPED).to_string(),
+            "1_234_567"
+        );
+        assert_eq!(
+            Goldilocks::zero()
+                .display_with(DisplayOptions::GROUPED)
+                .to_string(),
+            "0"
+        );
+        let minus_one = -Goldilocks::one();
+        assert_eq!(
+            minus_one.display_with(DisplayOptions::GROUPED).to_string(),
+            "18_446_744_069_414_584_320"
+        );
+        assert_eq!(
+            minus_one
+                .display_with(DisplayOptions::elided(5, 4))
+                .to_string(),
+            "18_446...4_320"
+        );
+        // short numbers are not elided
+        assert_eq!(
+            x.display_with(DisplayOptions::elided(4, 3)).to_string(),
+            "1_234_567"
+        );
+        let options = DisplayOptions {
+            group_size: 0,
+            separator: ',',
+            elide: Some((3, 3)),
+        };
+        assert_eq!(minus_one.display_with(options).to_string(), "184...320");
+        let options = DisplayOptions {
+            group_size: 4,
+            separator: ',',
+            elide: None,
+        };
+        assert_eq!(x.display_with(options).to_string(), "123,4567");
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn bn128_elided() {
+        let x = -crate::Bn128FieldElement::one();
+        assert_eq!(
+            x.display_with(DisplayOptions::elided(11, 6)).to_string(),
+            "21_888_242_871...495_616"
+        );
+    }
+}
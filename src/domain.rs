@@ -0,0 +1,180 @@
+//! Multiplicative evaluation domains over `FieldElement`.
+//!
+//! An `EvaluationDomain` is a coset `offset * <g>` where `g` generates
+//! the subgroup of size `2^k`. Elements are in natural order,
+//! `offset * g^i`.
+//!
+//! FRI style folding maps each point `x` to `x^2`. The image of a
+//! domain of size `n` is a domain of size `n/2` with generator `g^2`
+//! and offset `offset^2`. Because `g^(n/2) = -1` the points `x_i` and
+//! `x_(i + n/2)` are negatives of each other and fold to the same point
+//! `x_i^2`, the `i`-th element of the halved domain.
+//!
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationDomain<T: FieldElement> {
+    log_size: u32,
+    generator: T,
+    offset: T,
+}
+
+impl<T: FieldElement> EvaluationDomain<T> {
+    /// The subgroup of size `2^log_size`. Panics if the field
+    /// does not contain a subgroup of this size.
+    pub fn new(log_size: u32) -> Self {
+        Self::coset(log_size, T::one())
+    }
+
+    /// The coset `offset * H` where `H` is the subgroup of
+    /// size `2^log_size`.
+    pub fn coset(log_size: u32, offset: T) -> Self {
+        if offset == T::zero() {
+            panic!("coset offset must be non-zero");
+        }
+        EvaluationDomain {
+            log_size,
+            generator: T::two_adic_generator(log_size),
+            offset,
+        }
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn generator(&self) -> &T {
+        &self.generator
+    }
+
+    pub fn offset(&self) -> &T {
+        &self.offset
+    }
+
+    /// The `i`-th element of the domain, `offset * g^i`.
+    pub fn element(&self, i: usize) -> T {
+        let mut out = self.offset.clone();
+        let mut base = self.generator.clone();
+        let mut i = i % self.size();
+        while i > 0 {
+            if i & 1 == 1 {
+                out *= base.clone();
+            }
+            base = base.clone() * base;
+            i >>= 1;
+        }
+        out
+    }
+
+    /// All elements of the domain in natural order.
+    pub fn elements(&self) -> Vec<T> {
+        let mut out = Vec::with_capacity(self.size());
+        let mut x = self.offset.clone();
+        for _ in 0..self.size() {
+            out.push(x.clone());
+            x *= self.generator.clone();
+        }
+        out
+    }
+
+    /// The image of the domain under `x -> x^2`. Panics if
+    /// the domain has a single element.
+    pub fn halve(&self) -> Self {
+        if self.log_size == 0 {
+            panic!("cannot halve a domain of size 1");
+        }
+        EvaluationDomain {
+            log_size: self.log_size - 1,
+            generator: T::halve_domain_generator(&self.generator),
+            offset: self.offset.clone() * self.offset.clone(),
+        }
+    }
+
+    /// Fold evaluations of `f` over this domain into evaluations
+    /// of `f_even + alpha * f_odd` over the halved domain, where
+    /// `f(x) = f_even(x^2) + x * f_odd(x^2)`. Returns the halved
+    /// domain along with the folded evaluations.
+    pub fn fold(&self, evaluations: &[T], alpha: &T) -> (Self, Vec<T>) {
+        if evaluations.len() != self.size() {
+            panic!(
+                "expected {} evaluations, got {}",
+                self.size(),
+                evaluations.len()
+            );
+        }
+        let half = self.size() / 2;
+        let two_inv = T::one() / T::from(2);
+        let mut x = self.offset.clone();
+        let mut out = Vec::with_capacity(half);
+        for i in 0..half {
+            // f(x) and f(-x)
+            let (pos, neg) = (evaluations[i].clone(), evaluations[i + half].clone());
+            let even = (pos.clone() + neg.clone()) * two_inv.clone();
+            let odd = (pos - neg) * two_inv.clone() / x.clone();
+            out.push(even + alpha.clone() * odd);
+            x *= self.generator.clone();
+        }
+        (self.halve(), out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polynomial::Polynomial;
+
+    scalar_ring!(F17FieldElement, 17_u128, "f17");
+
+    #[test]
+    fn elements_and_halving() {
+        let domain = EvaluationDomain::<F17FieldElement>::coset(3, F17FieldElement::from(3));
+        let elements = domain.elements();
+        assert_eq!(elements.len(), 8);
+        for (i, x) in elements.iter().enumerate() {
+            assert_eq!(&domain.element(i), x);
+        }
+        let halved = domain.halve();
+        assert_eq!(halved.size(), 4);
+        for (i, x) in halved.elements().iter().enumerate() {
+            assert_eq!(elements[i] * elements[i], *x);
+            assert_eq!(elements[i + 4] * elements[i + 4], *x);
+        }
+    }
+
+    #[test]
+    fn fold_matches_polynomial() {
+        let f = Polynomial::new((1..=8).map(F17FieldElement::from).collect());
+        let even = Polynomial::new(
+            f.coefficients()
+                .iter()
+                .step_by(2)
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        let odd = Polynomial::new(
+            f.coefficients()
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        let alpha = F17FieldElement::from(5);
+        let folded_poly = even + odd.mul_scalar(&alpha);
+
+        let domain = EvaluationDomain::coset(3, F17FieldElement::from(3));
+        let evaluations = domain
+            .elements()
+            .iter()
+            .map(|x| f.eval(x))
+            .collect::<Vec<_>>();
+        let (halved, folded) = domain.fold(&evaluations, &alpha);
+        for (x, y) in halved.elements().iter().zip(folded) {
+            assert_eq!(folded_poly.eval(x), y);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Error types returned by fallible operations.
+//!
+use std::fmt::Display;
+
+/// Why a string or byte encoding could not be decoded
+/// into a field element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contained no digits or bytes.
+    Empty,
+    /// A character that is not a decimal digit.
+    InvalidDigit { position: usize, character: char },
+    /// The encoded integer is not less than the modulus.
+    NonCanonical,
+    /// More than `byte_len()` bytes.
+    TooLong { len: usize, max: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseError::Empty => write!(f, "empty input"),
+            ParseError::InvalidDigit {
+                position,
+                character,
+            } => write!(f, "invalid digit {character:?} at position {position}"),
+            ParseError::NonCanonical => write!(f, "value is not less than the modulus"),
+            ParseError::TooLong { len, max } => {
+                write!(f, "expected at most {max} bytes, got {len}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why a field operation has no result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    /// Zero has no multiplicative inverse.
+    DivisionByZero,
+    /// A non-zero element sharing a factor with a composite modulus.
+    NotInvertible,
+    /// The value is not less than the modulus of the target field,
+    /// see `functions::try_convert`.
+    OutOfRange,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            FieldError::DivisionByZero => write!(f, "division by zero"),
+            FieldError::NotInvertible => write!(f, "element is not invertible"),
+            FieldError::OutOfRange => write!(f, "value does not fit in the target field"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Why the shapes of matrices or tensors don't support an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The operation requires 2 dimensional matrices.
+    NotTwoDimensional { dimensions: Vec<usize> },
+    /// The columns of the left matrix don't match the rows of the right,
+    /// or the contracted axes of two tensors have different lengths.
+    InnerDimensionMismatch { lhs: Vec<usize>, rhs: Vec<usize> },
+    /// The number of values is not the product of the shape.
+    LengthMismatch { shape: Vec<usize>, len: usize },
+    /// An axis index not less than the number of axes.
+    AxisOutOfRange { axis: usize, rank: usize },
+    /// The axes are not a permutation of `0..rank`.
+    InvalidPermutation { axes: Vec<usize> },
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ShapeError::NotTwoDimensional { dimensions } => {
+                write!(f, "expected a 2 dimensional matrix: {dimensions:?}")
+            }
+            ShapeError::InnerDimensionMismatch { lhs, rhs } => {
+                write!(f, "inner dimensions are not equal: {lhs:?} {rhs:?}")
+            }
+            ShapeError::LengthMismatch { shape, len } => {
+                write!(f, "{len} values don't fill the shape {shape:?}")
+            }
+            ShapeError::AxisOutOfRange { axis, rank } => {
+                write!(f, "axis {axis} is out of range for {rank} axes")
+            }
+            ShapeError::InvalidPermutation { axes } => {
+                write!(f, "not a permutation of the axes: {axes:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Why a sumcheck proof was rejected, see `sumcheck`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumcheckError {
+    /// The proof doesn't have one message per variable.
+    RoundCount { rounds: usize, expected: usize },
+    /// A round message doesn't have `degree + 1` evaluations.
+    MessageLength {
+        round: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// `g(0) + g(1)` is not the claimed sum of the round.
+    SumMismatch { round: usize },
+    /// The product of the final evaluations is not the final claim.
+    FinalMismatch,
+}
+
+impl Display for SumcheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            SumcheckError::RoundCount { rounds, expected } => {
+                write!(f, "expected {expected} sumcheck rounds, got {rounds}")
+            }
+            SumcheckError::MessageLength {
+                round,
+                len,
+                expected,
+            } => write!(
+                f,
+                "round {round}: expected {expected} evaluations, got {len}"
+            ),
+            SumcheckError::SumMismatch { round } => {
+                write!(f, "round {round}: g(0) + g(1) does not match the claim")
+            }
+            SumcheckError::FinalMismatch => {
+                write!(f, "final evaluations do not match the claim")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SumcheckError {}
+
+/// Why a layered circuit is malformed or can't be evaluated, see
+/// `circuit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    /// A layer without gates.
+    EmptyLayer { layer: usize },
+    /// A gate reads a wire not less than the width of the layer below.
+    WireOutOfRange {
+        layer: usize,
+        gate: usize,
+        wire: usize,
+        width: usize,
+    },
+    /// The number of inputs doesn't match the circuit.
+    InputLength { len: usize, expected: usize },
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            CircuitError::EmptyLayer { layer } => write!(f, "layer {layer} has no gates"),
+            CircuitError::WireOutOfRange {
+                layer,
+                gate,
+                wire,
+                width,
+            } => write!(
+                f,
+                "layer {layer} gate {gate}: wire {wire} is out of range for width {width}"
+            ),
+            CircuitError::InputLength { len, expected } => {
+                write!(f, "expected {expected} inputs, got {len}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
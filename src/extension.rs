@@ -0,0 +1,961 @@
+//! Extension fields of a `FieldElement` of odd characteristic.
+//!
+//! `QuadraticExtension<T, P>` is `T[i] / (i^2 - w)` for a quadratic
+//! non-residue `w` of `T` supplied by `P`. Use `quadratic_extension!`
+//! to define one:
+//!
+//! ```rust
+//! use scalarff::FieldElement;
+//! scalarff::scalar_ring!(F13FieldElement, 13_u128, "f13");
+//! scalarff::quadratic_extension!(F13SquaredFieldElement, F13SquaredParams, F13FieldElement, 2, "f13_2");
+//!
+//! let i = F13SquaredFieldElement::new(F13FieldElement::zero(), F13FieldElement::one());
+//! assert_eq!(i.clone() * i, F13SquaredFieldElement::from(2));
+//! ```
+//!
+//! The extension is itself a `FieldElement`. `prime()` is the number of
+//! elements `p^2` and `characteristic()` is `p`. The integer lift of
+//! `c0 + c1 * i` is `c0 + c1 * p`, the canonical bijection used by
+//! `serialize`, `to_bytes_le` and `nth_element`. `from_biguint` also
+//! uses this bijection, while `From<u64>` is the ring map into the
+//! prime subfield: `from(13) == 0` but `from_biguint(13) == i` in
+//! `F13[i]`.
+//!
+//! `ExtensionField<T, P, D>` is `T[x] / f(x)` for any degree `D` and
+//! monic irreducible `f` given by the reduction rule
+//! `x^D = r_0 + ... + r_{D-1} x^{D-1}`. Use `extension_field!`:
+//!
+//! ```rust
+//! use scalarff::FieldElement;
+//! scalarff::scalar_ring!(F13FieldElement, 13_u128, "f13");
+//! // x^3 = 2
+//! scalarff::extension_field!(F13CubedFieldElement, F13CubedParams, F13FieldElement, 3, [2, 0, 0], "f13_3");
+//!
+//! let x = F13CubedFieldElement::x();
+//! assert_eq!(x.pow_u64(3), F13CubedFieldElement::from(2));
+//! ```
+//!
+//! Multiplication goes through `Polynomial` (schoolbook or Karatsuba
+//! per `Tuning`) followed by reduction, inversion is the extended
+//! euclidean algorithm over `T[x]`. Irreducibility of the modulus is
+//! not checked.
+//!
+//! The generic algorithms in `sqrt` assume a prime field,
+//! `sqrt_with` is not supported. `sqrt` is overridden.
+//!
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+
+use super::polynomial::Polynomial;
+use super::FieldElement;
+use super::InvStrategy;
+
+/// The non-residue defining a `QuadraticExtension`.
+pub trait QuadraticParams<T: FieldElement>:
+    Clone + Copy + PartialEq + Eq + Hash + Debug + Default + Send + Sync + 'static
+{
+    /// A quadratic non-residue `w` of `T`, `i^2 = w`.
+    fn non_residue() -> T;
+
+    /// A short string identifier for the field.
+    const NAME: &'static str;
+}
+
+/// `c0 + c1 * i` with `i^2 = P::non_residue()`.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct QuadraticExtension<T: FieldElement, P: QuadraticParams<T>> {
+    pub c0: T,
+    pub c1: T,
+    params: PhantomData<P>,
+}
+
+/// Define a quadratic extension of a field with the non-residue `w`.
+/// Expands to a params type and a `QuadraticExtension` alias.
+#[macro_export]
+macro_rules! quadratic_extension {
+    ( $name: ident, $params: ident, $base: ty, $non_residue: literal, $name_str: expr ) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+        pub struct $params;
+
+        impl $crate::extension::QuadraticParams<$base> for $params {
+            fn non_residue() -> $base {
+                <$base>::from($non_residue)
+            }
+
+            const NAME: &'static str = $name_str;
+        }
+
+        pub type $name = $crate::extension::QuadraticExtension<$base, $params>;
+    };
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> QuadraticExtension<T, P> {
+    pub fn new(c0: T, c1: T) -> Self {
+        QuadraticExtension {
+            c0,
+            c1,
+            params: PhantomData,
+        }
+    }
+
+    /// Embed a base field element.
+    pub fn from_base(c0: T) -> Self {
+        Self::new(c0, T::zero())
+    }
+
+    /// The conjugate `c0 - c1 * i`, the image under the Frobenius map.
+    pub fn conj(&self) -> Self {
+        Self::new(self.c0.clone(), -self.c1.clone())
+    }
+
+    /// The norm `x * conj(x) = c0^2 - w * c1^2`, an element of `T`.
+    pub fn norm(&self) -> T {
+        self.c0.clone() * self.c0.clone() - P::non_residue() * self.c1.clone() * self.c1.clone()
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.norm();
+        if norm == T::zero() {
+            return None;
+        }
+        let norm_inv = T::one() / norm;
+        Some(Self::new(
+            self.c0.clone() * norm_inv.clone(),
+            -self.c1.clone() * norm_inv,
+        ))
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> FieldElement for QuadraticExtension<T, P> {
+    fn name_str() -> &'static str {
+        P::NAME
+    }
+
+    fn backend_str() -> &'static str {
+        T::backend_str()
+    }
+
+    fn zero() -> Self {
+        Self::from_base(T::zero())
+    }
+
+    fn one() -> Self {
+        Self::from_base(T::one())
+    }
+
+    fn byte_len() -> usize {
+        (Self::prime() - 1_u32).bits().div_ceil(8) as usize
+    }
+
+    fn prime() -> BigUint {
+        T::prime().pow(2)
+    }
+
+    fn characteristic() -> BigUint {
+        T::characteristic()
+    }
+
+    fn is_small_field() -> bool {
+        false
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    fn inv_fermat(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn mul_pow2(&self, k: u32) -> Self {
+        Self::new(self.c0.mul_pow2(k), self.c1.mul_pow2(k))
+    }
+
+    fn div_pow2(&self, k: u32) -> Self {
+        Self::new(self.c0.div_pow2(k), self.c1.div_pow2(k))
+    }
+
+    /// `x` is a square iff its norm is a square in `T`.
+    fn legendre(&self) -> i32 {
+        if self == &Self::zero() {
+            return 0;
+        }
+        self.norm().legendre()
+    }
+
+    fn checked_sqrt(&self) -> Option<Self> {
+        if self == &Self::zero() {
+            return Some(Self::zero());
+        }
+        // one base field exponentiation of the norm
+        if self.legendre() != 1 {
+            return None;
+        }
+        let two = T::one() + T::one();
+        let root = if self.c1 == T::zero() {
+            if self.c0.legendre() == 1 {
+                Self::from_base(self.c0.sqrt())
+            } else {
+                // (y i)^2 = y^2 w
+                Self::new(T::zero(), (self.c0.clone() / P::non_residue()).sqrt())
+            }
+        } else {
+            // (x0 + x1 i)^2 = a has x0^2 = (c0 +- sqrt(norm)) / 2
+            let alpha = self.norm().sqrt();
+            let mut delta = (self.c0.clone() + alpha.clone()) / two.clone();
+            if delta.legendre() != 1 {
+                delta = (self.c0.clone() - alpha) / two.clone();
+            }
+            let x0 = delta.sqrt();
+            let x1 = self.c1.clone() / (two * x0.clone());
+            Self::new(x0, x1)
+        };
+        Some(super::sqrt::canonical_root(root))
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        let two_adicity = Self::two_adicity();
+        if k > two_adicity {
+            panic!(
+                "field {} does not contain a subgroup of order 2^{k}",
+                Self::name_str()
+            );
+        }
+        // every element of T is a square, search c0 + i
+        let mut c = Self::new(T::zero(), T::one());
+        while c.legendre() != -1 {
+            c.c0 += T::one();
+        }
+        let mut g = c.pow(&((Self::prime() - 1_u32) >> two_adicity));
+        for _ in k..two_adicity {
+            g = g.clone() * g;
+        }
+        g
+    }
+
+    fn serialize(&self) -> String {
+        self.to_biguint().to_string()
+    }
+
+    fn deserialize(str: &str) -> Self {
+        Self::from_str(str).unwrap()
+    }
+
+    fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = (self.c0.to_biguint() + self.c1.to_biguint() * T::prime()).to_bytes_le();
+        bytes.resize(Self::byte_len(), 0);
+        bytes
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Self {
+        crate::assert_byte_len::<Self>(bytes);
+        let v = BigUint::from_bytes_le(bytes) % Self::prime();
+        let (c1, c0) = v.div_rem(&T::prime());
+        Self::new(T::from_biguint(&c0), T::from_biguint(&c1))
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Display for QuadraticExtension<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> FromStr for QuadraticExtension<T, P> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
+        let v = BigUint::from_str(s).map_err(|_| ())?;
+        Ok(Self::from_biguint(&v))
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Default for QuadraticExtension<T, P> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> From<u64> for QuadraticExtension<T, P> {
+    fn from(value: u64) -> Self {
+        Self::from_base(T::from(value))
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Add for QuadraticExtension<T, P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.c0 + other.c0, self.c1 + other.c1)
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Sub for QuadraticExtension<T, P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.c0 - other.c0, self.c1 - other.c1)
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Mul for QuadraticExtension<T, P> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // Karatsuba, 3 base multiplications and one by w
+        let v0 = self.c0.clone() * other.c0.clone();
+        let v1 = self.c1.clone() * other.c1.clone();
+        let c1 = (self.c0 + self.c1) * (other.c0 + other.c1) - v0.clone() - v1.clone();
+        Self::new(v0 + P::non_residue() * v1, c1)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: FieldElement, P: QuadraticParams<T>> Div for QuadraticExtension<T, P> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        match other.inverse() {
+            Some(inv) => self * inv,
+            None => panic!("Division by zero"),
+        }
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> AddAssign for QuadraticExtension<T, P> {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> MulAssign for QuadraticExtension<T, P> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> SubAssign for QuadraticExtension<T, P> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+impl<T: FieldElement, P: QuadraticParams<T>> Neg for QuadraticExtension<T, P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+/// The reduction rule of an `ExtensionField`, `x^D = sum(r_i x^i)`.
+/// The modulus `x^D - sum(r_i x^i)` must be irreducible over `T`.
+pub trait ExtensionParams<T: FieldElement, const D: usize>:
+    Clone + Copy + PartialEq + Eq + Hash + Debug + Default + Send + Sync + 'static
+{
+    /// The coefficients `r_0..r_{D-1}` with `x^D = sum(r_i x^i)`.
+    fn reduction() -> [T; D];
+
+    /// A short string identifier for the field.
+    const NAME: &'static str;
+}
+
+/// `sum(c_i x^i)` for `i < D`, an element of `T[x] / f(x)` with
+/// `f` the irreducible modulus supplied by `P`.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct ExtensionField<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> {
+    coefficients: [T; D],
+    params: PhantomData<P>,
+}
+
+/// Define a degree `D` extension of a field with the reduction rule
+/// `x^D = r_0 + r_1 x + ... + r_{D-1} x^{D-1}`. Expands to a params
+/// type and an `ExtensionField` alias.
+#[macro_export]
+macro_rules! extension_field {
+    ( $name: ident, $params: ident, $base: ty, $degree: literal, [$( $reduction: literal ),+], $name_str: expr ) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+        pub struct $params;
+
+        impl $crate::extension::ExtensionParams<$base, $degree> for $params {
+            fn reduction() -> [$base; $degree] {
+                [$( <$base>::from($reduction) ),+]
+            }
+
+            const NAME: &'static str = $name_str;
+        }
+
+        pub type $name = $crate::extension::ExtensionField<$base, $params, $degree>;
+    };
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> ExtensionField<T, P, D> {
+    /// Build an element from coefficients ordered lowest degree first.
+    pub fn new(coefficients: [T; D]) -> Self {
+        ExtensionField {
+            coefficients,
+            params: PhantomData,
+        }
+    }
+
+    /// Embed a base field element.
+    pub fn from_base(c0: T) -> Self {
+        let mut out = Self::zero();
+        out.coefficients[0] = c0;
+        out
+    }
+
+    /// The generator `x` of the extension. Panics if `D < 2`.
+    pub fn x() -> Self {
+        let mut out = Self::zero();
+        out.coefficients[1] = T::one();
+        out
+    }
+
+    pub fn coefficients(&self) -> &[T; D] {
+        &self.coefficients
+    }
+
+    /// The modulus `x^D - sum(r_i x^i)` as a polynomial.
+    pub fn modulus() -> Polynomial<T> {
+        let mut coefficients = P::reduction().map(|r| -r).to_vec();
+        coefficients.push(T::one());
+        Polynomial::new(coefficients)
+    }
+
+    fn from_polynomial(p: &Polynomial<T>) -> Self {
+        let mut out = Self::zero();
+        for (c, v) in out.coefficients.iter_mut().zip(p.coefficients()) {
+            *c = v.clone();
+        }
+        out
+    }
+
+    fn to_polynomial(&self) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.to_vec())
+    }
+
+    /// Extended Euclid over `T[x]`, `s * self + t * f = 1`.
+    fn inverse(&self) -> Option<Self> {
+        if self == &Self::zero() {
+            return None;
+        }
+        let (mut r0, mut r1) = (Self::modulus(), self.to_polynomial());
+        let (mut s0, mut s1) = (Polynomial::zero(), Polynomial::new(vec![T::one()]));
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = s0 - q * s1.clone();
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+        }
+        // r0 is a non-zero constant when the modulus is irreducible
+        if r0.degree() != 0 {
+            return None;
+        }
+        let c_inv = T::one() / r0.leading_coefficient();
+        Some(Self::from_polynomial(&s0.mul_scalar(&c_inv)))
+    }
+
+    /// An element with legendre symbol `-1`, searched among `c + x`.
+    fn non_residue() -> Self {
+        let mut c = Self::x();
+        while c.legendre() != -1 {
+            c.coefficients[0] += T::one();
+        }
+        c
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> FieldElement
+    for ExtensionField<T, P, D>
+{
+    fn name_str() -> &'static str {
+        P::NAME
+    }
+
+    fn backend_str() -> &'static str {
+        T::backend_str()
+    }
+
+    fn zero() -> Self {
+        Self::new(std::array::from_fn(|_| T::zero()))
+    }
+
+    fn one() -> Self {
+        Self::from_base(T::one())
+    }
+
+    fn byte_len() -> usize {
+        (Self::prime() - 1_u32).bits().div_ceil(8) as usize
+    }
+
+    fn prime() -> BigUint {
+        T::prime().pow(D as u32)
+    }
+
+    fn characteristic() -> BigUint {
+        T::characteristic()
+    }
+
+    fn is_small_field() -> bool {
+        false
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Euclid
+    }
+
+    fn inv_euclid(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn inv_fermat(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn mul_pow2(&self, k: u32) -> Self {
+        Self::new(self.coefficients.clone().map(|c| c.mul_pow2(k)))
+    }
+
+    fn div_pow2(&self, k: u32) -> Self {
+        Self::new(self.coefficients.clone().map(|c| c.div_pow2(k)))
+    }
+
+    /// Tonelli–Shanks with exponents over the field order `p^D`.
+    fn checked_sqrt(&self) -> Option<Self> {
+        if self == &Self::zero() {
+            return Some(Self::zero());
+        }
+        if Self::characteristic() == BigUint::from(2_u32) {
+            return Some(self.pow(&(Self::prime() >> 1)));
+        }
+        let s = Self::two_adicity();
+        let t: BigUint = (Self::prime() - 1_u32) >> s;
+        let mut m = s;
+        let mut c = Self::non_residue().pow(&t);
+        let mut u = self.pow(&t);
+        let mut root = self.pow(&((t + 1_u32) >> 1));
+        while u != Self::one() {
+            // the least i with u^(2^i) = 1
+            let mut i = 0;
+            let mut u2 = u.clone();
+            while u2 != Self::one() {
+                u2 = u2.clone() * u2;
+                i += 1;
+                if i == m {
+                    // u has order 2^s, not a square
+                    return None;
+                }
+            }
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b = b.clone() * b;
+            }
+            m = i;
+            c = b.clone() * b.clone();
+            u *= c.clone();
+            root *= b;
+        }
+        Some(super::sqrt::canonical_root(root))
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        let two_adicity = Self::two_adicity();
+        if k > two_adicity {
+            panic!(
+                "field {} does not contain a subgroup of order 2^{k}",
+                Self::name_str()
+            );
+        }
+        if k == 0 {
+            return Self::one();
+        }
+        let mut g = Self::non_residue().pow(&((Self::prime() - 1_u32) >> two_adicity));
+        for _ in k..two_adicity {
+            g = g.clone() * g;
+        }
+        g
+    }
+
+    fn serialize(&self) -> String {
+        self.to_biguint().to_string()
+    }
+
+    fn deserialize(str: &str) -> Self {
+        Self::from_str(str).unwrap()
+    }
+
+    fn to_bytes_le(&self) -> Vec<u8> {
+        let p = T::prime();
+        let v = self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(BigUint::from(0_u32), |acc, c| acc * &p + c.to_biguint());
+        let mut bytes = v.to_bytes_le();
+        bytes.resize(Self::byte_len(), 0);
+        bytes
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Self {
+        crate::assert_byte_len::<Self>(bytes);
+        let p = T::prime();
+        let mut v = BigUint::from_bytes_le(bytes) % Self::prime();
+        Self::new(std::array::from_fn(|_| {
+            let (q, r) = v.div_rem(&p);
+            v = q;
+            T::from_biguint(&r)
+        }))
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Display
+    for ExtensionField<T, P, D>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> FromStr
+    for ExtensionField<T, P, D>
+{
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
+        let v = BigUint::from_str(s).map_err(|_| ())?;
+        Ok(Self::from_biguint(&v))
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Default
+    for ExtensionField<T, P, D>
+{
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> From<u64>
+    for ExtensionField<T, P, D>
+{
+    fn from(value: u64) -> Self {
+        Self::from_base(T::from(value))
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Add for ExtensionField<T, P, D> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients) {
+            *a += b;
+        }
+        self
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Sub for ExtensionField<T, P, D> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients) {
+            *a -= b;
+        }
+        self
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Mul for ExtensionField<T, P, D> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // schoolbook or Karatsuba depending on the global `Tuning`
+        let product = self.to_polynomial() * other.to_polynomial();
+        let mut wide = product.coefficients().to_vec();
+        // reduce from the top, x^(D+j) = sum(r_i x^(i+j))
+        let reduction = P::reduction();
+        while wide.len() > D {
+            let top = wide.pop().unwrap();
+            let shift = wide.len() - D;
+            for (i, r) in reduction.iter().enumerate() {
+                wide[shift + i] += top.clone() * r.clone();
+            }
+        }
+        Self::from_polynomial(&Polynomial::new(wide))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Div for ExtensionField<T, P, D> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        match other.inverse() {
+            Some(inv) => self * inv,
+            None => panic!("Division by zero"),
+        }
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> AddAssign
+    for ExtensionField<T, P, D>
+{
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> MulAssign
+    for ExtensionField<T, P, D>
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> SubAssign
+    for ExtensionField<T, P, D>
+{
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+impl<T: FieldElement, P: ExtensionParams<T, D>, const D: usize> Neg for ExtensionField<T, P, D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(self.coefficients.map(|c| -c))
+    }
+}
+
+#[cfg(feature = "oxfoi")]
+quadratic_extension!(
+    OxfoiQuadraticFieldElement,
+    OxfoiQuadraticParams,
+    crate::OxfoiFieldElement,
+    7,
+    "oxfoi_2"
+);
+
+// x^3 - x + 1, the Triton VM extension field
+#[cfg(feature = "oxfoi")]
+extension_field!(
+    OxfoiCubicFieldElement,
+    OxfoiCubicParams,
+    crate::OxfoiFieldElement,
+    3,
+    [18446744069414584320, 1, 0],
+    "oxfoi_3"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+    quadratic_extension!(F169FieldElement, F169Params, F13FieldElement, 2, "f13_2");
+    extension_field!(
+        F13CubedFieldElement,
+        F13CubedParams,
+        F13FieldElement,
+        3,
+        [2, 0, 0],
+        "f13_3"
+    );
+    extension_field!(
+        F13FourthFieldElement,
+        F13FourthParams,
+        F13FieldElement,
+        4,
+        [2, 0, 0, 0],
+        "f13_4"
+    );
+
+    fn all_elements() -> Vec<F169FieldElement> {
+        (0..169_u64)
+            .map(|i| F169FieldElement::nth_element(&BigUint::from(i)))
+            .collect()
+    }
+
+    #[test]
+    fn field_axioms() {
+        assert_eq!(F169FieldElement::prime(), BigUint::from(169_u32));
+        assert_eq!(F169FieldElement::characteristic(), BigUint::from(13_u32));
+        let elements = all_elements();
+        for x in &elements[1..] {
+            assert_eq!(x.clone() / x.clone(), F169FieldElement::one());
+            assert_eq!(x.clone() * x.conj(), F169FieldElement::from_base(x.norm()));
+            // Frobenius: x^p = conj(x)
+            assert_eq!(x.pow_u64(13), x.conj());
+            assert_eq!(x.inv_euclid(), x.inv_fermat());
+        }
+        assert_eq!(F169FieldElement::zero().inv_fermat(), None);
+        let (a, b, c) = (
+            elements[20].clone(),
+            elements[77].clone(),
+            elements[150].clone(),
+        );
+        assert_eq!(
+            a.clone() * (b.clone() + c.clone()),
+            a.clone() * b.clone() + a.clone() * c
+        );
+        assert_eq!((a.clone() * b.clone()) * a.clone(), a.clone() * (b * a));
+    }
+
+    #[test]
+    fn encoding() {
+        for (i, x) in all_elements().into_iter().enumerate() {
+            assert_eq!(x.element_index(), BigUint::from(i));
+            assert_eq!(F169FieldElement::deserialize(&x.serialize()), x);
+            assert_eq!(F169FieldElement::from_bytes_le(&x.to_bytes_le()), x);
+        }
+        let x = F169FieldElement::new(F13FieldElement::from(3), F13FieldElement::from(5));
+        assert_eq!(x.serialize(), (3 + 5 * 13).to_string());
+        assert_eq!(F169FieldElement::byte_len(), 1);
+        // the ring map and the bijection differ past the prime subfield
+        assert_eq!(F169FieldElement::from(13), F169FieldElement::zero());
+        assert_eq!(
+            F169FieldElement::from_biguint(&BigUint::from(13_u32)),
+            F169FieldElement::new(F13FieldElement::zero(), F13FieldElement::one())
+        );
+    }
+
+    #[test]
+    fn pow2() {
+        for x in all_elements() {
+            for k in 0..10 {
+                let two_pow = F169FieldElement::from(1 << k);
+                assert_eq!(x.mul_pow2(k), x.clone() * two_pow.clone());
+                assert_eq!(x.div_pow2(k), x.clone() / two_pow);
+            }
+        }
+    }
+
+    #[test]
+    fn square_roots() {
+        let mut squares = 0;
+        for x in all_elements() {
+            let square = x.clone() * x.clone();
+            let root = square.sqrt();
+            assert!(root == x || root == -x.clone());
+            assert_eq!(x.checked_sqrt().is_some(), x.legendre() != -1);
+            if x.legendre() == 1 {
+                squares += 1;
+            }
+        }
+        assert_eq!(squares, 84);
+        // 169 - 1 = 2^3 * 21
+        assert_eq!(F169FieldElement::two_adicity(), 3);
+        let g = F169FieldElement::two_adic_generator(3);
+        assert_eq!(g.pow_u64(4), -F169FieldElement::one());
+    }
+
+    #[test]
+    fn cubic_extension() {
+        type F = F13CubedFieldElement;
+        assert_eq!(F::prime(), BigUint::from(2197_u32));
+        assert_eq!(F::x().pow_u64(3), F::from(2));
+        let elements = (0..2197_u64)
+            .map(|i| F::nth_element(&BigUint::from(i)))
+            .collect::<Vec<_>>();
+        let mut squares = 0;
+        for (i, x) in elements.iter().enumerate() {
+            assert_eq!(x.element_index(), BigUint::from(i));
+            assert_eq!(F::from_bytes_le(&x.to_bytes_le()), x.clone());
+            let square = x.clone() * x.clone();
+            let root = square.sqrt();
+            assert!(&root == x || root == -x.clone());
+            if i == 0 {
+                continue;
+            }
+            assert_eq!(x.clone() / x.clone(), F::one());
+            assert_eq!(x.pow_u64(2196), F::one());
+            assert_eq!(x.checked_sqrt().is_some(), x.legendre() == 1);
+            let (low, high) = square.sqrt_pair().unwrap();
+            assert!(low.element_index() <= high.element_index());
+            if x.legendre() == 1 {
+                squares += 1;
+            }
+        }
+        assert_eq!(squares, 1098);
+        assert_eq!(F::zero().inv_euclid(), None);
+        // 2197 - 1 = 2^2 * 549
+        let g = F::two_adic_generator(2);
+        assert_eq!(g.pow_u64(2), -F::one());
+        for x in elements.iter().step_by(37) {
+            for k in 0..10 {
+                assert_eq!(x.mul_pow2(k), x.clone() * F::from(1 << k));
+                assert_eq!(x.div_pow2(k), x.clone() / F::from(1 << k));
+            }
+        }
+    }
+
+    #[test]
+    fn quartic_extension() {
+        type F = F13FourthFieldElement;
+        let x = F::new([1, 2, 3, 4].map(F13FieldElement::from));
+        let y = F::new([12, 0, 7, 5].map(F13FieldElement::from));
+        // the Frobenius map is a field automorphism
+        assert_eq!(
+            (x.clone() * y.clone()).pow_u64(13),
+            x.pow_u64(13) * y.pow_u64(13)
+        );
+        assert_ne!(x.pow_u64(13), x);
+        assert_eq!(x.pow_u64(28561), x);
+        assert_eq!(x.clone() * (F::one() / x.clone()), F::one());
+        assert_eq!(F::modulus().degree(), 4);
+        let root = (y.clone() * y.clone()).sqrt();
+        assert!(root == y || root == -y);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn oxfoi_quadratic() {
+        type F = OxfoiQuadraticFieldElement;
+        assert_eq!(crate::OxfoiFieldElement::from(7).legendre(), -1);
+        let x = F::new(
+            crate::OxfoiFieldElement::from(123456789),
+            crate::OxfoiFieldElement::from(987654321),
+        );
+        assert_eq!(x.clone() * (F::one() / x.clone()), F::one());
+        let root = (x.clone() * x.clone()).sqrt();
+        assert!(root == x || root == -x.clone());
+        assert_eq!(F::byte_len(), 16);
+        assert_eq!(F::two_adicity(), 33);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn oxfoi_cubic() {
+        type F = OxfoiCubicFieldElement;
+        let x = F::new([123456789, 987654321, 42].map(crate::OxfoiFieldElement::from));
+        assert_eq!(x.clone() * (F::one() / x.clone()), F::one());
+        assert_eq!(F::x().pow_u64(3), F::x() - F::one());
+        let root = (x.clone() * x.clone()).sqrt();
+        assert!(root == x || root == -x.clone());
+        assert_eq!(F::byte_len(), 24);
+        assert_eq!(F::two_adicity(), 32);
+    }
+}
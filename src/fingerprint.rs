@@ -0,0 +1,363 @@
+//! A ring for fast fingerprinting of byte strings, integers modulo
+//! the product `n = p1 * p2` of two 64 bit primes.
+//!
+//!   - `p1 = 2^64 - 59`
+//!   - `p2 = 2^64 - 83`
+//!
+//! Elements are stored as the residues `(x mod p1, x mod p2)` and
+//! arithmetic is done independently in each component, the integer
+//! `x < n` is only reconstructed (with the Chinese remainder theorem)
+//! for serialization. Both primes are `2^64 - c` for a small `c`, a
+//! 128 bit product is reduced by folding the high half times `c` into
+//! the low half.
+//!
+//! `n` is composite, an element is invertible iff both residues are
+//! non-zero. Square roots and other prime field operations are
+//! meaningless in this ring. Two distinct inputs collide in
+//! `fingerprint_bytes` with probability about `len / 2^64`.
+//!
+use num_bigint::BigUint;
+
+use super::FieldElement;
+use super::FixedByteLen;
+
+/// `p1 = 2^64 - C1`
+const C1: u64 = 59;
+/// `p2 = 2^64 - C2`
+const C2: u64 = 83;
+
+/// Reduce `x` modulo `2^64 - c`.
+const fn reduce(x: u128, c: u64) -> u64 {
+    let p = (u64::MAX - c + 1) as u128;
+    let mask = u64::MAX as u128;
+    // 2^64 = c mod p, each fold shrinks the high half
+    let x = (x >> 64) * c as u128 + (x & mask);
+    let mut x = (x >> 64) * c as u128 + (x & mask);
+    while x >= p {
+        x -= p;
+    }
+    x as u64
+}
+
+const fn mul_mod(a: u64, b: u64, c: u64) -> u64 {
+    reduce(a as u128 * b as u128, c)
+}
+
+const fn pow_mod(base: u64, mut exp: u64, c: u64) -> u64 {
+    let mut out = 1;
+    let mut base = base;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            out = mul_mod(out, base, c);
+        }
+        base = mul_mod(base, base, c);
+        exp >>= 1;
+    }
+    out
+}
+
+fn add_mod(a: u64, b: u64, c: u64) -> u64 {
+    let p = u64::MAX - c + 1;
+    let (sum, carry) = a.overflowing_add(b);
+    if carry || sum >= p {
+        sum.wrapping_sub(p)
+    } else {
+        sum
+    }
+}
+
+fn sub_mod(a: u64, b: u64, c: u64) -> u64 {
+    let p = u64::MAX - c + 1;
+    if a >= b {
+        a - b
+    } else {
+        a.wrapping_sub(b).wrapping_add(p)
+    }
+}
+
+/// An element of `Z/nZ` with `n = (2^64 - 59) * (2^64 - 83)`,
+/// stored in residue form.
+#[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, std::hash::Hash, Default)]
+pub struct FingerprintElement(u64, u64);
+
+impl FingerprintElement {
+    /// The first prime factor of the modulus.
+    pub const P1: u64 = u64::MAX - C1 + 1;
+    /// The second prime factor of the modulus.
+    pub const P2: u64 = u64::MAX - C2 + 1;
+    /// The modulus `n = p1 * p2`.
+    pub const N: u128 = Self::P1 as u128 * Self::P2 as u128;
+    /// The evaluation point used by `fingerprint_bytes`.
+    pub const BASE: u64 = 0x9e3779b97f4a7c15;
+    /// `p1^-1 mod p2`
+    const P1_INV: u64 = pow_mod(Self::P1 - Self::P2, Self::P2 - 2, C2);
+
+    /// Build an element from its residues modulo `p1` and `p2`.
+    /// Residues that aren't reduced are reduced.
+    pub fn from_residues(r1: u64, r2: u64) -> Self {
+        FingerprintElement(reduce(u128::from(r1), C1), reduce(u128::from(r2), C2))
+    }
+
+    /// The residues `(x mod p1, x mod p2)`.
+    pub fn residues(&self) -> (u64, u64) {
+        (self.0, self.1)
+    }
+
+    /// The unique integer `x < n` with the residues of this element.
+    pub fn value(&self) -> u128 {
+        // x = r1 + p1 * ((r2 - r1) * p1^-1 mod p2)
+        let r1_mod_p2 = reduce(u128::from(self.0), C2);
+        let t = mul_mod(sub_mod(self.1, r1_mod_p2, C2), Self::P1_INV, C2);
+        u128::from(self.0) + u128::from(Self::P1) * u128::from(t)
+    }
+
+    fn from_u128(v: u128) -> Self {
+        FingerprintElement(reduce(v, C1), reduce(v, C2))
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.0 == 0 || self.1 == 0 {
+            return None;
+        }
+        Some(FingerprintElement(
+            pow_mod(self.0, Self::P1 - 2, C1),
+            pow_mod(self.1, Self::P2 - 2, C2),
+        ))
+    }
+}
+
+/// Fingerprint a byte string by evaluating the polynomial with
+/// coefficients `b_i + 1` at `FingerprintElement::BASE`, i.e.
+/// `sum (b_i + 1) * BASE^(len - 1 - i)`. Coefficients are offset by
+/// one so strings that differ only by leading zero bytes don't
+/// collide. Appending a byte `b` to a string with fingerprint `h`
+/// gives `h * BASE + (b + 1)`, removing the first byte `b` of a
+/// string of length `len` subtracts `(b + 1) * BASE^(len - 1)`.
+pub fn fingerprint_bytes(bytes: &[u8]) -> FingerprintElement {
+    let base = FingerprintElement::from(FingerprintElement::BASE);
+    bytes.iter().fold(FingerprintElement::zero(), |h, b| {
+        h * base + FingerprintElement::from(u64::from(*b) + 1)
+    })
+}
+
+impl FixedByteLen for FingerprintElement {
+    const BYTE_LEN: usize = 16;
+}
+
+impl FieldElement for FingerprintElement {
+    fn name_str() -> &'static str {
+        "fingerprint"
+    }
+
+    fn backend_str() -> &'static str {
+        "native-crt"
+    }
+
+    fn zero() -> Self {
+        FingerprintElement(0, 0)
+    }
+
+    fn one() -> Self {
+        FingerprintElement(1, 1)
+    }
+
+    fn byte_len() -> usize {
+        Self::BYTE_LEN
+    }
+
+    fn prime() -> BigUint {
+        BigUint::from(Self::N)
+    }
+
+    fn inv_fermat(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn inv_euclid(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn serialize(&self) -> String {
+        self.value().to_string()
+    }
+
+    fn deserialize(str: &str) -> Self {
+        <Self as std::str::FromStr>::from_str(str).unwrap()
+    }
+
+    fn to_bytes_le(&self) -> Vec<u8> {
+        self.value().to_le_bytes().to_vec()
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Self {
+        crate::assert_byte_len::<Self>(bytes);
+        let mut padded = [0_u8; 16];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        Self::from_u128(u128::from_le_bytes(padded))
+    }
+}
+
+impl std::fmt::Display for FingerprintElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+impl std::str::FromStr for FingerprintElement {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
+        let v = BigUint::from_str(s).map_err(|_| ())?;
+        Ok(Self::from_biguint(&v))
+    }
+}
+
+impl From<u64> for FingerprintElement {
+    fn from(value: u64) -> Self {
+        Self::from_u128(u128::from(value))
+    }
+}
+
+impl std::ops::Add for FingerprintElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        FingerprintElement(add_mod(self.0, other.0, C1), add_mod(self.1, other.1, C2))
+    }
+}
+
+impl std::ops::Sub for FingerprintElement {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        FingerprintElement(sub_mod(self.0, other.0, C1), sub_mod(self.1, other.1, C2))
+    }
+}
+
+impl std::ops::Mul for FingerprintElement {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        FingerprintElement(mul_mod(self.0, other.0, C1), mul_mod(self.1, other.1, C2))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl std::ops::Div for FingerprintElement {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        match other.inverse() {
+            Some(inv) => self * inv,
+            None => panic!("Division by a non-invertible element"),
+        }
+    }
+}
+
+impl std::ops::AddAssign for FingerprintElement {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::MulAssign for FingerprintElement {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl std::ops::SubAssign for FingerprintElement {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl std::ops::Neg for FingerprintElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulus() {
+        let n = BigUint::from(FingerprintElement::P1) * FingerprintElement::P2;
+        assert_eq!(FingerprintElement::prime(), n);
+        let p1_inv = BigUint::from(FingerprintElement::P1_INV);
+        assert_eq!(
+            p1_inv * FingerprintElement::P1 % FingerprintElement::P2,
+            BigUint::from(1_u32)
+        );
+    }
+
+    #[test]
+    fn arithmetic_matches_biguint() {
+        let n = FingerprintElement::prime();
+        let values = [
+            0_u128,
+            1,
+            u128::from(FingerprintElement::P1),
+            u128::from(FingerprintElement::P2) + 7,
+            FingerprintElement::N - 1,
+            0x0123456789abcdef_fedcba9876543210,
+        ];
+        for a in values {
+            for b in values {
+                let (x, y) = (
+                    FingerprintElement::from_u128(a),
+                    FingerprintElement::from_u128(b),
+                );
+                let (a, b) = (BigUint::from(a) % &n, BigUint::from(b) % &n);
+                assert_eq!(x.to_biguint(), a);
+                assert_eq!((x + y).to_biguint(), (&a + &b) % &n);
+                assert_eq!((x - y).to_biguint(), (&a + &n - &b) % &n);
+                assert_eq!((x * y).to_biguint(), (&a * &b) % &n);
+                assert_eq!((-x).to_biguint(), (&n - &a) % &n);
+            }
+        }
+    }
+
+    #[test]
+    fn inversion() {
+        let x = FingerprintElement::from(31337);
+        assert_eq!(x / x, FingerprintElement::one());
+        assert_eq!(x.inv_euclid(), x.inv_fermat());
+        // multiples of either prime are zero divisors
+        let p1 = FingerprintElement::from(FingerprintElement::P1);
+        assert_eq!(p1.inv_euclid(), None);
+        assert_eq!(FingerprintElement::zero().inv_euclid(), None);
+    }
+
+    #[test]
+    fn residues_round_trip() {
+        let x = FingerprintElement::from_residues(5, 9);
+        let (r1, r2) = x.residues();
+        assert_eq!((r1, r2), (5, 9));
+        let v = x.value();
+        assert_eq!(v % u128::from(FingerprintElement::P1), 5);
+        assert_eq!(v % u128::from(FingerprintElement::P2), 9);
+        assert_eq!(FingerprintElement::from_bytes_le(&x.to_bytes_le()), x);
+        assert_eq!(FingerprintElement::deserialize(&x.serialize()), x);
+    }
+
+    #[test]
+    fn fingerprints() {
+        assert_eq!(fingerprint_bytes(&[]), FingerprintElement::zero());
+        assert_ne!(fingerprint_bytes(b"a"), fingerprint_bytes(b"\0a"));
+        assert_ne!(fingerprint_bytes(b"ab"), fingerprint_bytes(b"ba"));
+        // rolling: drop the first byte, append a new one
+        let base = FingerprintElement::from(FingerprintElement::BASE);
+        let h = fingerprint_bytes(b"rolling");
+        let dropped = h - FingerprintElement::from(u64::from(b'r') + 1) * base.pow_u64(6);
+        let rolled = dropped * base + FingerprintElement::from(u64::from(b'!') + 1);
+        assert_eq!(rolled, fingerprint_bytes(b"olling!"));
+    }
+}
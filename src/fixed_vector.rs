@@ -0,0 +1,198 @@
+//! Vectors with a length known at compile time.
+//!
+//! `FixedVector<T, N>` stores `[T; N]`, so adding or taking the dot
+//! product of vectors of different lengths is a type error instead
+//! of a runtime panic:
+//!
+//! ```compile_fail
+//! use scalarff::FieldElement;
+//! use scalarff::fixed_vector::FixedVector;
+//! scalarff::scalar_ring!(F13FieldElement, 13_u128, "f13");
+//!
+//! let a = FixedVector::<F13FieldElement, 3>::zero();
+//! let b = FixedVector::<F13FieldElement, 4>::zero();
+//! a.dot(&b);
+//! ```
+//!
+//! Convert to a 1 dimensional `Matrix` or a `Vec` to use dynamically
+//! sized operations, and back with `from_matrix`/`from_slice`.
+//!
+use std::ops::Add;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use super::matrix::Matrix;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedVector<T: FieldElement, const N: usize>(pub [T; N]);
+
+impl<T: FieldElement, const N: usize> FixedVector<T, N> {
+    pub fn new(values: [T; N]) -> Self {
+        FixedVector(values)
+    }
+
+    pub fn zero() -> Self {
+        FixedVector(std::array::from_fn(|_| T::zero()))
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn as_array(&self) -> &[T; N] {
+        &self.0
+    }
+
+    pub fn into_array(self) -> [T; N] {
+        self.0
+    }
+
+    /// Copy a slice of length `N`. Returns `None` if the
+    /// slice has a different length.
+    pub fn from_slice(values: &[T]) -> Option<Self> {
+        if values.len() != N {
+            return None;
+        }
+        Some(FixedVector(std::array::from_fn(|i| values[i].clone())))
+    }
+
+    /// Convert a 1 dimensional matrix of length `N`.
+    pub fn from_matrix(m: &Matrix<T>) -> Option<Self> {
+        if m.dimensions != [N] {
+            return None;
+        }
+        Self::from_slice(&m.values)
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+    }
+
+    pub fn scale(&self, v: &T) -> Self {
+        FixedVector(std::array::from_fn(|i| self.0[i].clone() * v.clone()))
+    }
+}
+
+impl<T: FieldElement, const N: usize> Default for FixedVector<T, N> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: FieldElement, const N: usize> From<[T; N]> for FixedVector<T, N> {
+    fn from(values: [T; N]) -> Self {
+        FixedVector(values)
+    }
+}
+
+impl<T: FieldElement, const N: usize> From<FixedVector<T, N>> for Vec<T> {
+    fn from(v: FixedVector<T, N>) -> Self {
+        v.0.into()
+    }
+}
+
+impl<T: FieldElement, const N: usize> From<FixedVector<T, N>> for Matrix<T> {
+    fn from(v: FixedVector<T, N>) -> Self {
+        Matrix {
+            dimensions: vec![N],
+            values: v.0.into(),
+        }
+    }
+}
+
+impl<T: FieldElement, const N: usize> Index<usize> for FixedVector<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T: FieldElement, const N: usize> IndexMut<usize> for FixedVector<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+impl<T: FieldElement, const N: usize> Add for FixedVector<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        FixedVector(std::array::from_fn(|i| {
+            self.0[i].clone() + other.0[i].clone()
+        }))
+    }
+}
+
+impl<T: FieldElement, const N: usize> Sub for FixedVector<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        FixedVector(std::array::from_fn(|i| {
+            self.0[i].clone() - other.0[i].clone()
+        }))
+    }
+}
+
+impl<T: FieldElement, const N: usize> Neg for FixedVector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        FixedVector(self.0.map(|v| -v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn vector<const N: usize>(values: [u64; N]) -> FixedVector<F13FieldElement, N> {
+        FixedVector(values.map(F13FieldElement::from))
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = vector([1, 2, 3]);
+        let b = vector([4, 5, 6]);
+        // 4 + 10 + 18 = 32 = 6 mod 13
+        assert_eq!(a.dot(&b), F13FieldElement::from(6));
+        assert_eq!(a.clone() + b.clone(), vector([5, 7, 9]));
+        assert_eq!(b.clone() - a.clone(), vector([3, 3, 3]));
+        assert_eq!(a.scale(&F13FieldElement::from(5)), vector([5, 10, 2]));
+        assert_eq!(-a.clone() + a.clone(), FixedVector::zero());
+        assert_eq!(a[2], F13FieldElement::from(3));
+        assert_eq!(a.len(), 3);
+        assert!(FixedVector::<F13FieldElement, 0>::zero().is_empty());
+    }
+
+    #[test]
+    fn conversions() {
+        let a = vector([1, 2, 3]);
+        let m = Matrix::from(a.clone());
+        assert_eq!(m.dimensions, vec![3]);
+        assert_eq!(
+            FixedVector::<F13FieldElement, 3>::from_matrix(&m),
+            Some(a.clone())
+        );
+        assert_eq!(FixedVector::<F13FieldElement, 4>::from_matrix(&m), None);
+        assert_eq!(
+            FixedVector::<F13FieldElement, 3>::from_matrix(&Matrix::identity(3)),
+            None
+        );
+        let values = Vec::from(a.clone());
+        assert_eq!(FixedVector::from_slice(&values), Some(a));
+        assert_eq!(FixedVector::<F13FieldElement, 2>::from_slice(&values), None);
+    }
+}
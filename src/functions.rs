@@ -0,0 +1,563 @@
+//! Free functions operating on slices of `FieldElement`.
+//!
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use num_integer::Integer;
+
+use super::canonical::sort_canonical;
+use super::polynomial::Polynomial;
+use super::BigUint;
+use super::FieldElement;
+use super::FieldError;
+
+/// The [Jacobi symbol](https://en.wikipedia.org/wiki/Jacobi_symbol)
+/// `(a/n)` for odd `n`, computed with quadratic reciprocity instead of
+/// an exponentiation. For prime `n` it equals the Legendre symbol.
+/// Panics if `n` is even.
+pub fn jacobi(a: &BigUint, n: &BigUint) -> i32 {
+    assert!(n.is_odd(), "jacobi symbol requires an odd modulus");
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+    while a != BigUint::from(0_u32) {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        // (2/n) = -1 iff n = 3, 5 mod 8
+        let n_mod_8 = (&n % 8_u32).to_u32_digits().first().copied().unwrap_or(0);
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+        // reciprocity flips the sign iff both are 3 mod 4
+        let a_mod_4 = (&a % 4_u32).to_u32_digits().first().copied().unwrap_or(0);
+        if a_mod_4 == 3 && n_mod_8 % 4 == 3 {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        a %= &n;
+    }
+    if n == BigUint::from(1_u32) {
+        result
+    } else {
+        0
+    }
+}
+
+/// The polynomial of degree less than `points.len()` passing
+/// through every `(x, y)`. Panics if two points share an `x`.
+/// See `Polynomial::interpolate`.
+pub fn lagrange_interpolate<T: FieldElement>(points: &[(T, T)]) -> Polynomial<T> {
+    let (xs, ys): (Vec<T>, Vec<T>) = points.iter().cloned().unzip();
+    Polynomial::interpolate(&xs, &ys)
+}
+
+/// Exact multiset equality. Sorts copies of both inputs by
+/// their canonical lift, `O(n log n)`.
+pub fn multiset_eq<T: FieldElement>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    sort_canonical(&mut a);
+    sort_canonical(&mut b);
+    a == b
+}
+
+/// Randomized multiset equality using the grand product
+/// `prod (challenge - a_i) == prod (challenge - b_i)`. `O(n)`.
+///
+/// Equal multisets always pass. Unequal multisets pass with
+/// probability at most `n/p` over a uniformly random challenge.
+pub fn multiset_eq_fingerprint<T: FieldElement>(a: &[T], b: &[T], challenge: &T) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let product = |values: &[T]| {
+        values
+            .iter()
+            .fold(T::one(), |acc, v| acc * (challenge.clone() - v.clone()))
+    };
+    product(a) == product(b)
+}
+
+/// `multiset_eq_fingerprint` with a challenge sampled from `src`.
+/// Requires the `random` feature.
+#[cfg(feature = "random")]
+pub fn multiset_eq_random<T: FieldElement, R: rand::Rng>(a: &[T], b: &[T], src: &mut R) -> bool {
+    multiset_eq_fingerprint(a, b, &T::sample_uniform(src))
+}
+
+/// The maximum number of Pollard rho steps `prime_factors` takes
+/// to split a composite factor.
+pub const MAX_RHO_ITERATIONS: usize = 1 << 20;
+
+/// Miller–Rabin with the first 12 prime bases. Deterministic for
+/// `n < 3.3 * 10^24`, a probable prime test above that.
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    const BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let one = BigUint::from(1_u32);
+    if n <= &one {
+        return false;
+    }
+    for b in BASES {
+        if n == &BigUint::from(b) {
+            return true;
+        }
+        if n % b == BigUint::from(0_u32) {
+            return false;
+        }
+    }
+    let n_minus_one = n - 1_u32;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    'bases: for b in BASES {
+        let mut x = BigUint::from(b).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = (&x * &x) % n;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// The distinct prime factors of `n > 0` in increasing order. Uses
+/// trial division by small numbers then Pollard's rho, so factors
+/// up to roughly 80 bits are found quickly. Returns `None` if a
+/// composite factor isn't split within `MAX_RHO_ITERATIONS` steps.
+pub fn prime_factors(n: &BigUint) -> Option<Vec<BigUint>> {
+    let zero = BigUint::from(0_u32);
+    if n == &zero {
+        return None;
+    }
+    let mut factors = vec![];
+    let mut rest = n.clone();
+    for q in 2_u32..1000 {
+        if &rest % q == zero {
+            factors.push(BigUint::from(q));
+            while &rest % q == zero {
+                rest /= q;
+            }
+        }
+    }
+    let mut composites = vec![rest];
+    while let Some(m) = composites.pop() {
+        if m == BigUint::from(1_u32) {
+            continue;
+        }
+        if is_probable_prime(&m) {
+            factors.push(m);
+            continue;
+        }
+        let d = pollard_rho(&m)?;
+        composites.push(&m / &d);
+        composites.push(d);
+    }
+    factors.sort();
+    factors.dedup();
+    Some(factors)
+}
+
+/// A non-trivial factor of the odd composite `n`.
+fn pollard_rho(n: &BigUint) -> Option<BigUint> {
+    let one = BigUint::from(1_u32);
+    let mut iterations = 0;
+    // x -> x^2 + c, retry with another c if the cycle closes on n
+    for c in 1_u32..16 {
+        let step = |x: &BigUint| (x * x + c) % n;
+        let mut x = BigUint::from(2_u32);
+        let mut y = x.clone();
+        let mut d = one.clone();
+        while d == one {
+            x = step(&x);
+            y = step(&step(&y));
+            let diff = if x > y { &x - &y } else { &y - &x };
+            d = diff.gcd(n);
+            iterations += 1;
+            if iterations > MAX_RHO_ITERATIONS {
+                return None;
+            }
+        }
+        if &d != n {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Map `a` to the element of `B` with the same integer
+/// representative modulo `B::prime()`. Values are reduced if the
+/// modulus of `B` is smaller, see `try_convert` to reject them.
+pub fn lift_and_reduce<A: FieldElement, B: FieldElement>(a: &A) -> B {
+    B::from_biguint(&a.to_biguint())
+}
+
+/// Map `a` to the element of `B` with the same integer representative,
+/// failing with `FieldError::OutOfRange` if it is not less than
+/// `B::prime()`. Conversions that succeed are injective and are undone
+/// by converting back.
+pub fn try_convert<A: FieldElement, B: FieldElement>(a: &A) -> Result<B, FieldError> {
+    let v = a.to_biguint();
+    if v >= B::prime() {
+        return Err(FieldError::OutOfRange);
+    }
+    Ok(B::from_biguint(&v))
+}
+
+/// Random linear combination fingerprint `sum_i elements[i] * challenge^i`.
+///
+/// Two vectors of length `n` with different contents have the same
+/// fingerprint with probability at most `(n-1)/p` over a uniformly
+/// random challenge. Vectors of different length may collide if the
+/// longer one ends in zeroes, compare lengths separately.
+pub fn fingerprint<T: FieldElement>(elements: &[T], challenge: &T) -> T {
+    let mut acc = FingerprintAccumulator::new(challenge.clone());
+    acc.absorb_slice(elements);
+    acc.value()
+}
+
+/// Streaming version of `fingerprint`. Elements are absorbed one at a
+/// time so the vector never needs to be stored. Accumulators over
+/// consecutive chunks of a vector can be computed independently
+/// (e.g. on different machines) and combined with `merge`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerprintAccumulator<T: FieldElement> {
+    challenge: T,
+    // challenge^count
+    power: T,
+    value: T,
+    count: usize,
+}
+
+impl<T: FieldElement> FingerprintAccumulator<T> {
+    pub fn new(challenge: T) -> Self {
+        FingerprintAccumulator {
+            challenge,
+            power: T::one(),
+            value: T::zero(),
+            count: 0,
+        }
+    }
+
+    pub fn absorb(&mut self, v: &T) {
+        self.value += v.clone() * self.power.clone();
+        self.power *= self.challenge.clone();
+        self.count += 1;
+    }
+
+    pub fn absorb_slice(&mut self, values: &[T]) {
+        for v in values {
+            self.absorb(v);
+        }
+    }
+
+    /// Append the elements absorbed by `next`, which must use the
+    /// same challenge and cover the chunk directly after this one.
+    pub fn merge(&mut self, next: &Self) {
+        assert!(
+            self.challenge == next.challenge,
+            "cannot merge fingerprints with different challenges"
+        );
+        self.value += next.value.clone() * self.power.clone();
+        self.power *= next.power.clone();
+        self.count += next.count;
+    }
+
+    /// The fingerprint of all elements absorbed so far.
+    pub fn value(&self) -> T {
+        self.value.clone()
+    }
+
+    /// The number of elements absorbed so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// The number of elements each thread checks per batch in
+/// `scan_residues`.
+const SCAN_BATCH: u64 = 256;
+
+/// Lazily scan `T::from(i)` for `i` in `range`, yielding every non-zero
+/// quadratic residue `x` with a square root `r` for which
+/// `predicate(&x, &r)` holds, as `(i, x, r)` in increasing order of `i`.
+///
+/// Elements are checked in batches on `parallelism` threads (at least
+/// one). Nothing beyond the current batch is computed, stop early by
+/// dropping the iterator, e.g. with `take` or `find`.
+pub fn scan_residues<T, P>(predicate: P, range: Range<u64>, parallelism: usize) -> ResidueScan<T, P>
+where
+    T: FieldElement,
+    P: Fn(&T, &T) -> bool + Sync,
+{
+    ResidueScan {
+        predicate,
+        range,
+        parallelism: parallelism.max(1),
+        found: VecDeque::new(),
+    }
+}
+
+/// The iterator returned by `scan_residues`.
+pub struct ResidueScan<T: FieldElement, P> {
+    predicate: P,
+    range: Range<u64>,
+    parallelism: usize,
+    found: VecDeque<(u64, T, T)>,
+}
+
+impl<T, P> ResidueScan<T, P>
+where
+    T: FieldElement,
+    P: Fn(&T, &T) -> bool + Sync,
+{
+    fn check_range(&self, indices: Range<u64>) -> Vec<(u64, T, T)> {
+        indices
+            .filter_map(|i| {
+                let x = T::from(i);
+                if x == T::zero() {
+                    return None;
+                }
+                let root = x.checked_sqrt()?;
+                (self.predicate)(&x, &root).then_some((i, x, root))
+            })
+            .collect()
+    }
+
+    fn next_batch(&mut self) {
+        let threads = self.parallelism as u64;
+        let len = (self.range.end - self.range.start).min(threads * SCAN_BATCH);
+        let start = self.range.start;
+        self.range.start += len;
+        if self.parallelism == 1 {
+            let found = self.check_range(start..start + len);
+            self.found.extend(found);
+            return;
+        }
+        let chunk = len.div_ceil(threads);
+        let this = &*self;
+        let batches = std::thread::scope(|s| {
+            let handles = (0..threads)
+                .map(|t| {
+                    let from = (start + t * chunk).min(start + len);
+                    let to = (from + chunk).min(start + len);
+                    s.spawn(move || this.check_range(from..to))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("residue scan thread panicked"))
+                .collect::<Vec<_>>()
+        });
+        self.found.extend(batches.into_iter().flatten());
+    }
+}
+
+impl<T, P> Iterator for ResidueScan<T, P>
+where
+    T: FieldElement,
+    P: Fn(&T, &T) -> bool + Sync,
+{
+    type Item = (u64, T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.found.is_empty() && !self.range.is_empty() {
+            self.next_batch();
+        }
+        self.found.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    fn elements(values: &[u64]) -> Vec<F1009FieldElement> {
+        values.iter().map(|v| F1009FieldElement::from(*v)).collect()
+    }
+
+    #[test]
+    fn factoring() {
+        let factors = |n: u128| {
+            prime_factors(&BigUint::from(n))
+                .unwrap()
+                .iter()
+                .map(|q| u128::try_from(q).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(factors(1), Vec::<u128>::new());
+        assert_eq!(factors(1009 * 1009 * 4), vec![2, 1009]);
+        assert_eq!(
+            factors((1 << 64) - (1 << 32)),
+            vec![2, 3, 5, 17, 257, 65537]
+        );
+        assert_eq!(
+            factors(u128::MAX),
+            vec![3, 5, 17, 257, 641, 65537, 274177, 6700417, 67280421310721]
+        );
+        assert_eq!(prime_factors(&BigUint::from(0_u32)), None);
+        for p in [2_u128, 3, 1009, (1 << 61) - 1, (1 << 127) - 1] {
+            assert!(is_probable_prime(&BigUint::from(p)), "{p}");
+        }
+        // 561 is a Carmichael number
+        for n in [0_u128, 1, 561, 1009 * 1013, ((1 << 61) - 1) * 3] {
+            assert!(!is_probable_prime(&BigUint::from(n)), "{n}");
+        }
+    }
+
+    #[test]
+    fn field_conversion() {
+        scalar_ring!(F13FieldElement, 13_u128, "f13");
+        let small = F13FieldElement::from(12);
+        let big: F1009FieldElement = lift_and_reduce(&small);
+        assert_eq!(big, F1009FieldElement::from(12));
+        assert_eq!(try_convert::<_, F13FieldElement>(&big), Ok(small));
+        let x = F1009FieldElement::from(1000);
+        assert_eq!(
+            lift_and_reduce::<_, F13FieldElement>(&x),
+            F13FieldElement::from(1000 % 13)
+        );
+        assert_eq!(
+            try_convert::<_, F13FieldElement>(&x),
+            Err(FieldError::OutOfRange)
+        );
+        // -1 is the largest value, it only fits in the larger field
+        assert!(try_convert::<_, F1009FieldElement>(&-F13FieldElement::one()).is_ok());
+        assert!(try_convert::<_, F13FieldElement>(&-F1009FieldElement::one()).is_err());
+    }
+
+    #[test]
+    fn jacobi_symbol() {
+        // matches the legendre symbol for a prime modulus
+        for a in 0..1009_u64 {
+            assert_eq!(
+                jacobi(&BigUint::from(a), &F1009FieldElement::prime()),
+                F1009FieldElement::from(a).legendre()
+            );
+        }
+        // (2/15) = (2/3)(2/5) = 1, (7/15) = (7/3)(7/5) = -1, (5/15) = 0
+        let n = BigUint::from(15_u32);
+        assert_eq!(jacobi(&BigUint::from(2_u32), &n), 1);
+        assert_eq!(jacobi(&BigUint::from(7_u32), &n), -1);
+        assert_eq!(jacobi(&BigUint::from(5_u32), &n), 0);
+    }
+
+    fn test_lagrange<T: FieldElement>() {
+        // 3x^2 - x + 7
+        let f = |x: &T| T::from(3) * x.clone() * x.clone() - x.clone() + T::from(7);
+        let points = [5_u64, 100, 3]
+            .map(|x| (T::from(x), f(&T::from(x))))
+            .to_vec();
+        let poly = lagrange_interpolate(&points);
+        assert_eq!(poly.degree(), 2);
+        assert_eq!(poly.eval(&T::from(11)), f(&T::from(11)));
+        assert_eq!(lagrange_interpolate::<T>(&[]), Polynomial::zero());
+        let duplicate = [points[0].clone(), points[0].clone()];
+        assert!(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| lagrange_interpolate(
+                &duplicate
+            )))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn lagrange_scalar_ring() {
+        test_lagrange::<F1009FieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn lagrange_bn128() {
+        test_lagrange::<crate::Bn128FieldElement>();
+    }
+
+    #[test]
+    fn multisets() {
+        let a = elements(&[1, 2, 2, 3, 1000]);
+        let permuted = elements(&[2, 1000, 1, 3, 2]);
+        let different = elements(&[1, 2, 3, 3, 1000]);
+        assert!(multiset_eq(&a, &permuted));
+        assert!(!multiset_eq(&a, &different));
+        assert!(!multiset_eq(&a, &a[1..]));
+        for challenge in [0, 5, 777] {
+            let challenge = F1009FieldElement::from(challenge);
+            assert!(multiset_eq_fingerprint(&a, &permuted, &challenge));
+            assert!(!multiset_eq_fingerprint(&a, &different, &challenge));
+        }
+    }
+
+    #[test]
+    fn fingerprints() {
+        let values = (0..100)
+            .map(|i| F1009FieldElement::from(i * i))
+            .collect::<Vec<_>>();
+        let challenge = F1009FieldElement::from(7);
+        let expected = values
+            .iter()
+            .rev()
+            .fold(F1009FieldElement::zero(), |acc, v| acc * challenge + *v);
+        assert_eq!(fingerprint(&values, &challenge), expected);
+
+        let mut modified = values.clone();
+        modified[50] += F1009FieldElement::one();
+        assert_ne!(fingerprint(&modified, &challenge), expected);
+
+        // chunks computed independently and merged
+        let mut acc = FingerprintAccumulator::new(challenge);
+        for chunk in values.chunks(17) {
+            let mut chunk_acc = FingerprintAccumulator::new(challenge);
+            chunk_acc.absorb_slice(chunk);
+            acc.merge(&chunk_acc);
+        }
+        assert_eq!(acc.value(), expected);
+        assert_eq!(acc.count(), values.len());
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn multisets_random() {
+        let mut rng = rand::thread_rng();
+        let a = elements(&[4, 8, 15, 16, 23, 42]);
+        let b = elements(&[42, 23, 16, 15, 8, 4]);
+        assert!(multiset_eq_random(&a, &b, &mut rng));
+    }
+
+    #[test]
+    fn residue_scans() {
+        let expected = (1..1009_u64)
+            .filter(|i| F1009FieldElement::from(*i).legendre() == 1)
+            .collect::<Vec<_>>();
+        for parallelism in [0, 1, 3, 8] {
+            let found = scan_residues(|_: &F1009FieldElement, _| true, 0..1009, parallelism)
+                .map(|(i, x, root)| {
+                    assert_eq!(root * root, x);
+                    i
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+        // the first residue above 500 with an even root
+        let (i, _, root) = scan_residues(
+            |_: &F1009FieldElement, r| r.to_biguint().is_even(),
+            500..u64::MAX,
+            4,
+        )
+        .next()
+        .unwrap();
+        assert!(expected.contains(&i) && i >= 500);
+        assert!(root.to_biguint().is_even());
+        assert_eq!(
+            scan_residues(|_: &F1009FieldElement, _| true, 5..5, 2).next(),
+            None
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Hashing arbitrary bytes to field elements following
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html), section 5.
+//! Requires the `hash` feature.
+//!
+//! `expand_message_xmd` uses SHA-256, `expand_message_xmd_with` takes
+//! any `Digest` with a block size (e.g. SHA-512 or SHA3). `hash_to_field`
+//! expands the message to `L = ceil((ceil(log2(p)) + k) / 8)` bytes per
+//! element with `k = 128` bits of security, then reduces each big
+//! endian chunk mod p. The extra `k` bits keep the bias of the reduction
+//! negligible, don't reduce fewer bytes.
+//!
+//! `FieldElement::hash_to_field` and `FieldElement::hash_to_field2` are
+//! shorthands for one and two elements with SHA-256, e.g. to derive
+//! Fiat–Shamir challenges. `FieldElement::from_seed` uses
+//! `hash_to_field` as a PRF to derive reproducible elements.
+//!
+//! Outputs match other RFC 9380 implementations given the same
+//! domain separation tag (DST).
+//!
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::Digest;
+use sha2::Sha256;
+
+use super::BigUint;
+use super::FieldElement;
+
+/// The security parameter `k` in bits.
+pub const SECURITY_BITS: u64 = 128;
+
+/// The DST used by `FieldElement::from_seed`.
+pub const FROM_SEED_DST: &[u8] = b"SCALARFF-V01-FROM-SEED";
+
+/// `expand_message_xmd` with SHA-256. Returns `None` if `len_in_bytes`
+/// is more than `255 * 32` or the DST is longer than 255 bytes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Option<Vec<u8>> {
+    expand_message_xmd_with::<Sha256>(msg, dst, len_in_bytes)
+}
+
+/// `expand_message_xmd` with the hash `H`. Returns `None` if
+/// `len_in_bytes` is more than 255 hash outputs or the DST is longer
+/// than 255 bytes.
+pub fn expand_message_xmd_with<H: Digest + BlockSizeUser>(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Option<Vec<u8>> {
+    let b_in_bytes = <H as Digest>::output_size();
+    let s_in_bytes = H::block_size();
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    if ell > 255 || len_in_bytes > 65535 || dst.len() > 255 {
+        return None;
+    }
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b_0 = H::new()
+        .chain_update(vec![0_u8; s_in_bytes])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0_u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut b_i = H::new()
+        .chain_update(&b_0)
+        .chain_update([1_u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xored = b_0
+            .iter()
+            .zip(b_i.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        b_i = H::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    Some(uniform_bytes)
+}
+
+/// The number of uniform bytes `L` reduced into each element.
+pub fn element_len<T: FieldElement>() -> usize {
+    (T::prime().bits() + SECURITY_BITS).div_ceil(8) as usize
+}
+
+/// Hash `msg` to `count` field elements. Returns `None` if the
+/// DST is longer than 255 bytes or `count * L` exceeds the output
+/// limit of `expand_message_xmd`.
+pub fn hash_to_field<T: FieldElement>(msg: &[u8], dst: &[u8], count: usize) -> Option<Vec<T>> {
+    hash_to_field_with::<T, Sha256>(msg, dst, count)
+}
+
+/// `hash_to_field` with the hash `H` in `expand_message_xmd`.
+pub fn hash_to_field_with<T: FieldElement, H: Digest + BlockSizeUser>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Option<Vec<T>> {
+    let len = element_len::<T>();
+    let uniform_bytes = expand_message_xmd_with::<H>(msg, dst, count.checked_mul(len)?)?;
+    Some(
+        uniform_bytes
+            .chunks_exact(len)
+            .map(|chunk| T::from_biguint(&BigUint::from_bytes_be(chunk)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn rfc_9380_vectors() {
+        // appendix K.1
+        assert_eq!(
+            hex(&expand_message_xmd(b"", DST, 0x20).unwrap()),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex(&expand_message_xmd(b"abc", DST, 0x20).unwrap()),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        // the output length is hashed into b_0, so it isn't a prefix
+        let long = expand_message_xmd(b"abc", DST, 0x80).unwrap();
+        assert_eq!(long.len(), 0x80);
+        assert_ne!(long[..0x20], expand_message_xmd(b"abc", DST, 0x20).unwrap());
+    }
+
+    #[test]
+    fn rfc_9380_sha512_vectors() {
+        // appendix K.3
+        const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        assert_eq!(
+            hex(&expand_message_xmd_with::<sha2::Sha512>(b"", DST, 0x20).unwrap()),
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"
+        );
+        assert_eq!(
+            hex(&expand_message_xmd_with::<sha2::Sha512>(b"abc", DST, 0x20).unwrap()),
+            "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"
+        );
+        assert!(expand_message_xmd_with::<sha2::Sha512>(b"", DST, 255 * 64).is_some());
+        assert!(expand_message_xmd_with::<sha2::Sha512>(b"", DST, 255 * 64 + 1).is_none());
+    }
+
+    #[test]
+    fn limits() {
+        assert!(expand_message_xmd(b"", DST, 255 * 32).is_some());
+        assert!(expand_message_xmd(b"", DST, 255 * 32 + 1).is_none());
+        assert!(expand_message_xmd(b"", &[0; 256], 32).is_none());
+        assert!(hash_to_field::<F1009FieldElement>(b"", DST, usize::MAX).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "hash_to_field")]
+    fn long_dst() {
+        F1009FieldElement::hash_to_field(b"", &[0; 256]);
+    }
+
+    fn test_hash_to_field<T: FieldElement>(expected_len: usize) {
+        assert_eq!(element_len::<T>(), expected_len);
+        let a = hash_to_field::<T>(b"abc", DST, 2).unwrap();
+        assert_eq!(a.len(), 2);
+        assert_ne!(a[0], a[1]);
+        assert_eq!(hash_to_field::<T>(b"abc", DST, 2).unwrap(), a);
+        assert_ne!(hash_to_field::<T>(b"abc", b"other dst", 2).unwrap(), a);
+        assert_ne!(hash_to_field::<T>(b"abd", DST, 2).unwrap(), a);
+        assert_eq!(
+            T::hash_to_field(b"abc", DST),
+            hash_to_field::<T>(b"abc", DST, 1).unwrap()[0]
+        );
+        assert_eq!(T::hash_to_field2(b"abc", DST), [a[0].clone(), a[1].clone()]);
+        assert_ne!(
+            hash_to_field_with::<T, sha2::Sha512>(b"abc", DST, 2).unwrap(),
+            a
+        );
+    }
+
+    #[test]
+    fn from_seed() {
+        let values = (0..3)
+            .map(|i| F1009FieldElement::from_seed(b"seed", i))
+            .collect::<Vec<_>>();
+        // computed with an independent expand_message_xmd implementation
+        assert_eq!(values, [844, 438, 183].map(F1009FieldElement::from));
+        assert_ne!(F1009FieldElement::from_seed(b"seed2", 0), values[0]);
+        // the seed length is encoded, shifting bytes between the seed
+        // and the index changes the output
+        assert_ne!(
+            F1009FieldElement::from_seed(b"seed\x01", 0),
+            F1009FieldElement::from_seed(b"seed", 1)
+        );
+    }
+
+    #[test]
+    fn hash_to_scalar_ring() {
+        // 10 bits + 128 bits
+        test_hash_to_field::<F1009FieldElement>(18);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn hash_to_bn128() {
+        test_hash_to_field::<crate::Bn128FieldElement>(48);
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn hash_to_curve25519() {
+        test_hash_to_field::<crate::Curve25519FieldElement>(48);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn hash_to_foi() {
+        test_hash_to_field::<crate::OxfoiFieldElement>(24);
+    }
+}
@@ -0,0 +1,259 @@
+//! Inverses and square roots in rings with a prime power modulus
+//! `p^k`, e.g. `scalar_ring!` instances with moduli `2^32` or `3^20`.
+//!
+//! The generic `FieldElement` algorithms assume a prime modulus, in
+//! `Z/p^kZ` Fermat inversion gives wrong answers and `sqrt` may loop or
+//! panic. The functions here solve the problem modulo `p` and lift the
+//! solution to `p^k` with Hensel's lemma (Newton iteration), doubling
+//! the number of correct `p`-adic digits each step.
+//!
+//! `FieldElement::p_adic_valuation` splits off the non-unit part.
+//! `p` must be prime, the functions panic if the modulus is not a
+//! power of `p`.
+//!
+use num_bigint::BigInt;
+use num_bigint::BigUint;
+use num_integer::Integer;
+
+use super::FieldElement;
+
+/// The `k` with `T::prime() == p^k`, `None` if the modulus is not a
+/// power of `p`.
+pub fn prime_power_exponent<T: FieldElement>(p: u64) -> Option<u32> {
+    if p < 2 {
+        return None;
+    }
+    let mut m = T::prime();
+    let mut k = 0;
+    while m > BigUint::from(1_u32) {
+        let (q, r) = m.div_rem(&BigUint::from(p));
+        if r != BigUint::from(0_u32) {
+            return None;
+        }
+        m = q;
+        k += 1;
+    }
+    Some(k)
+}
+
+fn expect_exponent<T: FieldElement>(p: u64) -> u32 {
+    prime_power_exponent::<T>(p)
+        .unwrap_or_else(|| panic!("modulus of {} is not a power of {p}", T::name_str()))
+}
+
+/// The inverse of `x` modulo `p^k`, `None` if `p` divides `x`. Starts
+/// from the inverse modulo `p` and applies `y = y * (2 - x * y)`.
+pub fn hensel_inverse<T: FieldElement>(x: &T, p: u64) -> Option<T> {
+    let k = expect_exponent::<T>(p);
+    let p_big = BigUint::from(p);
+    let x_mod_p = x.to_biguint() % &p_big;
+    if x_mod_p == BigUint::from(0_u32) {
+        return None;
+    }
+    // x^(p-2) mod p, p is prime
+    let y0 = x_mod_p.modpow(&(&p_big - 2_u32), &p_big);
+    let mut y = T::from_biguint(&y0);
+    let two = T::from(2);
+    // y is correct modulo p^precision
+    let mut precision = 1;
+    while precision < k {
+        y = y.clone() * (two.clone() - x.clone() * y);
+        precision *= 2;
+    }
+    Some(y)
+}
+
+/// A square root of `x` modulo `p^k`, `None` if `x` is not a square.
+/// Non-units `x = p^v * u` have a root iff `v` is even and `u` is a
+/// square modulo `p^(k-v)`.
+pub fn hensel_sqrt<T: FieldElement>(x: &T, p: u64) -> Option<T> {
+    let k = expect_exponent::<T>(p);
+    let p_big = BigUint::from(p);
+    if x == &T::zero() {
+        return Some(T::zero());
+    }
+    let (v, u) = x.p_adic_valuation(p);
+    let u = u.to_biguint();
+    if v % 2 == 1 {
+        return None;
+    }
+    // s^2 = u mod p^(k-v), then (p^(v/2) s)^2 = x mod p^k
+    let m = p_big.pow(k - v);
+    let s = if p == 2 {
+        sqrt_unit_mod_power_of_two(&u, k - v)?
+    } else {
+        let r0 = sqrt_mod_prime(&(&u % &p_big), &p_big)?;
+        lift_sqrt(&u, r0, &m)
+    };
+    Some(T::from_biguint(&(s * p_big.pow(v / 2))))
+}
+
+/// Newton iteration `r = r - (r^2 - u) / (2r)` modulo `m`, for odd `p`.
+fn lift_sqrt(u: &BigUint, mut r: BigUint, m: &BigUint) -> BigUint {
+    let m_int = BigInt::from(m.clone());
+    let u = u % m;
+    while (&r * &r) % m != u {
+        let two_r = BigInt::from(&r * 2_u32);
+        // 2r is a unit, r is non-zero mod p
+        let inv = two_r.extended_gcd(&m_int).x.mod_floor(&m_int);
+        let delta = ((BigInt::from(&r * &r) - BigInt::from(u.clone())) * inv).mod_floor(&m_int);
+        r = (BigInt::from(r) - delta)
+            .mod_floor(&m_int)
+            .to_biguint()
+            .unwrap();
+    }
+    r
+}
+
+/// A square root of the odd `u` modulo `2^j`. Fixes one bit at a time,
+/// a root modulo `2^i` stays a root when bit `i - 1` is flipped.
+fn sqrt_unit_mod_power_of_two(u: &BigUint, j: u32) -> Option<BigUint> {
+    let one = BigUint::from(1_u32);
+    let residue = |bits: u32| u % (&one << bits);
+    match j {
+        0 | 1 => return Some(one),
+        2 if residue(2) != one => return None,
+        2 => return Some(one),
+        _ if residue(3) != one => return None,
+        _ => {}
+    }
+    let mut s = one.clone();
+    for i in 3..j {
+        // s^2 = u mod 2^i
+        let modulus = &one << (i + 1);
+        if (&s * &s) % &modulus != u % &modulus {
+            s += &one << (i - 1);
+        }
+    }
+    Some(s % (&one << j))
+}
+
+/// Tonelli–Shanks modulo an odd prime.
+fn sqrt_mod_prime(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let zero = BigUint::from(0_u32);
+    let one = BigUint::from(1_u32);
+    if a == &zero {
+        return Some(zero);
+    }
+    let p_minus_one = p - 1_u32;
+    if a.modpow(&(&p_minus_one >> 1), p) != one {
+        return None;
+    }
+    let s = p_minus_one.trailing_zeros().unwrap_or(0);
+    let q = &p_minus_one >> s;
+    let mut z = BigUint::from(2_u32);
+    while z.modpow(&(&p_minus_one >> 1), p) != p_minus_one {
+        z += 1_u32;
+    }
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1_u32) >> 1), p);
+    while t != one {
+        // the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while t2 != one {
+            t2 = (&t2 * &t2) % p;
+            i += 1;
+        }
+        let b = c.modpow(&(one.clone() << (m - i - 1)), p);
+        m = i;
+        c = (&b * &b) % p;
+        t = (t * &c) % p;
+        r = (r * b) % p;
+    }
+    Some(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(Z3Pow20RingElement, 3486784401_u128, "z3_20");
+    scalar_ring!(Z2Pow32RingElement, 4294967296_u128, "z2_32");
+    scalar_ring!(Z5Pow3RingElement, 125_u128, "z5_3");
+
+    #[test]
+    fn exponents() {
+        assert_eq!(prime_power_exponent::<Z3Pow20RingElement>(3), Some(20));
+        assert_eq!(prime_power_exponent::<Z2Pow32RingElement>(2), Some(32));
+        assert_eq!(prime_power_exponent::<Z2Pow32RingElement>(3), None);
+    }
+
+    #[test]
+    fn inverses() {
+        for v in [1_u64, 2, 7, 1234567, 3486784400] {
+            let x = Z3Pow20RingElement::from(v);
+            let inv = hensel_inverse(&x, 3).unwrap();
+            assert_eq!(x * inv, Z3Pow20RingElement::one());
+            assert_eq!(Some(inv), x.inv_euclid());
+        }
+        assert_eq!(hensel_inverse(&Z3Pow20RingElement::from(81), 3), None);
+        let x = Z2Pow32RingElement::from(0xdeadbeef);
+        assert_eq!(
+            x * hensel_inverse(&x, 2).unwrap(),
+            Z2Pow32RingElement::one()
+        );
+        assert_eq!(hensel_inverse(&Z2Pow32RingElement::from(6), 2), None);
+    }
+
+    #[test]
+    fn valuations() {
+        let x = Z3Pow20RingElement::from(2 * 81);
+        assert_eq!(x.p_adic_valuation(3), (4, Z3Pow20RingElement::from(2)));
+        assert_eq!(
+            Z3Pow20RingElement::zero().p_adic_valuation(3),
+            (20, Z3Pow20RingElement::zero())
+        );
+        for v in 1..1000_u64 {
+            let x = Z2Pow32RingElement::from(v);
+            let (k, u) = x.p_adic_valuation(2);
+            assert_eq!(k, v.trailing_zeros());
+            assert!(hensel_inverse(&u, 2).is_some());
+            assert_eq!(Z2Pow32RingElement::from(1 << k) * u, x);
+        }
+        // 2 does not divide the modulus 125
+        assert_eq!(
+            Z5Pow3RingElement::zero().p_adic_valuation(2),
+            (0, Z5Pow3RingElement::zero())
+        );
+    }
+
+    fn test_sqrt<T: FieldElement>(p: u64, values: &[u64]) {
+        for v in values {
+            let x = T::from(*v);
+            let square = x.clone() * x.clone();
+            let root = hensel_sqrt(&square, p).unwrap();
+            assert_eq!(root.clone() * root, square);
+        }
+    }
+
+    #[test]
+    fn square_roots() {
+        test_sqrt::<Z3Pow20RingElement>(3, &[1, 2, 5, 9, 27, 1234567, 3486784400]);
+        test_sqrt::<Z2Pow32RingElement>(2, &[1, 3, 4, 6, 0xdeadbeef, 1 << 15, 1 << 16]);
+        // exhaustive over a small ring, compare against brute force
+        for v in 0..125_u64 {
+            let x = Z5Pow3RingElement::from(v);
+            let is_square = (0..125_u64).any(|r| {
+                let r = Z5Pow3RingElement::from(r);
+                r * r == x
+            });
+            let root = hensel_sqrt(&x, 5);
+            assert_eq!(root.is_some(), is_square, "{v}");
+            if let Some(root) = root {
+                assert_eq!(root * root, x);
+            }
+        }
+        // 3 is not a square mod 8
+        assert_eq!(hensel_sqrt(&Z2Pow32RingElement::from(3), 2), None);
+        assert_eq!(hensel_sqrt(&Z3Pow20RingElement::from(3), 3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a power of 5")]
+    fn wrong_prime() {
+        hensel_inverse(&Z3Pow20RingElement::one(), 5);
+    }
+}
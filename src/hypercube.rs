@@ -0,0 +1,212 @@
+//! The multilinear equality polynomial on the boolean hypercube.
+//!
+//! `eq(x, y) = prod_i (x_i y_i + (1 - x_i)(1 - y_i))` is 1 when
+//! `x = y` are points of `{0, 1}^n` and 0 at other points of the
+//! hypercube. The table of `eq(b, r)` over every `b` is used by
+//! sumcheck provers to evaluate multilinear extensions,
+//! `f(r) = sum_b f(b) eq(b, r)`.
+//!
+//! Entry `b` of a table corresponds to the point with coordinate `i`
+//! equal to bit `i` of `b`, so `point[0]` is the least significant bit.
+//!
+//! `eq_table` builds the full table with one multiplication per entry.
+//! `eq_chunks` streams it in consecutive chunks so the `2^n` entries
+//! never need to be stored at once.
+//!
+use super::parallel::for_each_chunk_mut;
+use super::tuning::tuning;
+use super::FieldElement;
+
+// values per parallel task when extending a table
+const CHUNK_SIZE: usize = 1 << 10;
+
+/// `prod_i (x_i y_i + (1 - x_i)(1 - y_i))`. Panics if the points
+/// have different lengths.
+pub fn eq<T: FieldElement>(x: &[T], y: &[T]) -> T {
+    if x.len() != y.len() {
+        panic!("points have different lengths: {} {}", x.len(), y.len());
+    }
+    x.iter().zip(y).fold(T::one(), |acc, (x, y)| {
+        let xy = x.clone() * y.clone();
+        // x y + (1 - x)(1 - y) = 1 - x - y + 2 x y
+        acc * (T::one() - x.clone() - y.clone() + xy.clone() + xy)
+    })
+}
+
+/// The `2^n` evaluations `eq(b, point)` for `b` in `{0, 1}^n`.
+pub fn eq_table<T: FieldElement>(point: &[T]) -> Vec<T> {
+    eq_table_scaled(point, T::one())
+}
+
+/// `eq_table` with every entry multiplied by `scale`, at no extra cost.
+pub fn eq_table_scaled<T: FieldElement>(point: &[T], scale: T) -> Vec<T> {
+    let mut table = Vec::with_capacity(1 << point.len());
+    table.push(scale);
+    for r in point {
+        extend_eq_table(&mut table, r);
+    }
+    table
+}
+
+/// Add a variable to a table of `eq(b, point)`, giving the table for
+/// `point` followed by `r`. The new coordinate is the most significant
+/// bit. Entry `b` splits into `t[b] (1 - r)` and `t[b] r`, so this is
+/// one multiplication per entry of the input.
+pub fn extend_eq_table<T: FieldElement>(table: &mut Vec<T>, r: &T) {
+    let half = table.len();
+    table.extend_from_within(..);
+    let cutoff = tuning().parallel_cutoff;
+    let (lo, hi) = table.split_at_mut(half);
+    for_each_chunk_mut(hi, CHUNK_SIZE, cutoff, |_, chunk| {
+        for v in chunk.iter_mut() {
+            *v *= r.clone();
+        }
+    });
+    let hi = &*hi;
+    for_each_chunk_mut(lo, CHUNK_SIZE, cutoff, |i, chunk| {
+        let start = i * CHUNK_SIZE;
+        for (v, h) in chunk.iter_mut().zip(&hi[start..]) {
+            *v -= h.clone();
+        }
+    });
+}
+
+/// Stream the table of `eq(b, point)` in order as chunks of
+/// `2^chunk_bits` entries (one chunk if `chunk_bits >= point.len()`).
+/// Only one chunk and a table for the low `chunk_bits` coordinates
+/// are kept in memory. Each chunk costs one multiplication per entry
+/// plus `O(n)` to update the factor of the high coordinates.
+pub fn eq_chunks<T: FieldElement>(point: &[T], chunk_bits: usize) -> EqChunks<T> {
+    let split = chunk_bits.min(point.len());
+    let (low, high) = point.split_at(split);
+    EqChunks {
+        low_table: eq_table(low),
+        high: high.to_vec(),
+        index: 0,
+    }
+}
+
+/// Iterator returned by `eq_chunks`.
+#[derive(Debug, Clone)]
+pub struct EqChunks<T: FieldElement> {
+    low_table: Vec<T>,
+    high: Vec<T>,
+    // the next value of the high coordinates as an integer
+    index: u64,
+}
+
+impl<T: FieldElement> Iterator for EqChunks<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.index >> self.high.len() != 0 {
+            return None;
+        }
+        let factor = self.high.iter().enumerate().fold(T::one(), |acc, (i, r)| {
+            match (self.index >> i) & 1 {
+                1 => acc * r.clone(),
+                _ => acc * (T::one() - r.clone()),
+            }
+        });
+        self.index += 1;
+        Some(
+            self.low_table
+                .iter()
+                .map(|v| v.clone() * factor.clone())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    fn point(values: &[u64]) -> Vec<F1009FieldElement> {
+        values.iter().map(|v| F1009FieldElement::from(*v)).collect()
+    }
+
+    fn bits(b: usize, n: usize) -> Vec<F1009FieldElement> {
+        (0..n)
+            .map(|i| F1009FieldElement::from(((b >> i) & 1) as u64))
+            .collect()
+    }
+
+    #[test]
+    fn equality_on_the_hypercube() {
+        for x in 0..8 {
+            for y in 0..8 {
+                let expected = if x == y { 1 } else { 0 };
+                assert_eq!(
+                    eq(&bits(x, 3), &bits(y, 3)),
+                    F1009FieldElement::from(expected)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn table() {
+        let r = point(&[3, 500, 77, 1008, 12]);
+        let table = eq_table(&r);
+        assert_eq!(table.len(), 32);
+        for (b, v) in table.iter().enumerate() {
+            assert_eq!(*v, eq(&bits(b, 5), &r));
+        }
+        // eq(., r) sums to one over the hypercube
+        let sum = table
+            .iter()
+            .fold(F1009FieldElement::zero(), |acc, v| acc + *v);
+        assert_eq!(sum, F1009FieldElement::one());
+        assert_eq!(
+            eq_table::<F1009FieldElement>(&[]),
+            vec![F1009FieldElement::one()]
+        );
+        let scale = F1009FieldElement::from(9);
+        let scaled = eq_table_scaled(&r, scale);
+        assert!(scaled.iter().zip(&table).all(|(s, t)| *s == *t * scale));
+        // multilinear extension of f(b) = b evaluated at r
+        let f_r = table
+            .iter()
+            .enumerate()
+            .fold(F1009FieldElement::zero(), |acc, (b, v)| {
+                acc + F1009FieldElement::from(b as u64) * *v
+            });
+        let expected = r
+            .iter()
+            .enumerate()
+            .fold(F1009FieldElement::zero(), |acc, (i, r)| {
+                acc + F1009FieldElement::from(1 << i) * *r
+            });
+        assert_eq!(f_r, expected);
+    }
+
+    #[test]
+    fn large_table() {
+        // large enough to be split into parallel chunks
+        let r = (0..14)
+            .map(|i| F1009FieldElement::from(i * 37 + 5))
+            .collect::<Vec<_>>();
+        let table = eq_table(&r);
+        for b in [0, 1, 1000, 4097, (1 << 14) - 1] {
+            assert_eq!(table[b], eq(&bits(b, 14), &r));
+        }
+    }
+
+    #[test]
+    fn streamed() {
+        let r = point(&[3, 500, 77, 1008, 12, 6]);
+        let table = eq_table(&r);
+        for chunk_bits in [0, 2, 6, 10] {
+            let chunks = eq_chunks(&r, chunk_bits).collect::<Vec<_>>();
+            assert_eq!(chunks.len(), 1 << (6 - chunk_bits.min(6)));
+            assert_eq!(chunks.concat(), table);
+        }
+        let mut incremental = eq_table(&r[..4]);
+        extend_eq_table(&mut incremental, &r[4]);
+        extend_eq_table(&mut incremental, &r[5]);
+        assert_eq!(incremental, table);
+    }
+}
@@ -0,0 +1,209 @@
+//! Conversions to the representations used by other ecosystems.
+//!
+//! circom and snarkjs store field elements in JSON (inputs, public
+//! signals, proofs) as arrays of decimal strings in standard (not
+//! Montgomery) form. circom input files additionally accept bare
+//! numbers and negative values, which are interpreted mod p.
+//!
+//! gnark specific conversions live on `Bn128FieldElement`, see
+//! `Bn128FieldElement::to_gnark_hex` and `to_montgomery_limbs`.
+//!
+//! With the `interop` feature the concrete field elements implement
+//! arkworks' `CanonicalSerialize` and `CanonicalDeserialize` so they can
+//! be used directly in arkworks data structures. `Bn128FieldElement`
+//! uses the same encoding as `ark_bn254::Fr`, other fields use their
+//! `byte_len` little endian canonical bytes.
+//!
+use super::FieldElement;
+
+/// Encode elements as a JSON array of decimal strings,
+/// e.g. `["1","21888242871839275222246405745257275088548364400416034343698204186575808495616"]`.
+pub fn to_snarkjs_json<T: FieldElement>(values: &[T]) -> String {
+    let entries = values
+        .iter()
+        .map(|v| format!("\"{}\"", v.serialize()))
+        .collect::<Vec<_>>();
+    format!("[{}]", entries.join(","))
+}
+
+/// Parse a flat JSON array of field elements. Entries may be decimal
+/// strings or bare integers, optionally negative. Returns `None`
+/// if the input is not a flat array of integers.
+pub fn from_snarkjs_json<T: FieldElement>(json: &str) -> Option<Vec<T>> {
+    let inner = json.trim().strip_prefix('[')?.strip_suffix(']')?.trim();
+    if inner.is_empty() {
+        return Some(vec![]);
+    }
+    inner
+        .split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            let entry = match entry.strip_prefix('"') {
+                Some(quoted) => quoted.strip_suffix('"')?,
+                None => entry,
+            };
+            parse_signed(entry)
+        })
+        .collect()
+}
+
+/// Parse a decimal integer with an optional leading `-`.
+fn parse_signed<T: FieldElement>(s: &str) -> Option<T> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let v = T::from_str(digits).ok()?;
+    if negative {
+        Some(-v)
+    } else {
+        Some(v)
+    }
+}
+
+/// Implement the arkworks serialization traits using the
+/// fixed width canonical little endian bytes of the element.
+#[cfg(feature = "interop")]
+#[allow(unused_macros)]
+macro_rules! canonical_bytes_serialize {
+    ( $name: ty ) => {
+        impl ark_serialize::CanonicalSerialize for $name {
+            fn serialize_with_mode<W: ark_serialize::Write>(
+                &self,
+                mut writer: W,
+                _compress: ark_serialize::Compress,
+            ) -> Result<(), ark_serialize::SerializationError> {
+                let mut bytes = self.to_bytes_le();
+                bytes.resize(<$name>::byte_len(), 0);
+                writer.write_all(&bytes)?;
+                Ok(())
+            }
+
+            fn serialized_size(&self, _compress: ark_serialize::Compress) -> usize {
+                <$name>::byte_len()
+            }
+        }
+
+        impl ark_serialize::Valid for $name {
+            fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+                Ok(())
+            }
+        }
+
+        impl ark_serialize::CanonicalDeserialize for $name {
+            fn deserialize_with_mode<R: ark_serialize::Read>(
+                mut reader: R,
+                _compress: ark_serialize::Compress,
+                validate: ark_serialize::Validate,
+            ) -> Result<Self, ark_serialize::SerializationError> {
+                let mut bytes = vec![0_u8; <$name>::byte_len()];
+                reader.read_exact(&mut bytes)?;
+                let v = <$name>::from_bytes_le(&bytes);
+                // reject encodings of values that aren't reduced
+                if validate == ark_serialize::Validate::Yes {
+                    let mut canonical = v.to_bytes_le();
+                    canonical.resize(<$name>::byte_len(), 0);
+                    if canonical != bytes {
+                        return Err(ark_serialize::SerializationError::InvalidData);
+                    }
+                }
+                Ok(v)
+            }
+        }
+    };
+}
+
+#[cfg(all(feature = "interop", feature = "curve25519"))]
+canonical_bytes_serialize!(crate::Curve25519FieldElement);
+#[cfg(all(feature = "interop", feature = "oxfoi"))]
+canonical_bytes_serialize!(crate::OxfoiFieldElement);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    #[test]
+    fn snarkjs_json() {
+        let values = [0, 1, 12].map(F13FieldElement::from);
+        let json = to_snarkjs_json(&values);
+        assert_eq!(json, r#"["0","1","12"]"#);
+        assert_eq!(from_snarkjs_json(&json), Some(values.to_vec()));
+        assert_eq!(
+            from_snarkjs_json(" [ \"1\", 27 , -1 ] "),
+            Some([1, 1, 12].map(F13FieldElement::from).to_vec())
+        );
+        assert_eq!(from_snarkjs_json::<F13FieldElement>("[]"), Some(vec![]));
+        for invalid in ["", "1", "[\"1\",]", "[\"x\"]", "[\"1]", "[[1]]"] {
+            assert_eq!(from_snarkjs_json::<F13FieldElement>(invalid), None);
+        }
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn snarkjs_json_bn128() {
+        use crate::Bn128FieldElement;
+        let p_minus_one =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        let values =
+            from_snarkjs_json::<Bn128FieldElement>(&format!("[\"{p_minus_one}\", -1]")).unwrap();
+        assert_eq!(values[0], -Bn128FieldElement::one());
+        assert_eq!(values[1], -Bn128FieldElement::one());
+        assert_eq!(
+            to_snarkjs_json(&values[..1]),
+            format!("[\"{p_minus_one}\"]")
+        );
+    }
+
+    #[cfg(all(feature = "interop", feature = "alt_bn128"))]
+    #[test]
+    fn ark_serialize_bn128() {
+        use crate::Bn128FieldElement;
+        use ark_serialize::CanonicalDeserialize;
+        use ark_serialize::CanonicalSerialize;
+        let v = -Bn128FieldElement::from(5);
+        let mut bytes = vec![];
+        v.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes, v.to_bytes_le());
+        assert_eq!(
+            Bn128FieldElement::deserialize_compressed(bytes.as_slice()).unwrap(),
+            v
+        );
+    }
+
+    #[cfg(all(feature = "interop", feature = "curve25519"))]
+    #[test]
+    fn ark_serialize_curve25519() {
+        use crate::Curve25519FieldElement;
+        use ark_serialize::CanonicalDeserialize;
+        use ark_serialize::CanonicalSerialize;
+        let v = -Curve25519FieldElement::from(5);
+        let mut bytes = vec![];
+        v.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(v.serialized_size(ark_serialize::Compress::Yes), 32);
+        assert_eq!(
+            Curve25519FieldElement::deserialize_compressed(bytes.as_slice()).unwrap(),
+            v
+        );
+        // the group order is not a canonical encoding
+        let order = Curve25519FieldElement::prime().to_bytes_le();
+        assert!(Curve25519FieldElement::deserialize_compressed(order.as_slice()).is_err());
+        assert!(Curve25519FieldElement::deserialize_compressed_unchecked(order.as_slice()).is_ok());
+    }
+
+    #[cfg(all(feature = "interop", feature = "oxfoi"))]
+    #[test]
+    fn ark_serialize_foi() {
+        use crate::OxfoiFieldElement;
+        use ark_serialize::CanonicalDeserialize;
+        use ark_serialize::CanonicalSerialize;
+        let values = vec![OxfoiFieldElement::one(), -OxfoiFieldElement::one()];
+        let mut bytes = vec![];
+        values.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            Vec::<OxfoiFieldElement>::deserialize_compressed(bytes.as_slice()).unwrap(),
+            values
+        );
+    }
+}
@@ -0,0 +1,532 @@
+//! Binary encodings of many elements at once.
+//!
+//! Elements are written as canonical little endian integers padded
+//! to a fixed stride of `FieldElement::byte_len` bytes. A slice of
+//! `n` elements occupies exactly `n * byte_len` bytes and element `i`
+//! starts at byte `i * byte_len`.
+//!
+//! `ElementReader` and `ElementWriter` stream the same layout through
+//! any `Read`/`Write` so files larger than memory can be processed one
+//! element at a time. With the `mmap` feature `MmapElements` provides
+//! random access to a file without reading it.
+//!
+//! `migrate_bytes_le` and `migrate_records` convert data written with
+//! trimmed or padded encodings to the fixed width layout.
+//!
+//! `read_wtns` and `write_wtns` handle the snarkjs binary witness
+//! format used by circom.
+//!
+use std::io::Read;
+use std::io::Write;
+use std::marker::PhantomData;
+
+use super::BigUint;
+use super::FieldElement;
+use super::ParseError;
+
+/// Encode a slice of elements into a single buffer with a
+/// fixed stride of `T::byte_len()` bytes per element.
+pub fn serialize_slice<T: FieldElement>(values: &[T]) -> Vec<u8> {
+    let stride = T::byte_len();
+    let mut out = vec![0_u8; values.len() * stride];
+    for (v, chunk) in values.iter().zip(out.chunks_exact_mut(stride)) {
+        write_element(v, chunk);
+    }
+    out
+}
+
+/// Decode `count` elements from a buffer produced by `serialize_slice`.
+/// Panics if the buffer is not exactly `count * T::byte_len()` bytes.
+pub fn deserialize_slice<T: FieldElement>(bytes: &[u8], count: usize) -> Vec<T> {
+    let stride = T::byte_len();
+    if bytes.len() != count * stride {
+        panic!(
+            "expected {} bytes for {count} {} elements, got {}",
+            count * stride,
+            T::name_str(),
+            bytes.len()
+        );
+    }
+    bytes.chunks_exact(stride).map(T::from_bytes_le).collect()
+}
+
+/// Decode one fixed width record from untrusted input, a
+/// non-canonical value is an `InvalidData` error instead of a panic.
+fn decode_element<T: FieldElement>(bytes: &[u8]) -> std::io::Result<T> {
+    T::try_from_bytes_le(bytes).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} element: {e}", T::name_str()),
+        )
+    })
+}
+
+/// Lazily decode elements from a reader. Each item is one
+/// element, or the io error encountered while reading it. A
+/// trailing partial element is an `UnexpectedEof` error, a
+/// non-canonical element is an `InvalidData` error.
+pub struct ElementReader<T: FieldElement, R: Read> {
+    inner: R,
+    buf: Vec<u8>,
+    _field: PhantomData<T>,
+}
+
+impl<T: FieldElement, R: Read> ElementReader<T, R> {
+    pub fn new(inner: R) -> Self {
+        ElementReader {
+            inner,
+            buf: vec![0; T::byte_len()],
+            _field: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<T: FieldElement, R: Read> Iterator for ElementReader<T, R> {
+    type Item = std::io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut filled = 0;
+        while filled < self.buf.len() {
+            match self.inner.read(&mut self.buf[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => {
+                    return Some(Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        format!("partial {} element", T::name_str()),
+                    )))
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(decode_element(&self.buf))
+    }
+}
+
+/// Encode elements to a writer one at a time. Wrap the
+/// writer in a `BufWriter` when writing to a file.
+pub struct ElementWriter<T: FieldElement, W: Write> {
+    inner: W,
+    buf: Vec<u8>,
+    _field: PhantomData<T>,
+}
+
+impl<T: FieldElement, W: Write> ElementWriter<T, W> {
+    pub fn new(inner: W) -> Self {
+        ElementWriter {
+            inner,
+            buf: vec![0; T::byte_len()],
+            _field: PhantomData,
+        }
+    }
+
+    pub fn write(&mut self, v: &T) -> std::io::Result<()> {
+        write_element(v, &mut self.buf);
+        self.inner.write_all(&self.buf)
+    }
+
+    pub fn write_slice(&mut self, values: &[T]) -> std::io::Result<()> {
+        for v in values {
+            self.write(v)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Random access to a file of encoded elements through a
+/// memory map. Requires the `mmap` feature.
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+pub struct MmapElements<T: FieldElement> {
+    map: memmap2::Mmap,
+    _field: PhantomData<T>,
+}
+
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+impl<T: FieldElement> MmapElements<T> {
+    /// Map a file for reading. Returns an `InvalidData` error if the
+    /// file length is not a multiple of `T::byte_len()`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any
+    /// other process, while it is mapped. See `memmap2::Mmap::map`.
+    pub unsafe fn open(file: &std::fs::File) -> std::io::Result<Self> {
+        // safety: forwarded to the caller
+        let map = unsafe { memmap2::Mmap::map(file)? };
+        if map.len() % T::byte_len() != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("file is not a whole number of {} elements", T::name_str()),
+            ));
+        }
+        Ok(MmapElements {
+            map,
+            _field: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len() / T::byte_len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Decode the `i`-th element, or `None` if out of bounds. A
+    /// non-canonical element is an `InvalidData` error.
+    pub fn get(&self, i: usize) -> Option<std::io::Result<T>> {
+        let stride = T::byte_len();
+        self.map
+            .get(i * stride..(i + 1) * stride)
+            .map(decode_element)
+    }
+
+    /// Decode every element in order, see `get`.
+    pub fn iter(&self) -> impl Iterator<Item = std::io::Result<T>> + '_ {
+        self.map.chunks_exact(T::byte_len()).map(decode_element)
+    }
+}
+
+/// Re-encode an element stored with a variable length encoding at
+/// the fixed width of `to_bytes_le`, e.g. bn128 elements written with
+/// the high zero byte trimmed (31 bytes). The input may be shorter
+/// than `T::byte_len()` or zero padded beyond it.
+pub fn migrate_bytes_le<T: FieldElement>(bytes: &[u8]) -> Result<Vec<u8>, ParseError> {
+    Ok(T::try_from_bytes_le(trim_zeros(bytes))?.to_bytes_le())
+}
+
+/// Drop high zero bytes, keeping one byte of a zero value. Padding
+/// past `byte_len()` is rejected by `try_from_bytes_le`.
+fn trim_zeros(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(1, |i| i + 1);
+    &bytes[..len.min(bytes.len())]
+}
+
+/// Migrate variable length records into the `serialize_slice` layout.
+pub fn migrate_records<'a, T: FieldElement>(
+    records: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Vec<u8>, ParseError> {
+    let mut out = vec![];
+    for record in records {
+        out.extend(migrate_bytes_le::<T>(record)?);
+    }
+    Ok(out)
+}
+
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+const WTNS_HEADER_SECTION: u32 = 1;
+const WTNS_WITNESS_SECTION: u32 = 2;
+
+/// Read a snarkjs `.wtns` witness file. Returns an `InvalidData`
+/// error if the file is malformed or the prime in the header is
+/// not the modulus of `T`.
+pub fn read_wtns<T: FieldElement, R: Read>(mut reader: R) -> std::io::Result<Vec<T>> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+    let mut magic = [0_u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != WTNS_MAGIC {
+        return Err(invalid("not a wtns file"));
+    }
+    let version = read_u32(&mut reader)?;
+    if version > WTNS_VERSION {
+        return Err(invalid("unsupported wtns version"));
+    }
+    let section_count = read_u32(&mut reader)?;
+    let mut header = None;
+    let mut witness = None;
+    for _ in 0..section_count {
+        let section_type = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        // the size is untrusted, read what is there instead of
+        // allocating it up front
+        let mut content = vec![];
+        reader.by_ref().take(size).read_to_end(&mut content)?;
+        if content.len() as u64 != size {
+            return Err(invalid("truncated wtns section"));
+        }
+        match section_type {
+            WTNS_HEADER_SECTION => header = Some(content),
+            WTNS_WITNESS_SECTION => witness = Some(content),
+            _ => {}
+        }
+    }
+    let header = header.ok_or_else(|| invalid("missing wtns header"))?;
+    let witness = witness.ok_or_else(|| invalid("missing wtns witness section"))?;
+    if header.len() < 4 {
+        return Err(invalid("truncated wtns header"));
+    }
+    let n8 = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    if header.len() != 4 + n8 + 4 {
+        return Err(invalid("malformed wtns header"));
+    }
+    if BigUint::from_bytes_le(&header[4..4 + n8]) != T::prime() {
+        return Err(invalid("wtns prime does not match the field"));
+    }
+    let count = u32::from_le_bytes(header[4 + n8..].try_into().unwrap()) as usize;
+    if n8 == 0 || witness.len() != count * n8 {
+        return Err(invalid("wtns witness section has the wrong length"));
+    }
+    witness
+        .chunks_exact(n8)
+        .map(|bytes| {
+            T::try_from_bytes_le(trim_zeros(bytes))
+                .map_err(|e| invalid(&format!("wtns witness value: {e}")))
+        })
+        .collect()
+}
+
+/// Write a snarkjs `.wtns` witness file (version 2).
+pub fn write_wtns<T: FieldElement, W: Write>(mut writer: W, values: &[T]) -> std::io::Result<()> {
+    // snarkjs pads elements to a whole number of 64 bit words
+    let n8 = T::prime().bits().div_ceil(64) as usize * 8;
+    let count = u32::try_from(values.len()).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "too many witness values")
+    })?;
+    writer.write_all(WTNS_MAGIC)?;
+    writer.write_all(&WTNS_VERSION.to_le_bytes())?;
+    writer.write_all(&2_u32.to_le_bytes())?;
+
+    let mut prime = T::prime().to_bytes_le();
+    prime.resize(n8, 0);
+    writer.write_all(&WTNS_HEADER_SECTION.to_le_bytes())?;
+    writer.write_all(&((4 + n8 + 4) as u64).to_le_bytes())?;
+    writer.write_all(&(n8 as u32).to_le_bytes())?;
+    writer.write_all(&prime)?;
+    writer.write_all(&count.to_le_bytes())?;
+
+    writer.write_all(&WTNS_WITNESS_SECTION.to_le_bytes())?;
+    writer.write_all(&((values.len() * n8) as u64).to_le_bytes())?;
+    let mut buf = vec![0_u8; n8];
+    for v in values {
+        buf.fill(0);
+        let bytes = v.to_bytes_le();
+        let len = bytes.len().min(n8);
+        buf[..len].copy_from_slice(&bytes[..len]);
+        writer.write_all(&buf)?;
+    }
+    Ok(())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut bytes = [0_u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Write the canonical encoding of `v` into `out`, which
+/// must be `T::byte_len()` bytes long.
+fn write_element<T: FieldElement>(v: &T, out: &mut [u8]) {
+    // to_bytes_le is exactly byte_len bytes
+    out.copy_from_slice(&v.to_bytes_le());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+    scalar_ring!(Z65537RingElement, 65537_u128, "z65537");
+
+    fn test_round_trip<T: FieldElement>() {
+        let values = (0..100)
+            .map(|i| -T::from(i * 7919))
+            .chain([T::zero(), T::one()])
+            .collect::<Vec<_>>();
+        let bytes = serialize_slice(&values);
+        assert_eq!(bytes.len(), values.len() * T::byte_len());
+        assert_eq!(deserialize_slice::<T>(&bytes, values.len()), values);
+        assert!(serialize_slice::<T>(&[]).is_empty());
+    }
+
+    #[test]
+    fn round_trip_scalar_ring() {
+        test_round_trip::<F13FieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn round_trip_bn128() {
+        test_round_trip::<crate::Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn round_trip_curve25519() {
+        test_round_trip::<crate::Curve25519FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn round_trip_foi() {
+        test_round_trip::<crate::OxfoiFieldElement>();
+    }
+
+    #[test]
+    fn migrate_variable_length() {
+        let values = [1_u64, 300, 65536].map(Z65537RingElement::from);
+        // trimmed and zero padded encodings of the same values
+        let records: [&[u8]; 3] = [&[1], &[44, 1, 0, 0], &[0, 0, 1]];
+        let migrated = migrate_records::<Z65537RingElement>(records).unwrap();
+        assert_eq!(migrated, serialize_slice(&values));
+        assert_eq!(
+            migrate_bytes_le::<Z65537RingElement>(&[]),
+            Err(ParseError::Empty)
+        );
+        assert_eq!(
+            migrate_bytes_le::<Z65537RingElement>(&[1, 0, 1]),
+            Err(ParseError::NonCanonical)
+        );
+        assert_eq!(
+            migrate_bytes_le::<Z65537RingElement>(&[1, 0, 0, 1]),
+            Err(ParseError::TooLong { len: 4, max: 3 })
+        );
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn migrate_bn128() {
+        let x = crate::Bn128FieldElement::from(12345);
+        let bytes = x.to_bytes_le();
+        assert_eq!(
+            migrate_bytes_le::<crate::Bn128FieldElement>(&bytes[..31]).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn stream_round_trip() {
+        let values = (0..50).map(F13FieldElement::from).collect::<Vec<_>>();
+        let mut writer = ElementWriter::new(vec![]);
+        writer.write_slice(&values).unwrap();
+        let bytes = writer.into_inner();
+        assert_eq!(bytes, serialize_slice(&values));
+        let read = ElementReader::<F13FieldElement, _>::new(bytes.as_slice())
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read, values);
+        // trailing partial element
+        let values = (0..2).map(Z65537RingElement::from).collect::<Vec<_>>();
+        let bytes = serialize_slice(&values);
+        assert_eq!(bytes.len(), 6);
+        let mut reader = ElementReader::<Z65537RingElement, _>::new(&bytes[..4]);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        // 13 is not a canonical f13 element
+        let mut reader = ElementReader::<F13FieldElement, _>::new(&[12_u8, 13, 1][..]);
+        assert_eq!(reader.next().unwrap().unwrap(), F13FieldElement::from(12));
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert!(reader.next().unwrap().is_ok());
+    }
+
+    #[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+    #[test]
+    fn mmap_elements() {
+        let values = (0..50).map(F13FieldElement::from).collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(format!("scalarff-mmap-{}", std::process::id()));
+        let mut bytes = serialize_slice(&values);
+        // a non-canonical record past the values
+        bytes.push(200);
+        std::fs::write(&path, bytes).unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+        // safety: the file is private to this test
+        let elements = unsafe { MmapElements::<F13FieldElement>::open(&file) }.unwrap();
+        assert_eq!(elements.len(), values.len() + 1);
+        assert_eq!(elements.get(7).unwrap().unwrap(), values[7]);
+        assert_eq!(
+            elements.get(50).unwrap().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert!(elements.get(51).is_none());
+        let read = elements
+            .iter()
+            .take(50)
+            .collect::<std::io::Result<Vec<_>>>();
+        assert_eq!(read.unwrap(), values);
+        assert!(elements
+            .iter()
+            .collect::<std::io::Result<Vec<_>>>()
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn wtns_round_trip() {
+        let values = (0..20).map(F13FieldElement::from).collect::<Vec<_>>();
+        let mut bytes = vec![];
+        write_wtns(&mut bytes, &values).unwrap();
+        // magic, version, sections, header (12 + 16), witness (12 + 20 * 8)
+        assert_eq!(bytes.len(), 12 + 28 + 12 + 160);
+        assert_eq!(
+            read_wtns::<F13FieldElement, _>(bytes.as_slice()).unwrap(),
+            values
+        );
+        // header declares a different prime
+        scalar_ring!(F17FieldElement, 17_u128, "f17");
+        assert!(read_wtns::<F17FieldElement, _>(bytes.as_slice()).is_err());
+        assert!(read_wtns::<F13FieldElement, _>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_wtns::<F13FieldElement, _>(&b"wtnx"[..]).is_err());
+        // a section claiming 2^62 bytes is rejected without allocating it
+        let mut huge = b"wtns".to_vec();
+        huge.extend(2_u32.to_le_bytes());
+        huge.extend(1_u32.to_le_bytes());
+        huge.extend(WTNS_HEADER_SECTION.to_le_bytes());
+        huge.extend((1_u64 << 62).to_le_bytes());
+        huge.extend([0_u8; 16]);
+        assert_eq!(
+            read_wtns::<F13FieldElement, _>(huge.as_slice())
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn wtns_bn128() {
+        use crate::Bn128FieldElement;
+        let values = vec![
+            Bn128FieldElement::one(),
+            Bn128FieldElement::from(33),
+            -Bn128FieldElement::one(),
+        ];
+        let mut bytes = vec![];
+        write_wtns(&mut bytes, &values).unwrap();
+        // n8 is 32 for bn128
+        assert_eq!(&bytes[24..28], &32_u32.to_le_bytes());
+        assert_eq!(bytes.len(), 12 + 12 + 40 + 12 + 3 * 32);
+        assert_eq!(
+            read_wtns::<Bn128FieldElement, _>(bytes.as_slice()).unwrap(),
+            values
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_length() {
+        deserialize_slice::<F13FieldElement>(&[0; 12], 2);
+    }
+}
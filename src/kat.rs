@@ -0,0 +1,657 @@
+//! Known answer tests (KATs) for the built in fields.
+//!
+//! Each vector pins the result of one operation on specific values:
+//! addition, multiplication, inversion, the canonical (smaller) square
+//! root and the `to_bytes_le` encoding. Negative vectors check that
+//! zero has no inverse, non-residues have no root and non-canonical
+//! bytes and strings are rejected. Values were computed with Python
+//! integers independently of any backend, the `alt_bn128` encodings
+//! agree with arkworks (see `fixtures/format.txt`).
+//!
+//! The vectors are keyed by `FieldElement::name_str`. Downstream crates
+//! that swap a backend can re-run them with `check::<T>()`.
+//!
+use std::fmt::Display;
+
+use super::FieldElement;
+
+/// One known answer. Elements are decimal strings, encodings are hex
+/// of `to_bytes_le`. `None` marks a negative vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kat {
+    /// `a + b = c`.
+    Add(&'static str, &'static str, &'static str),
+    /// `a * b = c`.
+    Mul(&'static str, &'static str, &'static str),
+    /// `inv(a)`, `None` if `inv` must fail.
+    Inv(&'static str, Option<&'static str>),
+    /// `sqrt(a)`, `None` if `a` is a non-residue.
+    Sqrt(&'static str, Option<&'static str>),
+    /// `a` encodes to the hex bytes and decodes back to `a`.
+    Bytes(&'static str, &'static str),
+    /// `try_from_bytes_le` rejects the hex bytes.
+    RejectBytes(&'static str),
+    /// `try_deserialize` rejects the string.
+    RejectString(&'static str),
+}
+
+/// A vector that didn't produce the expected answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KatFailure {
+    pub field: &'static str,
+    pub index: usize,
+    pub kat: Kat,
+    /// The result that was produced instead, debug formatted.
+    pub actual: String,
+}
+
+impl Display for KatFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} vector {}: {:?} got {}",
+            self.field, self.index, self.kat, self.actual
+        )
+    }
+}
+
+impl std::error::Error for KatFailure {}
+
+/// The vectors for the field named `name`, see `FieldElement::name_str`.
+pub fn vectors(name: &str) -> Option<&'static [Kat]> {
+    match name {
+        "alt_bn128" => Some(ALT_BN128),
+        "curve25519" => Some(CURVE25519),
+        "oxfoi" => Some(OXFOI),
+        "babybear" => Some(BABYBEAR),
+        "mersenne31" => Some(MERSENNE31),
+        "mersenne61" => Some(MERSENNE61),
+        "mersenne127" => Some(MERSENNE127),
+        "pallas" => Some(PALLAS),
+        "vesta" => Some(VESTA),
+        "stark252" => Some(STARK252),
+        _ => None,
+    }
+}
+
+/// Run the vectors for `T`. Returns the number of vectors checked,
+/// `0` if there are none for `T::name_str()`.
+pub fn check<T: FieldElement>() -> Result<usize, KatFailure> {
+    let kats = vectors(T::name_str()).unwrap_or(&[]);
+    check_vectors::<T>(kats)?;
+    Ok(kats.len())
+}
+
+/// Run arbitrary vectors against `T`.
+pub fn check_vectors<T: FieldElement>(kats: &[Kat]) -> Result<(), KatFailure> {
+    for (index, kat) in kats.iter().enumerate() {
+        let fail = |actual: String| KatFailure {
+            field: T::name_str(),
+            index,
+            kat: *kat,
+            actual,
+        };
+        let e = |s: &str| T::deserialize(s);
+        match *kat {
+            Kat::Add(a, b, c) => {
+                let out = e(a) + e(b);
+                if out != e(c) {
+                    return Err(fail(out.serialize()));
+                }
+            }
+            Kat::Mul(a, b, c) => {
+                let out = e(a) * e(b);
+                if out != e(c) {
+                    return Err(fail(out.serialize()));
+                }
+            }
+            Kat::Inv(a, expected) => {
+                let out = e(a).inv().ok();
+                if out != expected.map(e) {
+                    return Err(fail(format!("{:?}", out.map(|v| v.serialize()))));
+                }
+            }
+            Kat::Sqrt(a, None) => {
+                let legendre = e(a).legendre();
+                if legendre != -1 {
+                    return Err(fail(format!("legendre symbol {legendre}")));
+                }
+            }
+            Kat::Sqrt(a, Some(root)) => {
+                let out = e(a).sqrt();
+                if out != e(root) {
+                    return Err(fail(out.serialize()));
+                }
+            }
+            Kat::Bytes(a, hex) => {
+                let bytes = e(a).to_bytes_le();
+                if to_hex(&bytes) != hex || T::from_bytes_le(&bytes) != e(a) {
+                    return Err(fail(to_hex(&bytes)));
+                }
+            }
+            Kat::RejectBytes(hex) => {
+                if let Ok(v) = T::try_from_bytes_le(&from_hex(hex)) {
+                    return Err(fail(v.serialize()));
+                }
+            }
+            Kat::RejectString(s) => {
+                if let Ok(v) = T::try_deserialize(s) {
+                    return Err(fail(v.serialize()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// alt_bn128, p = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+pub const ALT_BN128: &[Kat] = &[
+    Kat::Add(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+        "19484335469364778877233755813569027416053651295204679484264529616979978332754",
+        "19484335469364778877233755813569027416053651295204679484264529616979978332753",
+    ),
+    Kat::Add(
+        "19484335469364778877233755813569027416053651295204679484264529616979978332754",
+        "7237005577332262213973186563042994240829374041602535252466099000494570614841",
+        "4833098174857765868960536631354746568334660936391180393032424430898740451978",
+    ),
+    Kat::Mul(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+        "1",
+    ),
+    Kat::Mul(
+        "19484335469364778877233755813569027416053651295204679484264529616979978332754",
+        "7237005577332262213973186563042994240829374041602535252466099000494570614841",
+        "12255575985604290702264330236733504403492979305946296352366755877232864731204",
+    ),
+    Kat::Inv(
+        "19484335469364778877233755813569027416053651295204679484264529616979978332754",
+        Some("15317973183746357379213589045492845075823458014205696310669050952838793890738"),
+    ),
+    Kat::Inv(
+        "7237005577332262213973186563042994240829374041602535252466099000494570614841",
+        Some("20429078222000538026066381581884228674061816283139311813530197612068047264351"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "7824414942328444521969634552085633001037929556426905685683994721172856088998",
+        Some("2403907402474496345012649931688247672494713105211354859433674569595830162863"),
+    ),
+    Kat::Sqrt(
+        "7237005577332262213973186563042994240829374041602535252466099000494570614841",
+        Some("5703251533136441058498835141035140158139705294781534316092758006717805493886"),
+    ),
+    Kat::Sqrt("5", None),
+    Kat::Bytes(
+        "19484335469364778877233755813569027416053651295204679484264529616979978332754",
+        "52aa3f83b017a5ce2e89b52c73869c39b71f7210a15f94b1d5bb76a639be132b",
+    ),
+    Kat::Bytes(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+        "000000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430",
+    ),
+    Kat::RejectBytes("010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430"),
+    Kat::RejectString(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    ),
+    Kat::RejectString("12x"),
+];
+
+/// curve25519, p = 7237005577332262213973186563042994240857116359379907606001950938285454250989
+pub const CURVE25519: &[Kat] = &[
+    Kat::Add(
+        "7237005577332262213973186563042994240857116359379907606001950938285454250988",
+        "7201217954578788343593535839965173688703967550486290784351129779271123560995",
+        "7201217954578788343593535839965173688703967550486290784351129779271123560994",
+    ),
+    Kat::Add(
+        "7201217954578788343593535839965173688703967550486290784351129779271123560995",
+        "3618502788666131106986593281521497120414687020801267626233049500247285313593",
+        "3582715165912657236606942558443676568261538211907650804582228341232954623599",
+    ),
+    Kat::Mul(
+        "7237005577332262213973186563042994240857116359379907606001950938285454250988",
+        "7237005577332262213973186563042994240857116359379907606001950938285454250988",
+        "1",
+    ),
+    Kat::Mul(
+        "7201217954578788343593535839965173688703967550486290784351129779271123560995",
+        "3618502788666131106986593281521497120414687020801267626233049500247285313593",
+        "6034893170175797582674581651371010824263767381246335248701859529786537802277",
+    ),
+    Kat::Inv(
+        "7201217954578788343593535839965173688703967550486290784351129779271123560995",
+        Some("3602720948434816679739542975117538567042029544729811006663968387886798223112"),
+    ),
+    Kat::Inv(
+        "3618502788666131106986593281521497120414687020801267626233049500247285313593",
+        Some("424452263292249408425249133016749551501264438169891946936232621991831654891"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "1554372278753873365077556417083725867784513990014936535592234920341451798097",
+        Some("35787622753473870379650723077820552153148808893616821650821159014330689994"),
+    ),
+    Kat::Sqrt(
+        "3618502788666131106986593281521497120414687020801267626233049500247285313593",
+        None,
+    ),
+    Kat::Sqrt("2", None),
+    Kat::Bytes(
+        "7201217954578788343593535839965173688703967550486290784351129779271123560995",
+        "236aa3d8a7d91edd864619e266fe3029c1f2e8f9f40453c81b48af4bb3beeb0f",
+    ),
+    Kat::Bytes(
+        "7237005577332262213973186563042994240857116359379907606001950938285454250988",
+        "ecd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010",
+    ),
+    Kat::RejectBytes("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010"),
+    Kat::RejectString(
+        "7237005577332262213973186563042994240857116359379907606001950938285454250989",
+    ),
+    Kat::RejectString("12x"),
+];
+
+/// oxfoi, p = 18446744069414584321
+pub const OXFOI: &[Kat] = &[
+    Kat::Add(
+        "18446744069414584320",
+        "15470282148057639734",
+        "15470282148057639733",
+    ),
+    Kat::Add(
+        "15470282148057639734",
+        "4611686018427400249",
+        "1635224097070455662",
+    ),
+    Kat::Mul("18446744069414584320", "18446744069414584320", "1"),
+    Kat::Mul(
+        "15470282148057639734",
+        "4611686018427400249",
+        "1672679448255216862",
+    ),
+    Kat::Inv("15470282148057639734", Some("18204867258248064599")),
+    Kat::Inv("4611686018427400249", Some("13263169093315628723")),
+    Kat::Inv("0", None),
+    Kat::Sqrt("15950877473171430384", Some("2976461921356944587")),
+    Kat::Sqrt("4611686018427400249", None),
+    Kat::Sqrt("7", None),
+    Kat::Bytes("15470282148057639734", "36eb0a0aa67bb1d6"),
+    Kat::Bytes("18446744069414584320", "00000000ffffffff"),
+    Kat::RejectBytes("01000000ffffffff"),
+    Kat::RejectString("18446744069414584321"),
+    Kat::RejectString("12x"),
+];
+
+/// babybear, p = 2013265921
+pub const BABYBEAR: &[Kat] = &[
+    Kat::Add("2013265920", "1265172484", "1265172483"),
+    Kat::Add("1265172484", "536883257", "1802055741"),
+    Kat::Mul("2013265920", "2013265920", "1"),
+    Kat::Mul("1265172484", "536883257", "1447404182"),
+    Kat::Inv("1265172484", Some("853414823")),
+    Kat::Inv("536883257", Some("1608103371")),
+    Kat::Inv("0", None),
+    Kat::Sqrt("1273259052", Some("748093437")),
+    Kat::Sqrt("536883257", None),
+    Kat::Sqrt("11", None),
+    Kat::Bytes("1265172484", "0400694b"),
+    Kat::Bytes("2013265920", "00000078"),
+    Kat::RejectBytes("01000078"),
+    Kat::RejectString("2013265921"),
+    Kat::RejectString("12x"),
+];
+
+/// mersenne31, p = 2147483647
+pub const MERSENNE31: &[Kat] = &[
+    Kat::Add("2147483646", "2102586776", "2102586775"),
+    Kat::Add("2102586776", "536883257", "491986386"),
+    Kat::Mul("2147483646", "2147483646", "1"),
+    Kat::Mul("2102586776", "536883257", "324555125"),
+    Kat::Inv("2102586776", Some("976644619")),
+    Kat::Inv("536883257", Some("1540912255")),
+    Kat::Inv("0", None),
+    Kat::Sqrt("2090268679", Some("44896871")),
+    Kat::Sqrt("536883257", Some("708324221")),
+    Kat::Sqrt("3", None),
+    Kat::Bytes("2102586776", "98ed527d"),
+    Kat::Bytes("2147483646", "feffff7f"),
+    Kat::RejectBytes("ffffff7f"),
+    Kat::RejectString("2147483647"),
+    Kat::RejectString("12x"),
+];
+
+/// mersenne61, p = 2305843009213693951
+pub const MERSENNE61: &[Kat] = &[
+    Kat::Add(
+        "2305843009213693950",
+        "491698912387354197",
+        "491698912387354196",
+    ),
+    Kat::Add(
+        "491698912387354197",
+        "576460752303435833",
+        "1068159664690790030",
+    ),
+    Kat::Mul("2305843009213693950", "2305843009213693950", "1"),
+    Kat::Mul(
+        "491698912387354197",
+        "576460752303435833",
+        "1743658651845344970",
+    ),
+    Kat::Inv("491698912387354197", Some("750161669174828457")),
+    Kat::Inv("576460752303435833", Some("748239776019931388")),
+    Kat::Inv("0", None),
+    Kat::Sqrt("457534076011839553", Some("491698912387354197")),
+    Kat::Sqrt("576460752303435833", Some("389638841766556492")),
+    Kat::Sqrt("3", None),
+    Kat::Bytes("491698912387354197", "554e4b418eddd206"),
+    Kat::Bytes("2305843009213693950", "feffffffffffff1f"),
+    Kat::RejectBytes("ffffffffffffff1f"),
+    Kat::RejectString("2305843009213693951"),
+    Kat::RejectString("12x"),
+];
+
+/// mersenne127, p = 170141183460469231731687303715884105727
+pub const MERSENNE127: &[Kat] = &[
+    Kat::Add(
+        "170141183460469231731687303715884105726",
+        "85461358271717877535212866461015981600",
+        "85461358271717877535212866461015981599",
+    ),
+    Kat::Add(
+        "85461358271717877535212866461015981600",
+        "42535295865117307932921825928971038777",
+        "127996654136835185468134692389987020377",
+    ),
+    Kat::Mul(
+        "170141183460469231731687303715884105726",
+        "170141183460469231731687303715884105726",
+        "1",
+    ),
+    Kat::Mul(
+        "85461358271717877535212866461015981600",
+        "42535295865117307932921825928971038777",
+        "166495749015890905125356639376091340000",
+    ),
+    Kat::Inv(
+        "85461358271717877535212866461015981600",
+        Some("37683637718367281900718039892870941566"),
+    ),
+    Kat::Inv(
+        "42535295865117307932921825928971038777",
+        Some("53863168446113191301542447884619919095"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "158897916113907975189927621868643561857",
+        Some("84679825188751354196474437254868124127"),
+    ),
+    Kat::Sqrt(
+        "42535295865117307932921825928971038777",
+        Some("25291727277827446742188441776097611490"),
+    ),
+    Kat::Sqrt("3", None),
+    Kat::Bytes(
+        "85461358271717877535212866461015981600",
+        "20be8fa2268e379b3c5a8d2947424b40",
+    ),
+    Kat::Bytes(
+        "170141183460469231731687303715884105726",
+        "feffffffffffffffffffffffffffff7f",
+    ),
+    Kat::RejectBytes("ffffffffffffffffffffffffffffff7f"),
+    Kat::RejectString("170141183460469231731687303715884105727"),
+    Kat::RejectString("12x"),
+];
+
+/// pallas, p = 28948022309329048855892746252171976963363056481941647379679742748393362948097
+pub const PALLAS: &[Kat] = &[
+    Kat::Add(
+        "28948022309329048855892746252171976963363056481941647379679742748393362948096",
+        "2466311905209732800973765921584597632810051883992005696868487786912432410494",
+        "2466311905209732800973765921584597632810051883992005696868487786912432410493",
+    ),
+    Kat::Add(
+        "2466311905209732800973765921584597632810051883992005696868487786912432410494",
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        "16940323059874257228920139047670586114468799967197076201800685787901573627831",
+    ),
+    Kat::Mul(
+        "28948022309329048855892746252171976963363056481941647379679742748393362948096",
+        "28948022309329048855892746252171976963363056481941647379679742748393362948096",
+        "1",
+    ),
+    Kat::Mul(
+        "2466311905209732800973765921584597632810051883992005696868487786912432410494",
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        "3978523766729779989264916439311506745768833842328189507531412463226323743705",
+    ),
+    Kat::Inv(
+        "2466311905209732800973765921584597632810051883992005696868487786912432410494",
+        Some("16353793661316648368254953989366080324864564403689949685532591605135333062084"),
+    ),
+    Kat::Inv(
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        Some("17483113312029573367688239301615678665233024958759510052061976975910722673231"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "14575132544688932003193434092293890731791893178756764827088163337472560151452",
+        Some("2466311905209732800973765921584597632810051883992005696868487786912432410494"),
+    ),
+    Kat::Sqrt(
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        Some("4422552202908523779486453710792615432257479054257815867183208013545188059029"),
+    ),
+    Kat::Sqrt("5", None),
+    Kat::Bytes(
+        "2466311905209732800973765921584597632810051883992005696868487786912432410494",
+        "7e4fae80f519c36dfa43a33b9521779f348332bd08142024b7ca17e210e27305",
+    ),
+    Kat::Bytes(
+        "28948022309329048855892746252171976963363056481941647379679742748393362948096",
+        "0000000021eb468cdda89409fc98462200000000000000000000000000000040",
+    ),
+    Kat::RejectBytes("0100000021eb468cdda89409fc98462200000000000000000000000000000040"),
+    Kat::RejectString(
+        "28948022309329048855892746252171976963363056481941647379679742748393362948097",
+    ),
+    Kat::RejectString("12x"),
+];
+
+/// vesta, p = 28948022309329048855892746252171976963363056481941560715954676764349967630337
+pub const VESTA: &[Kat] = &[
+    Kat::Add(
+        "28948022309329048855892746252171976963363056481941560715954676764349967630336",
+        "26222335615112160635809602496399772250172247190454619180291532347226593792039",
+        "26222335615112160635809602496399772250172247190454619180291532347226593792038",
+    ),
+    Kat::Add(
+        "26222335615112160635809602496399772250172247190454619180291532347226593792039",
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        "11748324460447636207863229370313783768467938791718128969269053583865767379039",
+    ),
+    Kat::Mul(
+        "28948022309329048855892746252171976963363056481941560715954676764349967630336",
+        "28948022309329048855892746252171976963363056481941560715954676764349967630336",
+        "1",
+    ),
+    Kat::Mul(
+        "26222335615112160635809602496399772250172247190454619180291532347226593792039",
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        "856085525747867629004851322139209743642514948588752385126534019306575986880",
+    ),
+    Kat::Inv(
+        "26222335615112160635809602496399772250172247190454619180291532347226593792039",
+        Some("23584125152958260078172967011973859209336711689207320644573661248916854628205"),
+    ),
+    Kat::Inv(
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        Some("783911971037168175823853693978439591695077858219621113601036771814052453522"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "21870841283246674956127567502465932141368195022030191496224486056910950976663",
+        Some("2725686694216888220083143755772204713190809291486941535663144417123373838298"),
+    ),
+    Kat::Sqrt(
+        "14474011154664524427946373126085988481658748083205070504932198000989141217337",
+        None,
+    ),
+    Kat::Sqrt("5", None),
+    Kat::Bytes(
+        "26222335615112160635809602496399772250172247190454619180291532347226593792039",
+        "279075742cc55539b1f08221e2abbf33d2c20e68404fadc974f3587de550f939",
+    ),
+    Kat::Bytes(
+        "28948022309329048855892746252171976963363056481941560715954676764349967630336",
+        "00000000ed302d991bf94c09fc98462200000000000000000000000000000040",
+    ),
+    Kat::RejectBytes("01000000ed302d991bf94c09fc98462200000000000000000000000000000040"),
+    Kat::RejectString(
+        "28948022309329048855892746252171976963363056481941560715954676764349967630337",
+    ),
+    Kat::RejectString("12x"),
+];
+
+/// stark252, p = 3618502788666131213697322783095070105623107215331596699973092056135872020481
+pub const STARK252: &[Kat] = &[
+    Kat::Add(
+        "3618502788666131213697322783095070105623107215331596699973092056135872020480",
+        "2452921889979352450664919031048621333512096764708745089586236579138005274789",
+        "2452921889979352450664919031048621333512096764708745089586236579138005274788",
+    ),
+    Kat::Add(
+        "2452921889979352450664919031048621333512096764708745089586236579138005274789",
+        "1809251394333065553493296640760748560207343510400633813116524750123642662969",
+        "643670495646286790460892888714299788096333059777782202729669273125775917277",
+    ),
+    Kat::Mul(
+        "3618502788666131213697322783095070105623107215331596699973092056135872020480",
+        "3618502788666131213697322783095070105623107215331596699973092056135872020480",
+        "1",
+    ),
+    Kat::Mul(
+        "2452921889979352450664919031048621333512096764708745089586236579138005274789",
+        "1809251394333065553493296640760748560207343510400633813116524750123642662969",
+        "1657323789954143960994438529175193592807025288730341684344659598438023973976",
+    ),
+    Kat::Inv(
+        "2452921889979352450664919031048621333512096764708745089586236579138005274789",
+        Some("2812687605110808546658122200270504692456037085948847746545206978432374080869"),
+    ),
+    Kat::Inv(
+        "1809251394333065553493296640760748560207343510400633813116524750123642662969",
+        Some("1100285355042027338441544046443026805645713073827982716461690539588351700875"),
+    ),
+    Kat::Inv("0", None),
+    Kat::Sqrt(
+        "1509919120079022478698669963843007894473249811170198780471143425491715127044",
+        Some("1165580898686778763032403752046448772111010450622851610386855476997866745692"),
+    ),
+    Kat::Sqrt(
+        "1809251394333065553493296640760748560207343510400633813116524750123642662969",
+        None,
+    ),
+    Kat::Sqrt("3", None),
+    Kat::Bytes(
+        "2452921889979352450664919031048621333512096764708745089586236579138005274789",
+        "a5909ab3d20b88248abbc988efcaa3a9081a0e75ec3b6096af295b9df94d6c05",
+    ),
+    Kat::Bytes(
+        "3618502788666131213697322783095070105623107215331596699973092056135872020480",
+        "0000000000000000000000000000000000000000000000001100000000000008",
+    ),
+    Kat::RejectBytes("0100000000000000000000000000000000000000000000001100000000000008"),
+    Kat::RejectString(
+        "3618502788666131213697322783095070105623107215331596699973092056135872020481",
+    ),
+    Kat::RejectString("12x"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    fn test_kats<T: FieldElement>() {
+        assert_eq!(check::<T>(), Ok(vectors(T::name_str()).unwrap().len()));
+    }
+
+    #[test]
+    fn negative_vectors_fail() {
+        scalar_ring!(F13FieldElement, 13_u128, "f13");
+        assert_eq!(check::<F13FieldElement>(), Ok(0));
+        let wrong = [Kat::Add("1", "2", "3"), Kat::Mul("3", "5", "3")];
+        let failure = check_vectors::<F13FieldElement>(&wrong).unwrap_err();
+        assert_eq!(failure.index, 1);
+        assert_eq!(failure.actual, "2");
+        assert!(check_vectors::<F13FieldElement>(&[Kat::Sqrt("4", None)]).is_err());
+        assert!(check_vectors::<F13FieldElement>(&[Kat::RejectBytes("0c")]).is_err());
+        assert!(check_vectors::<F13FieldElement>(&[Kat::RejectBytes("0d")]).is_ok());
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn kat_bn128() {
+        test_kats::<crate::Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn kat_curve25519() {
+        test_kats::<crate::Curve25519FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn kat_foi() {
+        test_kats::<crate::OxfoiFieldElement>();
+    }
+
+    #[cfg(feature = "babybear")]
+    #[test]
+    fn kat_babybear() {
+        test_kats::<crate::BabyBearFieldElement>();
+    }
+
+    #[cfg(feature = "mersenne")]
+    #[test]
+    fn kat_mersenne() {
+        test_kats::<crate::Mersenne31FieldElement>();
+        test_kats::<crate::Mersenne61FieldElement>();
+        test_kats::<crate::Mersenne127FieldElement>();
+    }
+
+    #[cfg(feature = "pallas")]
+    #[test]
+    fn kat_pallas() {
+        test_kats::<crate::PallasFieldElement>();
+    }
+
+    #[cfg(feature = "vesta")]
+    #[test]
+    fn kat_vesta() {
+        test_kats::<crate::VestaFieldElement>();
+    }
+
+    #[cfg(feature = "stark252")]
+    #[test]
+    fn kat_stark252() {
+        test_kats::<crate::Stark252FieldElement>();
+    }
+}
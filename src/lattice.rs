@@ -0,0 +1,348 @@
+//! Lattice algorithms on the integer lifts of field element matrices.
+//!
+//! Integer matrices are `Vec<Vec<BigInt>>` with one `Vec` per row,
+//! lattices are spanned by the rows. `lift_matrix` converts a 2
+//! dimensional `Matrix` to its lifts in `[0, p)`. `q_ary_basis` gives
+//! the Hermite normal form of the q-ary lattice `{x : x = yA mod p}`
+//! spanned by the rows of a matrix and `p Z^n`, the lattice used in
+//! SIS/LWE style commitments.
+//!
+//! `gram_schmidt` and `lll` work with exact rational arithmetic, the
+//! rows of the basis must be linearly independent (e.g. the output
+//! of `hermite_normal_form`).
+//!
+//! These are textbook algorithms intended for analysis of small
+//! dimensions, e.g. sanity checking parameters, not for attacks.
+//!
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+
+use super::matrix::Matrix;
+use super::FieldElement;
+
+pub type IntMatrix = Vec<Vec<BigInt>>;
+
+/// The integer lifts in `[0, p)` of a 2 dimensional matrix.
+pub fn lift_matrix<T: FieldElement>(m: &Matrix<T>) -> IntMatrix {
+    if m.dimensions.len() != 2 {
+        panic!("expected a 2 dimensional matrix: {:?}", m.dimensions);
+    }
+    m.rows()
+        .map(|row| row.iter().map(|v| BigInt::from(v.to_biguint())).collect())
+        .collect()
+}
+
+/// The Hermite normal form of the lattice spanned by the rows of `m`:
+/// upper triangular (echelon) with positive pivots and entries above
+/// each pivot reduced into `[0, pivot)`. Zero rows are removed, so the
+/// output is a basis of the lattice.
+pub fn hermite_normal_form(m: &IntMatrix) -> IntMatrix {
+    let mut m = m.clone();
+    let cols = m.first().map_or(0, |row| row.len());
+    let zero = BigInt::from(0);
+    let mut r = 0;
+    for c in 0..cols {
+        if r == m.len() {
+            break;
+        }
+        // combine the rows below so only row r is non-zero in column c
+        for i in r + 1..m.len() {
+            if m[i][c] == zero {
+                continue;
+            }
+            let gcd = m[r][c].extended_gcd(&m[i][c]);
+            let a = &m[r][c] / &gcd.gcd;
+            let b = &m[i][c] / &gcd.gcd;
+            // [[x, y], [-b, a]] has determinant 1
+            let new_r = combine(&m[r], &gcd.x, &m[i], &gcd.y);
+            let new_i = combine(&m[r], &-b, &m[i], &a);
+            m[r] = new_r;
+            m[i] = new_i;
+        }
+        if m[r][c] == zero {
+            continue;
+        }
+        if m[r][c] < zero {
+            m[r] = m[r].iter().map(|v| -v).collect();
+        }
+        for k in 0..r {
+            let q = m[k][c].div_floor(&m[r][c]);
+            let reduced = combine(&m[k], &BigInt::from(1), &m[r], &-q);
+            m[k] = reduced;
+        }
+        r += 1;
+    }
+    m.truncate(r);
+    m
+}
+
+/// The invariant factors `d_1 | d_2 | ...` on the diagonal of the
+/// Smith normal form of `m`, zero factors omitted. Their product is
+/// the index of the lattice in its span (e.g. `|det m|` for a square
+/// nonsingular `m`).
+pub fn smith_normal_form(m: &IntMatrix) -> Vec<BigInt> {
+    let mut m = m.clone();
+    let rows = m.len();
+    let cols = m.first().map_or(0, |row| row.len());
+    let zero = BigInt::from(0);
+    let mut factors = vec![];
+    for t in 0..rows.min(cols) {
+        loop {
+            // move the smallest non-zero entry to (t, t)
+            let pivot = (t..rows)
+                .flat_map(|i| (t..cols).map(move |j| (i, j)))
+                .filter(|(i, j)| m[*i][*j] != zero)
+                .min_by_key(|(i, j)| m[*i][*j].magnitude().clone());
+            let Some((pi, pj)) = pivot else {
+                return factors;
+            };
+            m.swap(t, pi);
+            for row in m.iter_mut() {
+                row.swap(t, pj);
+            }
+            let mut done = true;
+            for i in t + 1..rows {
+                let q = m[i][t].div_floor(&m[t][t]);
+                m[i] = combine(&m[i], &BigInt::from(1), &m[t], &-q);
+                done &= m[i][t] == zero;
+            }
+            for j in t + 1..cols {
+                let q = m[t][j].div_floor(&m[t][t]);
+                for row in m.iter_mut().skip(t) {
+                    let v = &row[j] - &q * &row[t];
+                    row[j] = v;
+                }
+                done &= m[t][j] == zero;
+            }
+            if !done {
+                continue;
+            }
+            // the pivot must divide every remaining entry
+            let not_divisible =
+                (t + 1..rows).find(|i| (t + 1..cols).any(|j| &m[*i][j] % &m[t][t] != zero));
+            match not_divisible {
+                Some(i) => {
+                    let row = m[i].clone();
+                    m[t] = combine(&m[t], &BigInt::from(1), &row, &BigInt::from(1));
+                }
+                None => break,
+            }
+        }
+        factors.push(m[t][t].magnitude().clone().into());
+    }
+    factors
+}
+
+/// The Hermite normal form basis of the q-ary lattice spanned by the
+/// rows of `m` and `p Z^n`, where `p` is the field modulus.
+pub fn q_ary_basis<T: FieldElement>(m: &Matrix<T>) -> IntMatrix {
+    let mut rows = lift_matrix(m);
+    let n = m.dimensions[1];
+    let p = BigInt::from(T::prime());
+    for i in 0..n {
+        let mut row = vec![BigInt::from(0); n];
+        row[i] = p.clone();
+        rows.push(row);
+    }
+    hermite_normal_form(&rows)
+}
+
+/// The Gram–Schmidt orthogonalization of a basis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GramSchmidt {
+    /// The orthogonal vectors `b*_i`.
+    pub vectors: Vec<Vec<BigRational>>,
+    /// `mu[i][j] = <b_i, b*_j> / <b*_j, b*_j>` for `j < i`, zero otherwise.
+    pub mu: Vec<Vec<BigRational>>,
+}
+
+impl GramSchmidt {
+    /// The squared norms `<b*_i, b*_i>`.
+    pub fn norms_squared(&self) -> Vec<BigRational> {
+        self.vectors.iter().map(|v| dot(v, v)).collect()
+    }
+}
+
+/// Orthogonalize the rows of `basis`. Panics if the rows are
+/// linearly dependent.
+pub fn gram_schmidt(basis: &IntMatrix) -> GramSchmidt {
+    let zero = BigRational::from_integer(BigInt::from(0));
+    let mut vectors: Vec<Vec<BigRational>> = vec![];
+    let mut norms: Vec<BigRational> = vec![];
+    let mut mu = vec![vec![zero.clone(); basis.len()]; basis.len()];
+    for (i, row) in basis.iter().enumerate() {
+        let b = row
+            .iter()
+            .map(|v| BigRational::from_integer(v.clone()))
+            .collect::<Vec<_>>();
+        let mut v = b.clone();
+        for j in 0..i {
+            mu[i][j] = dot(&b, &vectors[j]) / &norms[j];
+            for (x, y) in v.iter_mut().zip(vectors[j].iter()) {
+                *x -= &mu[i][j] * y;
+            }
+        }
+        let norm = dot(&v, &v);
+        if norm == zero {
+            panic!("basis rows are linearly dependent");
+        }
+        vectors.push(v);
+        norms.push(norm);
+    }
+    GramSchmidt { vectors, mu }
+}
+
+/// LLL reduce the rows of `basis` with `delta = 3/4`.
+pub fn lll(basis: &IntMatrix) -> IntMatrix {
+    lll_with_delta(basis, &BigRational::new(3.into(), 4.into()))
+}
+
+/// LLL reduce the rows of `basis` with the Lovász parameter
+/// `delta` in `(1/4, 1]`. Panics if the rows are linearly dependent.
+pub fn lll_with_delta(basis: &IntMatrix, delta: &BigRational) -> IntMatrix {
+    let mut b = basis.clone();
+    let mut gs = gram_schmidt(&b);
+    let mut k = 1;
+    while k < b.len() {
+        for j in (0..k).rev() {
+            let q = gs.mu[k][j].round().to_integer();
+            if q != BigInt::from(0) {
+                b[k] = combine(&b[k], &BigInt::from(1), &b[j], &-q);
+                gs = gram_schmidt(&b);
+            }
+        }
+        let norms = gs.norms_squared();
+        let mu = &gs.mu[k][k - 1];
+        if norms[k] >= (delta - mu * mu) * &norms[k - 1] {
+            k += 1;
+        } else {
+            b.swap(k, k - 1);
+            gs = gram_schmidt(&b);
+            k = (k - 1).max(1);
+        }
+    }
+    b
+}
+
+/// Check the size reduction (`|mu[i][j]| <= 1/2`) and Lovász
+/// conditions for the rows of `basis`.
+pub fn is_lll_reduced(basis: &IntMatrix, delta: &BigRational) -> bool {
+    let gs = gram_schmidt(basis);
+    let norms = gs.norms_squared();
+    let half = BigRational::new(1.into(), 2.into());
+    let size_reduced =
+        (0..basis.len()).all(|i| (0..i).all(|j| gs.mu[i][j] <= half && gs.mu[i][j] >= -&half));
+    size_reduced
+        && (1..basis.len()).all(|k| {
+            let mu = &gs.mu[k][k - 1];
+            norms[k] >= (delta - mu * mu) * &norms[k - 1]
+        })
+}
+
+fn dot(a: &[BigRational], b: &[BigRational]) -> BigRational {
+    a.iter()
+        .zip(b.iter())
+        .fold(BigRational::from_integer(BigInt::from(0)), |acc, (x, y)| {
+            acc + x * y
+        })
+}
+
+/// `a * x + b * y` element wise.
+fn combine(a: &[BigInt], x: &BigInt, b: &[BigInt], y: &BigInt) -> Vec<BigInt> {
+    a.iter().zip(b.iter()).map(|(u, v)| u * x + v * y).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn int_matrix(rows: &[&[i64]]) -> IntMatrix {
+        rows.iter()
+            .map(|row| row.iter().map(|v| BigInt::from(*v)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn hermite() {
+        let m = int_matrix(&[&[2, 4, 4], &[-6, 6, 12], &[10, -4, -16]]);
+        let h = hermite_normal_form(&m);
+        // |det| = 144 = product of the pivots
+        assert_eq!(h, int_matrix(&[&[2, 4, 4], &[0, 6, 0], &[0, 0, 12]]));
+        // dependent rows are dropped
+        let m = int_matrix(&[&[1, 2], &[2, 4], &[3, 6]]);
+        assert_eq!(hermite_normal_form(&m), int_matrix(&[&[1, 2]]));
+        assert_eq!(hermite_normal_form(&vec![]), IntMatrix::new());
+    }
+
+    #[test]
+    fn smith() {
+        let m = int_matrix(&[&[2, 4, 4], &[-6, 6, 12], &[10, -4, -16]]);
+        assert_eq!(smith_normal_form(&m), [2, 6, 12].map(BigInt::from).to_vec());
+        let m = int_matrix(&[&[1, 2], &[2, 4], &[3, 6]]);
+        assert_eq!(smith_normal_form(&m), vec![BigInt::from(1)]);
+        // gcd of the entries comes first
+        let m = int_matrix(&[&[4, 0], &[0, 6]]);
+        assert_eq!(smith_normal_form(&m), [2, 12].map(BigInt::from).to_vec());
+    }
+
+    #[test]
+    fn orthogonalize() {
+        let gs = gram_schmidt(&int_matrix(&[&[3, 1], &[2, 2]]));
+        let r = |n: i64, d: i64| BigRational::new(n.into(), d.into());
+        assert_eq!(gs.mu[1][0], r(4, 5));
+        assert_eq!(gs.vectors[1], vec![r(-2, 5), r(6, 5)]);
+        assert_eq!(gs.norms_squared(), vec![r(10, 1), r(8, 5)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "linearly dependent")]
+    fn orthogonalize_dependent() {
+        gram_schmidt(&int_matrix(&[&[1, 2], &[2, 4]]));
+    }
+
+    #[test]
+    fn reduce() {
+        let basis = int_matrix(&[&[1, 1, 1], &[-1, 0, 2], &[3, 5, 6]]);
+        let delta = BigRational::new(3.into(), 4.into());
+        assert!(!is_lll_reduced(&basis, &delta));
+        let reduced = lll(&basis);
+        // mu = 1/2 for the last row rounds away from zero
+        assert_eq!(reduced, int_matrix(&[&[0, 1, 0], &[1, 0, 1], &[-2, 0, 1]]));
+        assert!(is_lll_reduced(&reduced, &delta));
+        // the reduced basis spans the same lattice
+        assert_eq!(hermite_normal_form(&reduced), hermite_normal_form(&basis));
+
+        // a q-ary lattice from a field matrix
+        let m = Matrix {
+            dimensions: vec![1, 3],
+            values: [1, 5, 8].map(F13FieldElement::from).to_vec(),
+        };
+        let basis = q_ary_basis(&m);
+        let reduced = lll(&basis);
+        assert!(is_lll_reduced(&reduced, &delta));
+        assert_eq!(hermite_normal_form(&reduced), basis);
+        // the first vector is short
+        let norm = reduced[0].iter().map(|v| v * v).sum::<BigInt>();
+        assert!(norm < BigInt::from(13 * 13));
+    }
+
+    #[test]
+    fn q_ary() {
+        let matrix = |values: [u64; 4]| Matrix {
+            dimensions: vec![2, 2],
+            values: values.map(F13FieldElement::from).to_vec(),
+        };
+        // invertible mod 13, the lattice is all of Z^2
+        assert_eq!(
+            q_ary_basis(&matrix([1, 2, 3, 4])),
+            int_matrix(&[&[1, 0], &[0, 1]])
+        );
+        assert_eq!(
+            q_ary_basis(&matrix([1, 2, 2, 4])),
+            int_matrix(&[&[1, 2], &[0, 13]])
+        );
+    }
+}
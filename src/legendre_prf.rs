@@ -0,0 +1,98 @@
+//! The Legendre PRF, an educational implementation.
+//!
+//! For a secret key `k` the output on input `x` is the bit
+//! `(1 - L(k + x)) / 2` where `L` is the Legendre symbol, i.e. 1 if
+//! `k + x` is a quadratic non-residue and 0 otherwise (`L(0)` is treated
+//! as 1). The PRF is cheap to evaluate in MPC: `k + x` can be masked
+//! with a random square and opened, the symbol of the opened value
+//! determines the output.
+//!
+//! See [Damgård 88](https://link.springer.com/chapter/10.1007/0-387-34799-2_13)
+//! and [Grassi et al. 16](https://eprint.iacr.org/2016/542).
+//!
+//! This implementation is not constant time and is not intended
+//! for production use.
+//!
+use super::functions::jacobi;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendrePrf<T: FieldElement> {
+    key: T,
+}
+
+impl<T: FieldElement> LegendrePrf<T> {
+    pub fn new(key: T) -> Self {
+        LegendrePrf { key }
+    }
+
+    /// Create a PRF with a random key. Requires the
+    /// `random` feature.
+    #[cfg(feature = "random")]
+    pub fn random<R: rand::Rng>(src: &mut R) -> Self {
+        Self::new(T::sample_uniform(src))
+    }
+
+    /// The Legendre symbol of `key + x`.
+    pub fn symbol(&self, x: &T) -> i32 {
+        jacobi(&(self.key.clone() + x.clone()).to_biguint(), &T::prime())
+    }
+
+    /// The output bit on input `x`.
+    pub fn eval(&self, x: &T) -> u8 {
+        u8::from(self.symbol(x) == -1)
+    }
+
+    /// The output bits on each input.
+    pub fn eval_batch(&self, xs: &[T]) -> Vec<u8> {
+        xs.iter().map(|x| self.eval(x)).collect()
+    }
+
+    /// The output bits on the consecutive inputs `start, start + 1, ...`
+    pub fn eval_range(&self, start: &T, count: usize) -> Vec<u8> {
+        let mut x = start.clone();
+        let mut out = Vec::with_capacity(count);
+        for _ in 0..count {
+            out.push(self.eval(&x));
+            x += T::one();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    #[test]
+    fn outputs() {
+        let key = F1009FieldElement::from(123);
+        let prf = LegendrePrf::new(key);
+        let xs = (0..1009).map(F1009FieldElement::from).collect::<Vec<_>>();
+        let bits = prf.eval_batch(&xs);
+        assert_eq!(bits, prf.eval_range(&F1009FieldElement::zero(), 1009));
+        for (x, bit) in xs.iter().zip(&bits) {
+            assert_eq!(*bit == 1, (key + *x).legendre() == -1);
+        }
+        // exactly (p - 1) / 2 non-residues
+        assert_eq!(bits.iter().filter(|b| **b == 1).count(), 504);
+        // key + x = 0 maps to 0
+        assert_eq!(prf.eval(&-key), 0);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn outputs_bn128() {
+        use crate::Bn128FieldElement;
+        let prf = LegendrePrf::new(Bn128FieldElement::from(999));
+        for x in 0..20 {
+            let x = Bn128FieldElement::from(x);
+            assert_eq!(
+                prf.eval(&x) == 1,
+                (Bn128FieldElement::from(999) + x).legendre() == -1
+            );
+        }
+    }
+}
@@ -12,6 +12,27 @@
 //!   - `alt_bn128` - (aka Bn254)
 //!   - `curve25519`
 //!   - `oxfoi` - (aka goldilocks)
+//!   - `binary_field` - GF(2^8) through GF(2^128)
+//!   - `babybear` - 15 * 2^27 + 1
+//!   - `mersenne` - 2^31 - 1, 2^61 - 1 and 2^127 - 1
+//!   - `pallas`, `vesta` - the Pasta curve cycle used by halo2
+//!   - `stark252` - the Starknet/Cairo prime 2^251 + 17 * 2^192 + 1
+//!
+//! With the `tracing` feature expensive operations (square roots,
+//! inversion, matrix products, NTTs) are wrapped in `tracing` debug
+//! spans with the field name and element counts as fields.
+//!
+//! Fields with other moduli can be defined without dependencies using
+//! `scalar_ring!` (moduli below 2^64) or `mont_field!` (Montgomery
+//! form with any number of 64 bit limbs, see `montgomery`).
+//! `quadratic_extension!` builds `F_p^2` over any odd characteristic
+//! field, `extension_field!` builds `F_p^d` for any degree, see
+//! `extension`.
+//! `scalar_ring!` moduli that are prime powers `p^k` have inverses and
+//! square roots via Hensel lifting in `hensel`.
+//! `kat` has known answer vectors for the built in fields.
+//! `fingerprint::FingerprintElement` is a ready made ring modulo the
+//! product of two 64 bit primes for hashing byte strings.
 //!
 //! Example usage:
 //! ```toml
@@ -22,7 +43,9 @@
 //! ```rust
 //! use scalarff::FieldElement; // Bring the trait in scope
 //! // Import 1 or more concrete instances
+//! # #[cfg(feature = "curve25519")]
 //! use scalarff::Curve25519FieldElement;
+//! # #[cfg(feature = "oxfoi")]
 //! use scalarff::OxfoiFieldElement;
 //! ```
 //!
@@ -39,38 +62,247 @@ use std::ops::Sub;
 use std::ops::SubAssign;
 use std::str::FromStr;
 
+use num_bigint::BigInt;
 use num_integer::Integer;
 
+/// Enter a `tracing` span for the rest of the enclosing block.
+/// Expands to nothing unless the `tracing` feature is enabled.
+macro_rules! trace_span {
+    ( $name: expr $(, $field: ident = $value: expr)* ) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $field = $value)*).entered();
+    };
+}
+
 #[macro_use]
 mod custom;
 
 #[cfg(feature = "alt_bn128")]
 pub mod alt_bn128;
+#[cfg(feature = "babybear")]
+pub mod baby_bear;
+#[cfg(feature = "binary_field")]
+pub mod binary_field;
 #[cfg(feature = "curve25519")]
 pub mod curve_25519;
+#[cfg(feature = "mersenne")]
+pub mod mersenne;
 #[cfg(all(feature = "oxfoi", target_pointer_width = "64"))]
 pub mod oxfoi;
 #[cfg(feature = "oxfoi")]
 pub mod oxfoi_slow;
+#[cfg(any(feature = "pallas", feature = "vesta"))]
+pub mod pasta;
+#[cfg(feature = "stark252")]
+pub mod stark252;
 
+pub mod addition_chain;
+pub mod canonical;
+pub mod challenge;
+pub mod circuit;
+pub mod combinatorics;
+pub mod curve;
+pub mod display;
+pub mod domain;
+pub mod error;
+pub mod extension;
+pub mod fingerprint;
+pub mod fixed_vector;
+pub mod functions;
+#[cfg(feature = "hash")]
+pub mod hash_to_field;
+pub mod hensel;
+pub mod hypercube;
+pub mod interop;
+pub mod io;
+pub mod kat;
+pub mod lattice;
+pub mod legendre_prf;
+pub mod linear_code;
 pub mod matrix;
+pub mod mimc;
+pub mod model_check;
+pub mod module;
+pub mod montgomery;
+pub mod multilinear;
+pub mod ntt;
+pub mod parallel;
+pub mod polynomial;
+pub mod pow_table;
+pub mod residue_table;
+pub mod sqrt;
+pub mod square_matrix;
+pub mod sumcheck;
+pub mod tensor;
 pub mod timing;
+pub mod tuning;
+pub mod vector;
+#[cfg(feature = "verify-backend")]
+mod verify;
 
 #[cfg(feature = "alt_bn128")]
 pub use alt_bn128::Bn128FieldElement;
+#[cfg(feature = "babybear")]
+pub use baby_bear::BabyBearFieldElement;
+#[cfg(feature = "binary_field")]
+pub use binary_field::Binary128FieldElement;
+#[cfg(feature = "binary_field")]
+pub use binary_field::Binary16FieldElement;
+#[cfg(feature = "binary_field")]
+pub use binary_field::Binary32FieldElement;
+#[cfg(feature = "binary_field")]
+pub use binary_field::Binary64FieldElement;
+#[cfg(feature = "binary_field")]
+pub use binary_field::Binary8FieldElement;
 #[cfg(feature = "curve25519")]
 pub use curve_25519::Curve25519FieldElement;
+pub use error::FieldError;
+pub use error::ParseError;
+pub use error::ShapeError;
+#[cfg(feature = "oxfoi")]
+pub use extension::OxfoiCubicFieldElement;
+#[cfg(feature = "oxfoi")]
+pub use extension::OxfoiQuadraticFieldElement;
+pub use fingerprint::FingerprintElement;
+#[cfg(feature = "mersenne")]
+pub use mersenne::Mersenne127FieldElement;
+#[cfg(feature = "mersenne")]
+pub use mersenne::Mersenne31FieldElement;
+#[cfg(feature = "mersenne")]
+pub use mersenne::Mersenne61FieldElement;
 pub use num_bigint::BigUint;
 #[cfg(all(feature = "oxfoi", target_pointer_width = "64"))]
 pub use oxfoi::OxfoiFieldElement;
 #[cfg(all(feature = "oxfoi", not(target_pointer_width = "64")))]
 pub use oxfoi_slow::OxfoiFieldElement;
+#[cfg(feature = "pallas")]
+pub use pasta::PallasFieldElement;
+#[cfg(feature = "vesta")]
+pub use pasta::VestaFieldElement;
+#[cfg(feature = "stark252")]
+pub use stark252::Stark252FieldElement;
+
+/// Version of the encodings produced by `FieldElement::serialize`
+/// and `FieldElement::to_bytes_le`. Incremented whenever the string
+/// or byte representation of any field changes. Encodings are pinned
+/// by the fixtures in `fixtures/format.txt`.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Operations available in a commutative ring. Every `FieldElement`
+/// is a `RingElement`. Structures that don't have general inverses
+/// (e.g. polynomials) can implement this trait to be used where
+/// only ring arithmetic is needed.
+///
+/// `Self::from(0)` and `Self::from(1)` are the additive and
+/// multiplicative identities.
+pub trait RingElement:
+    Add<Output = Self>
+    + AddAssign
+    + Mul<Output = Self>
+    + MulAssign
+    + Neg<Output = Self>
+    + Sub<Output = Self>
+    + SubAssign
+    + PartialEq
+    + Clone
+    + Debug
+    + From<u64>
+    + Display
+{
+}
+
+impl<T: FieldElement> RingElement for T {}
+
+/// The algorithm used to compute multiplicative inverses.
+/// See `FieldElement::inv_strategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvStrategy {
+    /// Exponentiation `x^(p-2)`, requires a prime modulus.
+    Fermat,
+    /// Extended euclidean algorithm on the integer lift, works
+    /// for any modulus.
+    Euclid,
+}
+
+/// Pack little endian bytes into little endian `u64` limbs, the
+/// exponent format used by arkworks and `ff`.
+#[allow(dead_code)]
+pub(crate) fn le_bytes_to_limbs(bytes: &[u8]) -> Vec<u64> {
+    bytes
+        .chunks(8)
+        .map(|chunk| {
+            let mut limb = [0_u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(limb)
+        })
+        .collect()
+}
+
+/// Fields whose encoding length is a compile time constant.
+/// `byte_len()` returns `BYTE_LEN` and every `to_bytes_le` output is
+/// exactly `BYTE_LEN` bytes, the byte length of `p - 1`. The
+/// `scalar_ring!` and `mont_field!` macros check `BYTE_LEN` against
+/// the modulus with a static assertion, the `byte_lengths` test checks
+/// every implementation.
+///
+/// Extension fields compute their length from `p^d` at runtime and
+/// don't implement this trait. Use `io::migrate_bytes_le` to convert
+/// data written with trimmed or otherwise variable length encodings.
+pub trait FixedByteLen: FieldElement {
+    const BYTE_LEN: usize;
+}
+
+/// Panic if `bytes` is longer than `T::byte_len()`. Shared by the
+/// `from_bytes_le` implementations so oversized inputs are rejected
+/// the same way in every field.
+#[doc(hidden)]
+pub fn assert_byte_len<T: FieldElement>(bytes: &[u8]) {
+    assert!(
+        bytes.len() <= T::byte_len(),
+        "{}: expected at most {} bytes, got {}",
+        T::name_str(),
+        T::byte_len(),
+        bytes.len()
+    );
+}
+
+/// The byte length of `modulus - 1` for a modulus given as little
+/// endian limbs, at least 1. The field macros compare `BYTE_LEN`
+/// against this in a static assertion.
+#[doc(hidden)]
+pub const fn modulus_byte_len(modulus: &[u64]) -> usize {
+    let mut bits = 0;
+    let mut borrow = 1;
+    let mut i = 0;
+    while i < modulus.len() {
+        let (limb, b) = modulus[i].overflowing_sub(borrow);
+        borrow = b as u64;
+        if limb != 0 {
+            bits = 64 * i + (64 - limb.leading_zeros() as usize);
+        }
+        i += 1;
+    }
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(8)
+    }
+}
 
 /// A generic representation of a scalar finite field element.
 /// For use in internal module logic. Supports field operations
 /// using builtin operators (*-+/) and other convenience traits.
 /// Handles serialization and deserialization to a reasonable
-/// string representation.
+/// string representation. `Default` is the zero element.
+///
+/// Elements are `Send + Sync + 'static` so generic code can move
+/// them across threads without repeating the bounds.
+///
+/// The internal representation of an element may not be unique,
+/// e.g. Montgomery form (oxfoi) or a lazily reduced type that stores
+/// values in `[0, 2p)`. Implementations must make `PartialEq` and
+/// `Hash` compare the canonical value, never the raw representation.
+/// `canonicalize` reduces the representation in place.
 pub trait FieldElement:
     Add<Output = Self>
     + AddAssign
@@ -87,7 +319,18 @@ pub trait FieldElement:
     + Debug
     + From<u64>
     + Display
+    + Default
+    + Send
+    + Sync
+    + 'static
 {
+    /// Reduce the internal representation to its unique canonical
+    /// form. Arithmetic, comparison and serialization are correct
+    /// without calling this, it only matters to code that inspects
+    /// the representation (e.g. `unsafe` transmutes or raw limb
+    /// access). A no-op for types that are always canonical.
+    fn canonicalize(&mut self) {}
+
     /// Get the zero element.
     fn zero() -> Self {
         Self::from(0)
@@ -99,7 +342,7 @@ pub trait FieldElement:
     }
 
     /// Minimum number of bytes needed to represent
-    /// an element.
+    /// an element, the length of every `to_bytes_le` output.
     fn byte_len() -> usize;
 
     /// Sample a random element from the field using a supplied
@@ -113,6 +356,58 @@ pub trait FieldElement:
         Self::from_bytes_le(&bytes)
     }
 
+    /// Hash bytes to an element per RFC 9380 with SHA-256, see
+    /// `hash_to_field`. Panics if the DST is longer than 255 bytes.
+    /// Requires the `hash` feature.
+    #[cfg(feature = "hash")]
+    fn hash_to_field(msg: &[u8], dst: &[u8]) -> Self {
+        let mut out = hash_to_field::hash_to_field(msg, dst, 1)
+            .expect("hash_to_field: DST is longer than 255 bytes");
+        out.pop().unwrap()
+    }
+
+    /// Two independent elements from one expansion, e.g. for the two
+    /// field elements of `hash_to_curve`. Panics if the DST is longer
+    /// than 255 bytes. Requires the `hash` feature.
+    #[cfg(feature = "hash")]
+    fn hash_to_field2(msg: &[u8], dst: &[u8]) -> [Self; 2] {
+        let mut out = hash_to_field::hash_to_field(msg, dst, 2)
+            .expect("hash_to_field: DST is longer than 255 bytes");
+        let second = out.pop().unwrap();
+        [out.pop().unwrap(), second]
+    }
+
+    /// A uniform element derived from `seed` and `index` with
+    /// `hash_to_field` as a PRF, using `hash_to_field::FROM_SEED_DST`.
+    /// The same inputs give the same element on every platform and
+    /// version. Not a substitute for a CSPRNG when the seed is secret
+    /// and low entropy. Requires the `hash` feature.
+    #[cfg(feature = "hash")]
+    fn from_seed(seed: &[u8], index: u64) -> Self {
+        let msg = [
+            &(seed.len() as u64).to_le_bytes(),
+            seed,
+            &index.to_le_bytes(),
+        ]
+        .concat();
+        Self::hash_to_field(&msg, hash_to_field::FROM_SEED_DST)
+    }
+
+    /// A 64 bit FNV-1a hash of the canonical byte representation
+    /// with trailing zero bytes removed. Equal elements hash identically
+    /// no matter how they were constructed. The value is stable
+    /// across processes and platforms.
+    fn canonical_hash_u64(&self) -> u64 {
+        let bytes = self.to_bytes_le();
+        let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        let mut hash = 0xcbf29ce484222325_u64;
+        for b in &bytes[..len] {
+            hash ^= u64::from(*b);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
     /// Get a valid string representation
     /// of the element.
     fn serialize(&self) -> String;
@@ -121,8 +416,31 @@ pub trait FieldElement:
     /// valid string representation.
     fn deserialize(str: &str) -> Self;
 
+    /// Parse a decimal string without panicking. Leading zeroes are
+    /// accepted, values not less than the modulus are rejected. Use
+    /// this for untrusted input.
+    fn try_deserialize(str: &str) -> Result<Self, ParseError> {
+        if str.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if let Some((position, character)) = str.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(ParseError::InvalidDigit {
+                position,
+                character,
+            });
+        }
+        // only ascii digits remain
+        let v = BigUint::parse_bytes(str.as_bytes(), 10).ok_or(ParseError::Empty)?;
+        if v >= Self::prime() {
+            return Err(ParseError::NonCanonical);
+        }
+        Ok(Self::from_biguint(&v))
+    }
+
     /// The prime modulus of the field as an
-    /// arbitrary precision integer.
+    /// arbitrary precision integer. For fields that are not prime
+    /// order (e.g. `binary_field`) this is the number of elements,
+    /// see `characteristic`.
     fn prime() -> BigUint {
         // this is a generic implementation.
         // Concrete instances may provide
@@ -130,9 +448,35 @@ pub trait FieldElement:
         (-Self::one()).to_biguint() + 1_u32
     }
 
+    /// The characteristic of the field, the smallest `n` with
+    /// `n * 1 = 0`. Equal to `prime()` for prime fields. Generic
+    /// algorithms use this to specialize for characteristic 2,
+    /// where `1 + 1 = 0` and every element is a square.
+    fn characteristic() -> BigUint {
+        Self::prime()
+    }
+
+    /// Whether the modulus is less than `2^64`. Generic algorithms can
+    /// use this to pick strategies, e.g. table based discrete logs.
+    fn is_small_field() -> bool {
+        Self::prime().bits() <= 64
+    }
+
+    /// Whether the integer lift of every element fits in a `u64`.
+    fn fits_in_u64() -> bool {
+        (Self::prime() - 1_u32).bits() <= 64
+    }
+
     /// A short string identifier for the field.
     fn name_str() -> &'static str;
 
+    /// The library (and major version) implementing the field
+    /// arithmetic, e.g. `"ark-bn254 0.4"`. For diagnostics, logs
+    /// and bug reports.
+    fn backend_str() -> &'static str {
+        "unknown"
+    }
+
     /// Parse an element from a usize
     /// throws if the field size is smaller than
     /// the usize on the machine.
@@ -154,19 +498,79 @@ pub trait FieldElement:
     /// Convert a `num_bigint::BigUint` into a field element
     /// precision operations. Numbers will be converted % self.prime()
     fn from_biguint(v: &BigUint) -> Self {
-        Self::from_bytes_le(&v.clone().to_bytes_le()[..])
+        Self::from_bytes_le(&(v % Self::prime()).to_bytes_le()[..])
     }
 
-    /// Parse an element from a byte representation. Panics
-    /// if the byte representation is too long. e.g. if the bytes
-    /// represent a value > Self::prime().
+    /// The `i`-th element of the field under the canonical bijection
+    /// between `[0, p)` and the field. Panics if `i >= p`, see
+    /// `try_nth_element`. Inverse of `element_index`.
+    fn nth_element(i: &BigUint) -> Self {
+        Self::try_nth_element(i)
+            .unwrap_or_else(|| panic!("index {i} is out of range for field {}", Self::name_str()))
+    }
+
+    /// The `i`-th element of the field, or `None` if `i >= p`.
+    fn try_nth_element(i: &BigUint) -> Option<Self> {
+        if i >= &Self::prime() {
+            return None;
+        }
+        Some(Self::from_biguint(i))
+    }
+
+    /// The index of the element in `[0, p)`, the inverse
+    /// of `nth_element`.
+    fn element_index(&self) -> BigUint {
+        self.to_biguint()
+    }
+
+    /// Parse an element from a byte representation of at most
+    /// `byte_len()` bytes. Values not less than the modulus are
+    /// reduced. Panics if the input is longer than `byte_len()`,
+    /// use `from_biguint` to reduce wide values.
     fn from_bytes_le(bytes: &[u8]) -> Self;
 
+    /// Decode little endian bytes without panicking. Inputs longer
+    /// than `byte_len()` and values not less than the modulus are
+    /// rejected instead of reduced.
+    fn try_from_bytes_le(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if bytes.len() > Self::byte_len() {
+            return Err(ParseError::TooLong {
+                len: bytes.len(),
+                max: Self::byte_len(),
+            });
+        }
+        let v = BigUint::from_bytes_le(bytes);
+        if v >= Self::prime() {
+            return Err(ParseError::NonCanonical);
+        }
+        Ok(Self::from_biguint(&v))
+    }
+
     /// Convert a field element to a byte representation.
-    /// The number of bytes may be variable, but is guaranteed
-    /// to be accepted by `from_bytes_le` for the same curve.
+    /// Always exactly `byte_len()` bytes: high zero bytes are kept
+    /// up to `byte_len()` and never beyond, so the length does not
+    /// depend on the value. Accepted by `from_bytes_le` for the same
+    /// field, which also accepts shorter (trimmed) inputs.
     fn to_bytes_le(&self) -> Vec<u8>;
 
+    /// `to_bytes_le` checked to be exactly `byte_len()` bytes, for
+    /// stable layouts e.g. when absorbing elements into a transcript.
+    /// Panics if an implementation returns a different length.
+    fn to_bytes_le_fixed(&self) -> Vec<u8> {
+        let bytes = self.to_bytes_le();
+        assert_eq!(
+            bytes.len(),
+            Self::byte_len(),
+            "{}: to_bytes_le returned {} bytes",
+            Self::name_str(),
+            bytes.len()
+        );
+        bytes
+    }
+
     /// A string representation of a field element using
     /// only the lower 60 bits of the element. A normal
     /// decimal representation will be given if it's shorter
@@ -188,6 +592,291 @@ pub trait FieldElement:
         }
     }
 
+    /// Format the decimal representation with grouped digits and
+    /// optionally elide the middle, see `display::DisplayOptions`.
+    /// Lossy when eliding.
+    fn display_with(&self, options: display::DisplayOptions) -> display::DisplayWith<'_, Self> {
+        display::DisplayWith::new(self, options)
+    }
+
+    /// The inversion strategy this field prefers. Fields backed by
+    /// a library with fast exponentiation prefer `Fermat`. Rings
+    /// with a composite modulus must use `Euclid`.
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Euclid
+    }
+
+    /// The multiplicative inverse using `inv_strategy`. Unlike `/`,
+    /// which panics with a backend specific message, this returns
+    /// `FieldError::DivisionByZero` for zero and
+    /// `FieldError::NotInvertible` for non-units of a ring.
+    fn inv(&self) -> Result<Self, FieldError> {
+        if self == &Self::zero() {
+            return Err(FieldError::DivisionByZero);
+        }
+        let inv = match Self::inv_strategy() {
+            InvStrategy::Fermat => self.inv_fermat(),
+            InvStrategy::Euclid => self.inv_euclid(),
+        };
+        inv.ok_or(FieldError::NotInvertible)
+    }
+
+    /// `self / other`, `None` if `other` is not invertible. Backends
+    /// disagree on division by zero (panics or garbage), generic code
+    /// should use this when the divisor may be zero.
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        other.inv().ok().map(|inv| self.clone() * inv)
+    }
+
+    /// Invert using Fermat's little theorem, `x^(p-2)`. Only correct
+    /// when the modulus is prime. Returns `None` for zero.
+    fn inv_fermat(&self) -> Option<Self> {
+        trace_span!("inv_fermat", field = Self::name_str());
+        if self == &Self::zero() {
+            return None;
+        }
+        Some(addition_chain::inverse_chain::<Self>().apply(self))
+    }
+
+    /// Invert using the extended euclidean algorithm on the integer
+    /// lift of the element. Returns `None` if the element shares a
+    /// factor with the modulus (e.g. zero).
+    ///
+    /// The integer lift is meaningless when the field isn't prime
+    /// order, these fields use `inv_fermat` instead.
+    fn inv_euclid(&self) -> Option<Self> {
+        trace_span!("inv_euclid", field = Self::name_str());
+        if Self::characteristic() != Self::prime() {
+            return self.inv_fermat();
+        }
+        let p = BigInt::from(Self::prime());
+        let gcd = BigInt::from(self.to_biguint()).extended_gcd(&p);
+        if gcd.gcd != BigInt::from(1) {
+            return None;
+        }
+        // x is in (-p, p), shift it into [0, p)
+        let inv = gcd.x.mod_floor(&p);
+        Some(Self::from_biguint(&inv.to_biguint().unwrap()))
+    }
+
+    /// Decompose the integer lift as `p^v * u` with `p` not dividing
+    /// `u`, returns `(v, u)`. In a ring modulo `p^k` the unit `u` is
+    /// invertible, see `hensel`. Zero returns `(k, 0)` where `k` is the
+    /// multiplicity of `p` in the modulus. Panics if `p < 2`.
+    fn p_adic_valuation(&self, p: u64) -> (u32, Self) {
+        assert!(p >= 2, "p-adic valuation requires p >= 2");
+        let p = BigUint::from(p);
+        let zero = BigUint::from(0_u32);
+        let mut u = self.to_biguint();
+        if u == zero {
+            u = Self::prime();
+        }
+        let mut v = 0;
+        loop {
+            let (q, r) = u.div_rem(&p);
+            if r != zero {
+                break;
+            }
+            u = q;
+            v += 1;
+        }
+        if self == &Self::zero() {
+            return (v, Self::zero());
+        }
+        (v, Self::from_biguint(&u))
+    }
+
+    /// Multiply by `2^k`. Fields may override this with a
+    /// cheaper implementation than a general multiplication.
+    fn mul_pow2(&self, k: u32) -> Self {
+        if k > 0 && Self::characteristic() == BigUint::from(2_u32) {
+            return Self::zero();
+        }
+        let two_pow = BigUint::from(2_u32).modpow(&BigUint::from(k), &Self::prime());
+        self.clone() * Self::from_biguint(&two_pow)
+    }
+
+    /// Divide by `2^k`. Panics if 2 is not invertible, e.g. in
+    /// a ring with an even modulus or characteristic 2.
+    fn div_pow2(&self, k: u32) -> Self {
+        if k > 0 && Self::characteristic() == BigUint::from(2_u32) {
+            panic!("2 is not invertible in characteristic 2");
+        }
+        let two_inv = Self::one() / (Self::one() + Self::one());
+        let two_inv_pow = two_inv
+            .to_biguint()
+            .modpow(&BigUint::from(k), &Self::prime());
+        self.clone() * Self::from_biguint(&two_inv_pow)
+    }
+
+    /// The largest `s` such that `2^s` divides `p - 1`, i.e. the
+    /// largest power of two length supported by a radix-2 NTT.
+    fn two_adicity() -> u32 {
+        (Self::prime() - 1_u32).trailing_zeros().unwrap_or(0) as u32
+    }
+
+    /// A primitive `2^two_adicity()`-th root of unity, the generator
+    /// of the largest power of two NTT domain. Its square is the root
+    /// for half the size, see `two_adic_generator`.
+    fn two_adic_root_of_unity() -> Self {
+        Self::two_adic_generator(Self::two_adicity())
+    }
+
+    /// A primitive `order`-th root of unity. Returns `None` if
+    /// `order` does not divide `p - 1` or, in a ring with a composite
+    /// modulus, no element of order `order` is found. Powers of two use
+    /// `two_adic_generator`, other orders search for an element
+    /// `c^((p-1)/order)` with exact order `order`.
+    fn root_of_unity(order: u64) -> Option<Self> {
+        let p_minus_one = Self::prime() - 1_u32;
+        if order == 0 || &p_minus_one % order != BigUint::from(0_u32) {
+            return None;
+        }
+        if order.is_power_of_two() {
+            return Some(Self::two_adic_generator(order.trailing_zeros()));
+        }
+        let mut prime_factors = vec![];
+        let mut rest = order;
+        let mut q = 2;
+        while q * q <= rest {
+            if rest.is_multiple_of(q) {
+                prime_factors.push(q);
+                while rest.is_multiple_of(q) {
+                    rest /= q;
+                }
+            }
+            q += 1;
+        }
+        if rest > 1 {
+            prime_factors.push(rest);
+        }
+        let exp = (p_minus_one / order).to_bytes_le();
+        // a random c works with probability phi(order)/order, bound
+        // the search so rings with a composite modulus terminate.
+        // Candidates come from the canonical bijection, not from the
+        // ring map `Self::from`, so extension fields see more than the
+        // prime subfield. In a ring with a composite modulus `c^((p-1)/order)`
+        // need not have order dividing `order`, check it explicitly.
+        let end = BigUint::from(2_u32 + (1 << 12)).min(Self::prime());
+        let mut i = BigUint::from(2_u32);
+        while i < end {
+            let root = Self::nth_element(&i).modpow_bytes(&exp);
+            i += 1_u32;
+            if root == Self::zero() || root.modpow_bytes(&order.to_le_bytes()) != Self::one() {
+                continue;
+            }
+            if prime_factors
+                .iter()
+                .all(|q| root.modpow_bytes(&(order / q).to_le_bytes()) != Self::one())
+            {
+                return Some(root);
+            }
+        }
+        None
+    }
+
+    /// A generator of the multiplicative group, an element of order
+    /// `p - 1`. The default factors `p - 1` with
+    /// `functions::prime_factors` and returns the first `nth_element(i)`,
+    /// `i >= 2`, whose order is not a proper divisor. Fields with a
+    /// hard to factor `p - 1` should override this with a constant.
+    /// Panics if `p - 1` can't be factored or there is no generator,
+    /// e.g. for a composite modulus.
+    fn generator() -> Self {
+        let p_minus_one = Self::prime() - 1_u32;
+        let factors = functions::prime_factors(&p_minus_one).unwrap_or_else(|| {
+            panic!(
+                "could not factor the group order of {}, override FieldElement::generator",
+                Self::name_str()
+            )
+        });
+        let order = p_minus_one.to_bytes_le();
+        let cofactors = factors
+            .iter()
+            .map(|q| (&p_minus_one / q).to_bytes_le())
+            .collect::<Vec<_>>();
+        // enumerate with the bijection, `Self::from` only reaches the
+        // prime subfield of an extension field
+        let end = BigUint::from(2_u32 + (1 << 12)).min(Self::prime());
+        let mut i = BigUint::from(2_u32);
+        while i < end {
+            let c = Self::nth_element(&i);
+            i += 1_u32;
+            if c.modpow_bytes(&order) == Self::one()
+                && cofactors.iter().all(|e| c.modpow_bytes(e) != Self::one())
+            {
+                return c;
+            }
+        }
+        panic!("{} has no multiplicative generator", Self::name_str());
+    }
+
+    /// The primitive `order`-th root of unity `g^((p-1)/order)` where
+    /// `g = generator()`. Returns `None` if `order` does not divide
+    /// `p - 1`. Roots of different orders are powers of each other,
+    /// `primitive_root_of_unity(a * b)^a == primitive_root_of_unity(b)`.
+    /// `root_of_unity` instead agrees with `two_adic_generator`.
+    fn primitive_root_of_unity(order: u64) -> Option<Self> {
+        let p_minus_one = Self::prime() - 1_u32;
+        if order == 0 || &p_minus_one % order != BigUint::from(0_u32) {
+            return None;
+        }
+        Some(Self::generator().pow(&(p_minus_one / order)))
+    }
+
+    /// Return a generator of the multiplicative subgroup of
+    /// order `2^k`. Panics if `2^k` does not divide `p - 1`.
+    ///
+    /// The default implementation searches for a non-residue
+    /// `c` and squares `c^((p-1)/2^s)` down the chain, where `s`
+    /// is the two-adicity of `p - 1`. Fields should override this
+    /// with a precomputed root.
+    fn two_adic_generator(k: u32) -> Self {
+        let p_minus_one = Self::prime() - 1_u32;
+        let two_adicity = Self::two_adicity();
+        if k > two_adicity {
+            panic!(
+                "field {} does not contain a subgroup of order 2^{k}",
+                Self::name_str()
+            );
+        }
+        if k == 0 {
+            return Self::one();
+        }
+        let mut c = Self::one() + Self::one();
+        while c.legendre() != -1 {
+            c += Self::one();
+        }
+        let exp = p_minus_one >> two_adicity;
+        let mut g = c.pow(&exp);
+        for _ in k..two_adicity {
+            g = g.clone() * g;
+        }
+        g
+    }
+
+    /// Given a generator `g` of a domain of size `n`, return the
+    /// generator of the folded domain of size `n/2`, i.e. `g^2`.
+    /// See `domain::EvaluationDomain::halve`.
+    fn halve_domain_generator(g: &Self) -> Self {
+        g.clone() * g.clone()
+    }
+
+    /// Whether `x -> x^k` is a permutation of the field, i.e. no
+    /// prime factor of `p - 1` divides `k`. Used to choose S-box
+    /// exponents for algebraic hashes like MiMC and Poseidon.
+    fn is_permutation_exponent(k: u64) -> bool {
+        (Self::prime() - 1_u32).gcd(&BigUint::from(k)) == BigUint::from(1_u32)
+    }
+
+    /// The smallest `k > 1` such that `x -> x^k` is a permutation
+    /// of the field. e.g. 5 for alt_bn128 and 7 for oxfoi.
+    fn smallest_permutation_exponent() -> u64 {
+        (2_u64..)
+            .find(|k| Self::is_permutation_exponent(*k))
+            .unwrap()
+    }
+
     /// Take a logarithm using a custom base and return the
     /// floored value. `O(logb(n))` time complexity where `n`
     /// is the size of the element.
@@ -211,86 +900,138 @@ pub trait FieldElement:
         unreachable!();
     }
 
+    /// Raise to the power of a little endian encoded exponent, e.g.
+    /// from `BigUint::to_bytes_le`. The base is never converted to a
+    /// `BigUint`. The default uses square and multiply, backends
+    /// override this with their native exponentiation.
+    fn modpow_bytes(&self, exp_le: &[u8]) -> Self {
+        let mut out = Self::one();
+        for byte in exp_le.iter().rev() {
+            for bit in (0..8).rev() {
+                out = out.clone() * out;
+                if (byte >> bit) & 1 == 1 {
+                    out *= self.clone();
+                }
+            }
+        }
+        out
+    }
+
+    /// Raise to the power `exp`. Uses `modpow_bytes`, so backends
+    /// with native exponentiation are used automatically.
+    fn pow(&self, exp: &BigUint) -> Self {
+        self.modpow_bytes(&exp.to_bytes_le())
+    }
+
+    /// Raise to the power of a `u64` exponent with square and multiply.
+    fn pow_u64(&self, mut exp: u64) -> Self {
+        let mut out = Self::one();
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out *= base.clone();
+            }
+            base = base.clone() * base;
+            exp >>= 1;
+        }
+        out
+    }
+
     /// Calculate the [legendre symbol](https://en.wikipedia.org/wiki/Legendre_symbol#Definition)
     /// for a field element. Used to determine if the
     /// element is a quadratic residue.
+    ///
+    /// In characteristic 2 every element is a square.
     fn legendre(&self) -> i32 {
         if self == &Self::zero() {
             return 0;
         }
-        let neg_one = Self::prime() - 1_u32;
-        let one = BigUint::from(1_u32);
-        let e = (-Self::one()) / (Self::one() + Self::one());
-        let e_bigint = BigUint::from_str(&e.serialize()).unwrap();
-        let a = BigUint::from_str(&self.serialize()).unwrap();
-        let l = a.modpow(&e_bigint, &Self::prime());
-        if l == neg_one {
-            -1
-        } else if l == one {
+        if Self::characteristic() == BigUint::from(2_u32) {
             return 1;
+        }
+        let e: BigUint = (Self::prime() - 1_u32) >> 1;
+        let l = self.pow(&e);
+        if l == -Self::one() {
+            -1
+        } else if l == Self::one() {
+            1
         } else {
             panic!("legendre symbol is not 1, -1, or 0");
         }
     }
 
-    /// [Kumar 08](https://arxiv.org/pdf/2008.11814v4) prime field square root implementation.
-    /// Always returns the smaller root e.g. the positive root.
+    /// Square root, always returns the smaller root e.g. the positive
+    /// root. Panics if the element is not a square, see `checked_sqrt`.
+    ///
+    /// The smaller root is the one with the smaller `to_biguint()`
+    /// lift:
+    ///   - prime fields (every backend, `scalar_ring!`, `mont_field!`)
+    ///     - the root in `[0, (p-1)/2]`
+    ///   - extension fields - the smaller `element_index`, i.e. the
+    ///     coefficients compared from the highest degree down
+    ///   - characteristic 2 - the root is unique, `x = -x`
     fn sqrt(&self) -> Self {
+        self.checked_sqrt()
+            .expect("legendre symbol is not 1: root does not exist or input is 0")
+    }
+
+    /// Square root, `None` if the element is not a square. Returns the
+    /// smaller root like `sqrt`. Non-residues are detected by the root
+    /// computation itself, don't call `legendre` first.
+    ///
+    /// Uses an addition chain when `p = 3 mod 4`, Atkin's method when
+    /// `p = 5 mod 8` and Tonelli–Shanks otherwise, see `sqrt`. In
+    /// characteristic 2 the root is unique, `x^(q/2)` for a field of
+    /// order `q`. Backends with native square roots override this.
+    fn checked_sqrt(&self) -> Option<Self> {
+        trace_span!("sqrt", field = Self::name_str());
         if self == &Self::zero() {
-            return Self::zero();
+            return Some(Self::zero());
         }
-        if self.legendre() != 1 {
-            panic!("legendre symbol is not 1: root does not exist or input is 0");
+        if Self::characteristic() == BigUint::from(2_u32) {
+            return Some(self.pow(&(Self::prime() >> 1)));
         }
-        // find a non-residue
-        let mut x = Self::one() + Self::one();
-        let non_residue;
-        loop {
-            if x.legendre() == -1 {
-                non_residue = x.clone();
-                break;
-            }
-            x += Self::one();
-        }
-        let b = BigUint::from_str(&non_residue.serialize()).unwrap();
-
-        let a = BigUint::from_str(&self.serialize()).unwrap();
-        let two = Self::one() + Self::one();
-        let m = (-Self::one()) / two.clone();
-        let mut apow = -Self::one();
-        let mut bpow = Self::zero();
-        while BigUint::from_str(&apow.serialize()).unwrap().is_even() {
-            apow = apow / two.clone();
-            bpow = bpow / two.clone();
-            let a_ = a.modpow(
-                &BigUint::from_str(&apow.serialize()).unwrap(),
-                &Self::prime(),
-            );
-            let b_ = b.modpow(
-                &BigUint::from_str(&bpow.serialize()).unwrap(),
-                &Self::prime(),
-            );
-            if (a_ * b_) % Self::prime() == Self::prime() - 1_u32 {
-                bpow += m.clone();
-            }
+        sqrt::sqrt(self).map(sqrt::canonical_root)
+    }
+
+    /// Both square roots `(low, high)` with `low` the smaller root
+    /// returned by `sqrt` and `high = -low`, `None` if the element is
+    /// not a square. In characteristic 2 and for zero the roots are
+    /// equal.
+    fn sqrt_pair(&self) -> Option<(Self, Self)> {
+        let low = self.checked_sqrt()?;
+        let high = -low.clone();
+        Some((low, high))
+    }
+
+    /// `sqrt` with an explicit generic algorithm, ignoring any
+    /// backend or `p = 3 mod 4` shortcut. Returns the smaller root.
+    /// Panics if the element is not a square. See `sqrt::SqrtAlgorithm`.
+    fn sqrt_with(&self, algorithm: sqrt::SqrtAlgorithm) -> Self {
+        trace_span!("sqrt_with", field = Self::name_str());
+        if self == &Self::zero() {
+            return Self::zero();
         }
-        apow = (apow + Self::one()) / two.clone();
-        bpow = bpow / two;
-        let a_ = a.modpow(
-            &BigUint::from_str(&apow.serialize()).unwrap(),
-            &Self::prime(),
-        );
-        let b_ = b.modpow(
-            &BigUint::from_str(&bpow.serialize()).unwrap(),
-            &Self::prime(),
-        );
-        let root = (a_ * b_) % Self::prime();
-        let other_root = Self::prime() - root.clone();
-        if root > other_root {
-            Self::from_biguint(&other_root)
-        } else {
-            Self::from_biguint(&root)
+        if Self::characteristic() == BigUint::from(2_u32) {
+            return self.pow(&(Self::prime() >> 1));
         }
+        let root = match algorithm {
+            sqrt::SqrtAlgorithm::TonelliShanks => sqrt::tonelli_shanks(self),
+            sqrt::SqrtAlgorithm::Cipolla => sqrt::cipolla(self),
+            sqrt::SqrtAlgorithm::CrossCheck => {
+                let a = sqrt::tonelli_shanks(self).map(sqrt::canonical_root);
+                let b = sqrt::cipolla(self).map(sqrt::canonical_root);
+                if a != b {
+                    panic!(
+                        "sqrt algorithms disagree in {}: tonelli-shanks {a:?}, cipolla {b:?}",
+                        Self::name_str()
+                    );
+                }
+                a
+            }
+        };
+        let root = root.expect("legendre symbol is not 1: root does not exist or input is 0");
+        sqrt::canonical_root(root)
     }
 }
 
@@ -304,35 +1045,848 @@ mod tests {
             let square = x.clone() * x.clone();
             let root = square.sqrt();
             assert_eq!(square, root.clone() * root.clone());
+            assert_eq!(square.checked_sqrt(), Some(root.clone()));
+            let (low, high) = square.sqrt_pair().unwrap();
+            assert_eq!(low, root);
+            assert_eq!(high.clone() * high.clone(), square);
+            assert!(low.to_biguint() <= high.to_biguint());
+            // non-residues have no root instead of panicking
+            assert_eq!(x.checked_sqrt().is_some(), x.legendre() != -1);
             x += T::one();
         }
     }
 
+    #[allow(dead_code)]
+    fn test_inv<T: FieldElement>() {
+        assert_eq!(T::zero().inv_fermat(), None);
+        assert_eq!(T::zero().inv_euclid(), None);
+        assert_eq!(T::zero().inv(), Err(FieldError::DivisionByZero));
+        assert_eq!(T::one().inv(), Ok(T::one()));
+        assert_eq!(T::one().checked_div(&T::zero()), None);
+        assert_eq!(T::zero().checked_div(&T::zero()), None);
+        assert_eq!(T::from(6).checked_div(&T::from(3)), Some(T::from(2)));
+        let mut x = -T::from(1000);
+        for _ in 1..1000 {
+            let inv = x.inv_euclid().unwrap();
+            assert_eq!(x.inv_fermat().unwrap(), inv);
+            assert_eq!(x.clone() * inv, T::one());
+            x += T::one();
+        }
+    }
+
+    fn test_parse<T: FieldElement>() {
+        for invalid in ["", "abc", "-1", "1.0", " 1", "0x10"] {
+            assert!(T::from_str(invalid).is_err(), "{invalid:?} parsed");
+        }
+        assert!(T::from_str("007").ok() == Some(T::from(7)));
+        assert!(T::from_str("000").ok() == Some(T::zero()));
+        // values are reduced mod p
+        assert!(T::from_str(&T::prime().to_string()).ok() == Some(T::zero()));
+    }
+
+    fn test_canonical_hash<T: FieldElement>() {
+        use std::hash::BuildHasher;
+        let hasher = std::collections::hash_map::RandomState::new();
+        let p = T::prime();
+        for v in [0_u64, 1, 7, 1 << 40] {
+            let x = T::from(v);
+            let constructed = [
+                T::from_str(&v.to_string()).ok().unwrap(),
+                T::deserialize(&x.serialize()),
+                T::from_bytes_le(&x.to_bytes_le()),
+                T::from_biguint(&(BigUint::from(v) + &p)),
+                (x.clone() + T::one()) - T::one(),
+                -(-x.clone()),
+            ];
+            for y in constructed {
+                assert_eq!(x, y);
+                assert_eq!(x.canonical_hash_u64(), y.canonical_hash_u64());
+                assert_eq!(hasher.hash_one(&x), hasher.hash_one(&y));
+            }
+        }
+    }
+
+    fn test_pow2<T: FieldElement>() {
+        let p = T::prime();
+        let x = -T::from(12345);
+        for k in (0..100).chain(180..200).chain([1000, u32::MAX]) {
+            let two_pow = BigUint::from(2_u32).modpow(&BigUint::from(k), &p);
+            let expected = x.clone() * T::from_biguint(&two_pow);
+            assert_eq!(x.mul_pow2(k), expected);
+            assert_eq!(expected.div_pow2(k), x);
+        }
+    }
+
+    fn test_two_adic_generator<T: FieldElement>(two_adicity: u32) {
+        assert_eq!(T::two_adicity(), two_adicity);
+        assert_eq!(
+            T::two_adic_root_of_unity(),
+            T::two_adic_generator(two_adicity)
+        );
+        assert_eq!(T::two_adic_generator(0), T::one());
+        for k in 1..=two_adicity {
+            let g = T::two_adic_generator(k);
+            // each generator is the square root of the previous one
+            assert_eq!(g.clone() * g.clone(), T::two_adic_generator(k - 1));
+            let mut x = g;
+            for _ in 1..k {
+                x = x.clone() * x;
+            }
+            assert_eq!(x, -T::one());
+        }
+        let result = std::panic::catch_unwind(|| T::two_adic_generator(two_adicity + 1));
+        assert!(result.is_err());
+    }
+
     scalar_ring!(F13FieldElement, 13, "f13");
+    scalar_ring!(Z12RingElement, 12, "z12");
+    // p = 3 mod 4
+    scalar_ring!(F19FieldElement, 19, "f19");
+
+    #[test]
+    fn two_adic_generator_scalar_ring() {
+        test_two_adic_generator::<F13FieldElement>(2);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn two_adic_generator_foi() {
+        test_two_adic_generator::<OxfoiFieldElement>(32);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn two_adic_generator_bn128() {
+        test_two_adic_generator::<Bn128FieldElement>(28);
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn two_adic_generator_curve25519() {
+        test_two_adic_generator::<Curve25519FieldElement>(2);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans() {
+        use std::sync::Mutex;
+
+        // records the names of created spans
+        struct Recorder(Mutex<Vec<&'static str>>);
+
+        impl tracing::Subscriber for Recorder {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                tracing::span::Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+            fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+            fn event(&self, _: &tracing::Event<'_>) {}
+            fn enter(&self, _: &tracing::span::Id) {}
+            fn exit(&self, _: &tracing::span::Id) {}
+        }
+
+        let recorder = std::sync::Arc::new(Recorder(Mutex::new(vec![])));
+        tracing::subscriber::with_default(recorder.clone(), || {
+            F13FieldElement::from(4).sqrt();
+            F13FieldElement::from(4).inv_euclid();
+            let mut values = vec![F13FieldElement::one(); 4];
+            ntt::ntt_dit(&mut values, &F13FieldElement::two_adic_generator(2));
+        });
+        let names = recorder.0.lock().unwrap();
+        for name in ["sqrt", "inv_euclid", "ntt_dit"] {
+            assert!(names.contains(&name), "missing span {name}");
+        }
+    }
+
+    fn test_canonicalize<T: FieldElement>() {
+        let p = T::prime();
+        for v in [T::zero(), -T::one(), T::from(7) / T::from(3)] {
+            // the same value reached through a different computation
+            let mut other = T::from_biguint(&(v.to_biguint() + &p));
+            other.canonicalize();
+            let mut v_ = v.clone();
+            v_.canonicalize();
+            assert_eq!(v_, v);
+            assert_eq!(other, v);
+            assert_eq!(other.to_bytes_le(), v.to_bytes_le());
+            assert_eq!(other.canonical_hash_u64(), v.canonical_hash_u64());
+        }
+    }
+
+    #[test]
+    fn canonicalize_fields() {
+        test_canonicalize::<F13FieldElement>();
+        #[cfg(feature = "alt_bn128")]
+        test_canonicalize::<Bn128FieldElement>();
+        #[cfg(feature = "curve25519")]
+        test_canonicalize::<Curve25519FieldElement>();
+        #[cfg(feature = "oxfoi")]
+        test_canonicalize::<OxfoiFieldElement>();
+    }
+
+    #[test]
+    fn backend_names() {
+        assert_eq!(F13FieldElement::backend_str(), "native-u128");
+        #[cfg(feature = "alt_bn128")]
+        assert_eq!(Bn128FieldElement::backend_str(), "ark-bn254 0.4");
+        #[cfg(feature = "curve25519")]
+        assert_eq!(Curve25519FieldElement::backend_str(), "curve25519-dalek 4");
+        #[cfg(feature = "pallas")]
+        assert_eq!(PallasFieldElement::backend_str(), "pasta_curves 0.5");
+        #[cfg(feature = "oxfoi")]
+        assert!(
+            OxfoiFieldElement::backend_str().starts_with("twenty-first")
+                || OxfoiFieldElement::backend_str() == "native-u128"
+        );
+    }
+
+    #[test]
+    fn permutation_exponents() {
+        // p - 1 = 12
+        assert_eq!(F13FieldElement::smallest_permutation_exponent(), 5);
+        assert!(!F13FieldElement::is_permutation_exponent(3));
+        assert!(F13FieldElement::is_permutation_exponent(7));
+        assert!(!F13FieldElement::is_permutation_exponent(0));
+        // x^5 is a bijection, x^3 is not
+        let cubes = (0..13)
+            .map(|i| {
+                (F13FieldElement::from(i) * F13FieldElement::from(i)) * F13FieldElement::from(i)
+            })
+            .collect::<std::collections::HashSet<_>>();
+        assert!(cubes.len() < 13);
+        #[cfg(feature = "alt_bn128")]
+        assert_eq!(Bn128FieldElement::smallest_permutation_exponent(), 5);
+        #[cfg(feature = "curve25519")]
+        assert_eq!(Curve25519FieldElement::smallest_permutation_exponent(), 5);
+        #[cfg(feature = "oxfoi")]
+        assert_eq!(OxfoiFieldElement::smallest_permutation_exponent(), 7);
+    }
+
+    #[test]
+    fn characteristic() {
+        scalar_ring!(F2FieldElement, 2_u128, "f2");
+        assert_eq!(F13FieldElement::characteristic(), F13FieldElement::prime());
+        assert_eq!(F2FieldElement::characteristic(), BigUint::from(2_u32));
+        let one = F2FieldElement::one();
+        assert_eq!(one.sqrt(), one);
+        assert_eq!(one.legendre(), 1);
+        assert_eq!(one.mul_pow2(1), F2FieldElement::zero());
+        #[cfg(feature = "oxfoi")]
+        assert_eq!(
+            OxfoiFieldElement::characteristic(),
+            OxfoiFieldElement::prime()
+        );
+    }
+
+    fn test_modpow_bytes<T: FieldElement>() {
+        let p = T::prime();
+        let x = -T::from(31337);
+        let exponents = [0_u32, 1, 2, 255, 65537]
+            .map(BigUint::from)
+            .into_iter()
+            .chain([&p - 1_u32, &p - 2_u32, &p * &p + 7_u32]);
+        for e in exponents {
+            let expected = T::from_biguint(&x.to_biguint().modpow(&e, &p));
+            assert_eq!(x.modpow_bytes(&e.to_bytes_le()), expected, "exponent {e}");
+            // trailing zero bytes don't change the exponent
+            let mut padded = e.to_bytes_le();
+            padded.resize(padded.len() + 9, 0);
+            assert_eq!(x.modpow_bytes(&padded), expected);
+            assert_eq!(x.pow(&e), expected);
+            if let Ok(e) = u64::try_from(&e) {
+                assert_eq!(x.pow_u64(e), expected);
+            }
+        }
+        assert_eq!(x.pow_u64(u64::MAX), x.pow(&BigUint::from(u64::MAX)));
+        assert_eq!(T::zero().pow_u64(0), T::one());
+        assert_eq!(T::zero().modpow_bytes(&[]), T::one());
+        assert_eq!(T::zero().modpow_bytes(&(&p * &p).to_bytes_le()), T::zero());
+    }
+
+    #[test]
+    fn modpow_bytes_fields() {
+        test_modpow_bytes::<F13FieldElement>();
+        test_modpow_bytes::<F19FieldElement>();
+        #[cfg(feature = "alt_bn128")]
+        test_modpow_bytes::<Bn128FieldElement>();
+        #[cfg(feature = "curve25519")]
+        test_modpow_bytes::<Curve25519FieldElement>();
+        #[cfg(feature = "oxfoi")]
+        test_modpow_bytes::<OxfoiFieldElement>();
+    }
+
+    fn test_root_of_unity<T: FieldElement>(orders: &[u64]) {
+        assert_eq!(
+            T::two_adicity(),
+            (T::prime() - 1_u32).trailing_zeros().unwrap() as u32
+        );
+        for order in orders {
+            let w = T::root_of_unity(*order).unwrap();
+            assert_eq!(w.modpow_bytes(&order.to_le_bytes()), T::one());
+            // primitive, no smaller power is one
+            let mut x = w.clone();
+            for _ in 1..(*order).min(1000) {
+                assert_ne!(x, T::one());
+                x *= w.clone();
+            }
+        }
+        assert!(T::root_of_unity(0).is_none());
+    }
+
+    #[test]
+    fn root_of_unity_fields() {
+        test_root_of_unity::<F13FieldElement>(&[1, 2, 3, 4, 6, 12]);
+        assert!(F13FieldElement::root_of_unity(5).is_none());
+        assert!(F13FieldElement::root_of_unity(24).is_none());
+        // 4^7 = 4 in Z15, no element has order 7
+        scalar_ring!(Z15RingElement, 15, "z15");
+        assert!(Z15RingElement::root_of_unity(7).is_none());
+        quadratic_extension!(F169, F169Params, F13FieldElement, 2, "f13_2");
+        test_root_of_unity::<F169>(&[3, 7, 21, 168]);
+        #[cfg(feature = "alt_bn128")]
+        test_root_of_unity::<Bn128FieldElement>(&[3, 13, 3 * (1 << 20), 983]);
+        #[cfg(feature = "curve25519")]
+        test_root_of_unity::<Curve25519FieldElement>(&[3, 4, 11]);
+        #[cfg(feature = "oxfoi")]
+        test_root_of_unity::<OxfoiFieldElement>(&[3, 5, 17, 257, 65537, 3 << 30]);
+    }
+
+    /// `factors` are the distinct prime factors of `p - 1`.
+    fn test_generator<T: FieldElement>(factors: &[&str], orders: &[u64]) {
+        let p_minus_one = T::prime() - 1_u32;
+        let g = T::generator();
+        assert_eq!(g.pow(&p_minus_one), T::one());
+        let mut rest = p_minus_one.clone();
+        for q in factors {
+            let q = BigUint::from_str(q).unwrap();
+            assert_ne!(g.pow(&(&p_minus_one / &q)), T::one(), "{q}");
+            while &rest % &q == BigUint::from(0_u32) {
+                rest /= &q;
+            }
+        }
+        assert_eq!(rest, BigUint::from(1_u32), "incomplete factorization");
+        for order in orders {
+            let w = T::primitive_root_of_unity(*order).unwrap();
+            assert_eq!(w.modpow_bytes(&order.to_le_bytes()), T::one());
+            for q in functions::prime_factors(&BigUint::from(*order)).unwrap() {
+                let e = BigUint::from(*order) / q;
+                assert_ne!(w.pow(&e), T::one());
+            }
+            // w^2 is the root of half the order
+            if order % 2 == 0 {
+                assert_eq!(T::primitive_root_of_unity(order / 2), Some(w.clone() * w));
+            }
+        }
+        assert!(T::primitive_root_of_unity(0).is_none());
+    }
+
+    #[test]
+    fn generators() {
+        test_generator::<F13FieldElement>(&["2", "3"], &[1, 2, 3, 4, 6, 12]);
+        assert_eq!(F13FieldElement::generator(), F13FieldElement::from(2));
+        assert!(F13FieldElement::primitive_root_of_unity(5).is_none());
+        scalar_ring!(F65537FieldElement, 65537_u128, "f65537");
+        test_generator::<F65537FieldElement>(&["2"], &[1 << 16, 1 << 10]);
+        assert_eq!(F65537FieldElement::generator(), F65537FieldElement::from(3));
+        // 169 - 1 = 2^3 * 3 * 7
+        quadratic_extension!(F169, F169Params, F13FieldElement, 2, "f13_2");
+        test_generator::<F169>(&["2", "3", "7"], &[168, 56, 21, 7]);
+        // 13^3 - 1 = 2^2 * 3^2 * 61
+        extension_field!(F2197, F2197Params, F13FieldElement, 3, [2, 0, 0], "f13_3");
+        test_generator::<F2197>(&["2", "3", "61"], &[2196, 549, 61, 12]);
+        #[cfg(feature = "alt_bn128")]
+        test_generator::<Bn128FieldElement>(
+            &[
+                "2",
+                "3",
+                "13",
+                "29",
+                "983",
+                "11003",
+                "237073",
+                "405928799",
+                "1670836401704629",
+                "13818364434197438864469338081",
+            ],
+            &[3 * (1 << 20), 13 * 29, 1 << 28],
+        );
+        #[cfg(feature = "curve25519")]
+        test_generator::<Curve25519FieldElement>(
+            &[
+                "2",
+                "3",
+                "11",
+                "198211423230930754013084525763697",
+                "276602624281642239937218680557139826668747",
+            ],
+            &[4, 12, 33],
+        );
+        #[cfg(feature = "oxfoi")]
+        test_generator::<OxfoiFieldElement>(
+            &["2", "3", "5", "17", "257", "65537"],
+            &[3 << 30, 65537 * 17, 257 * 5],
+        );
+    }
+
+    fn test_fallible_parse<T: FieldElement>() {
+        let p = T::prime();
+        let neg_one = -T::one();
+        assert_eq!(
+            T::try_deserialize(&neg_one.serialize()),
+            Ok(neg_one.clone())
+        );
+        assert_eq!(T::try_deserialize("0007"), Ok(T::from(7)));
+        assert_eq!(T::try_deserialize(""), Err(ParseError::Empty));
+        assert_eq!(
+            T::try_deserialize("12a"),
+            Err(ParseError::InvalidDigit {
+                position: 2,
+                character: 'a'
+            })
+        );
+        assert!(T::try_deserialize("-1").is_err());
+        assert!(T::try_deserialize(" 1").is_err());
+        assert!(T::try_deserialize("+7").is_err());
+        assert!(T::from_str("+7").is_err());
+        assert!(T::from_str("7").ok() == Some(T::from(7)));
+        assert_eq!(
+            T::try_deserialize(&p.to_string()),
+            Err(ParseError::NonCanonical)
+        );
+        assert_eq!(T::try_from_bytes_le(&neg_one.to_bytes_le()), Ok(neg_one));
+        let mut padded = T::from(9).to_bytes_le();
+        padded.resize(100, 0);
+        assert_eq!(
+            T::try_from_bytes_le(&padded),
+            Err(ParseError::TooLong {
+                len: 100,
+                max: T::byte_len()
+            })
+        );
+        assert_eq!(T::try_from_bytes_le(&[9]), Ok(T::from(9)));
+        assert_eq!(T::try_from_bytes_le(&[]), Err(ParseError::Empty));
+        assert_eq!(
+            T::try_from_bytes_le(&p.to_bytes_le()),
+            Err(ParseError::NonCanonical)
+        );
+        assert_eq!(
+            T::try_from_bytes_le(&vec![0xff; T::byte_len()]),
+            Err(ParseError::NonCanonical)
+        );
+    }
+
+    #[test]
+    fn fallible_parse_fields() {
+        test_fallible_parse::<F13FieldElement>();
+        #[cfg(feature = "alt_bn128")]
+        test_fallible_parse::<Bn128FieldElement>();
+        #[cfg(feature = "curve25519")]
+        test_fallible_parse::<Curve25519FieldElement>();
+        #[cfg(feature = "oxfoi")]
+        test_fallible_parse::<OxfoiFieldElement>();
+    }
+
+    fn test_nth_element<T: FieldElement>() {
+        let p = T::prime();
+        for i in [
+            BigUint::from(0_u32),
+            BigUint::from(1_u32),
+            BigUint::from(u64::MAX) % &p,
+            &p - 1_u32,
+        ] {
+            let v = T::nth_element(&i);
+            assert_eq!(v.element_index(), i);
+            assert_eq!(T::try_nth_element(&i), Some(v));
+        }
+        assert_eq!(T::nth_element(&(&p - 1_u32)), -T::one());
+        assert!(T::try_nth_element(&p).is_none());
+        assert!(T::try_nth_element(&(&p + 1_u32)).is_none());
+    }
+
+    #[test]
+    fn nth_element_scalar_ring() {
+        test_nth_element::<F13FieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn nth_element_bn128() {
+        test_nth_element::<Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn nth_element_curve25519() {
+        test_nth_element::<Curve25519FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn nth_element_foi() {
+        test_nth_element::<OxfoiFieldElement>();
+    }
+
+    #[test]
+    fn field_size_hints() {
+        assert!(F13FieldElement::is_small_field());
+        assert!(F13FieldElement::fits_in_u64());
+        #[cfg(feature = "oxfoi")]
+        assert!(OxfoiFieldElement::is_small_field() && OxfoiFieldElement::fits_in_u64());
+        #[cfg(feature = "alt_bn128")]
+        assert!(!Bn128FieldElement::is_small_field() && !Bn128FieldElement::fits_in_u64());
+        #[cfg(feature = "curve25519")]
+        assert!(!Curve25519FieldElement::is_small_field());
+    }
+
+    fn test_byte_len<T: FieldElement>() {
+        let p = T::prime();
+        assert_eq!(
+            T::byte_len(),
+            (p.clone() - 1_u32).bits().div_ceil(8).max(1) as usize,
+            "{}",
+            T::name_str()
+        );
+        for v in [
+            BigUint::from(0_u32),
+            BigUint::from(1_u32),
+            p.clone() >> 1,
+            p.clone() - 1_u32,
+        ] {
+            let x = T::from_biguint(&v);
+            let bytes = x.to_bytes_le();
+            assert_eq!(bytes.len(), T::byte_len(), "{}", T::name_str());
+            assert_eq!(x.to_bytes_le_fixed(), bytes);
+            assert_eq!(T::from_bytes_le(&bytes), x);
+        }
+        // one byte too many is rejected, even if it is zero
+        let oversized = vec![0_u8; T::byte_len() + 1];
+        let result = std::panic::catch_unwind(|| T::from_bytes_le(&oversized));
+        assert!(result.is_err(), "{}", T::name_str());
+        assert_eq!(
+            T::try_from_bytes_le(&oversized),
+            Err(ParseError::TooLong {
+                len: T::byte_len() + 1,
+                max: T::byte_len()
+            })
+        );
+    }
+
+    fn test_fixed_byte_len<T: FixedByteLen>() {
+        assert_eq!(
+            T::BYTE_LEN,
+            (T::prime() - 1_u32).bits().div_ceil(8).max(1) as usize,
+            "{}",
+            T::name_str()
+        );
+        assert_eq!(T::byte_len(), T::BYTE_LEN);
+        test_byte_len::<T>();
+    }
+
+    #[test]
+    fn modulus_byte_lengths() {
+        assert_eq!(modulus_byte_len(&[2]), 1);
+        assert_eq!(modulus_byte_len(&[256]), 1);
+        assert_eq!(modulus_byte_len(&[257]), 2);
+        assert_eq!(modulus_byte_len(&[0, 1]), 8);
+        assert_eq!(modulus_byte_len(&[1, 1]), 9);
+        assert_eq!(modulus_byte_len(&[0xffffffff00000001]), 8);
+    }
+
+    #[test]
+    fn byte_lengths() {
+        scalar_ring!(Z2RingElement, 2, "z2");
+        scalar_ring!(Z256RingElement, 256, "z256");
+        scalar_ring!(Z257RingElement, 257, "z257");
+        test_fixed_byte_len::<Z2RingElement>();
+        test_fixed_byte_len::<Z256RingElement>();
+        test_fixed_byte_len::<Z257RingElement>();
+        assert_eq!(Z257RingElement::byte_len(), 2);
+        test_fixed_byte_len::<F13FieldElement>();
+        test_fixed_byte_len::<Z12RingElement>();
+        test_fixed_byte_len::<FingerprintElement>();
+        mont_field!(F1009Mont, F1009MontParams, 1, [1009], "f1009-mont");
+        test_fixed_byte_len::<F1009Mont>();
+        quadratic_extension!(F169, F169Params, F13FieldElement, 2, "f13_2");
+        test_byte_len::<F169>();
+        extension_field!(
+            F13Cubed,
+            F13CubedParams,
+            F13FieldElement,
+            3,
+            [2, 0, 0],
+            "f13_3"
+        );
+        test_byte_len::<F13Cubed>();
+        #[cfg(feature = "alt_bn128")]
+        test_fixed_byte_len::<Bn128FieldElement>();
+        #[cfg(feature = "curve25519")]
+        test_fixed_byte_len::<Curve25519FieldElement>();
+        #[cfg(feature = "oxfoi")]
+        {
+            test_fixed_byte_len::<OxfoiFieldElement>();
+            test_byte_len::<OxfoiQuadraticFieldElement>();
+            test_byte_len::<OxfoiCubicFieldElement>();
+        }
+        #[cfg(feature = "babybear")]
+        test_fixed_byte_len::<BabyBearFieldElement>();
+        #[cfg(feature = "binary_field")]
+        {
+            test_fixed_byte_len::<Binary8FieldElement>();
+            test_fixed_byte_len::<Binary128FieldElement>();
+        }
+        #[cfg(feature = "mersenne")]
+        {
+            test_fixed_byte_len::<Mersenne31FieldElement>();
+            test_fixed_byte_len::<Mersenne61FieldElement>();
+            test_fixed_byte_len::<Mersenne127FieldElement>();
+        }
+        #[cfg(feature = "pallas")]
+        test_fixed_byte_len::<PallasFieldElement>();
+        #[cfg(feature = "vesta")]
+        test_fixed_byte_len::<VestaFieldElement>();
+        #[cfg(feature = "stark252")]
+        test_fixed_byte_len::<Stark252FieldElement>();
+    }
+
+    #[test]
+    fn pow2_scalar_ring() {
+        test_pow2::<F13FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn pow2_foi() {
+        test_pow2::<OxfoiFieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn pow2_bn128() {
+        test_pow2::<Bn128FieldElement>();
+    }
+
+    #[test]
+    fn default_is_zero() {
+        #[derive(Default)]
+        struct Witness {
+            x: F13FieldElement,
+            m: matrix::Matrix<F13FieldElement>,
+            p: polynomial::Polynomial<F13FieldElement>,
+        }
+        let w = Witness::default();
+        assert_eq!(w.x, F13FieldElement::zero());
+        assert!(w.m.is_empty());
+        w.m._assert_internal_consistency();
+        assert!(w.p.is_zero());
+    }
+
+    #[test]
+    fn canonical_hash_scalar_ring() {
+        test_canonical_hash::<F13FieldElement>();
+        // trailing zeroes don't change the hash
+        assert_eq!(
+            F13FieldElement::from(5).canonical_hash_u64(),
+            Z12RingElement::from(5).canonical_hash_u64()
+        );
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn canonical_hash_foi() {
+        test_canonical_hash::<OxfoiFieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn canonical_hash_bn128() {
+        test_canonical_hash::<Bn128FieldElement>();
+        // 32 byte bn128 and 8 byte oxfoi encodings of the same integer
+        #[cfg(feature = "oxfoi")]
+        assert_eq!(
+            Bn128FieldElement::from(1 << 40).canonical_hash_u64(),
+            OxfoiFieldElement::from(1 << 40).canonical_hash_u64()
+        );
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn canonical_hash_curve25519() {
+        test_canonical_hash::<Curve25519FieldElement>();
+    }
+
+    #[test]
+    fn parse_scalar_ring() {
+        test_parse::<F13FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn parse_foi() {
+        test_parse::<OxfoiFieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn parse_bn128() {
+        test_parse::<Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn parse_curve25519() {
+        test_parse::<Curve25519FieldElement>();
+    }
+
+    #[test]
+    fn inv_scalar_ring() {
+        // skip zero, the loop wraps the whole field
+        let mut x = F13FieldElement::one();
+        for _ in 1..13 {
+            assert_eq!(x.inv_fermat(), x.inv_euclid());
+            assert_eq!(x * x.inv_euclid().unwrap(), F13FieldElement::one());
+            x += F13FieldElement::one();
+        }
+        // composite modulus: only units are invertible
+        assert_eq!(Z12RingElement::from(4).inv_euclid(), None);
+        assert_eq!(
+            Z12RingElement::from(5).inv_euclid(),
+            Some(Z12RingElement::from(5))
+        );
+        assert_eq!(
+            Z12RingElement::from(4).inv(),
+            Err(FieldError::NotInvertible)
+        );
+        assert_eq!(Z12RingElement::from(5).inv(), Ok(Z12RingElement::from(5)));
+        assert_eq!(
+            Z12RingElement::from(8).checked_div(&Z12RingElement::from(2)),
+            None
+        );
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn inv_foi() {
+        test_inv::<OxfoiFieldElement>();
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn inv_bn128() {
+        test_inv::<Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn inv_curve25519() {
+        test_inv::<Curve25519FieldElement>();
+    }
 
     #[test]
     fn sqrt_scalar_ring() {
         test_sqrt::<F13FieldElement>();
+        test_sqrt::<F19FieldElement>();
     }
 
+    #[cfg(feature = "oxfoi")]
     #[test]
     fn sqrt_foi_slow() {
         test_sqrt::<oxfoi_slow::OxfoiFieldElement>();
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(all(feature = "oxfoi", target_pointer_width = "64"))]
     #[test]
     fn sqrt_foi() {
         test_sqrt::<oxfoi::OxfoiFieldElement>();
     }
 
+    #[cfg(feature = "alt_bn128")]
     #[test]
     fn sqrt_bn128() {
         test_sqrt::<alt_bn128::Bn128FieldElement>();
     }
 
+    #[cfg(feature = "curve25519")]
     #[test]
     fn sqrt_curve25519() {
         test_sqrt::<curve_25519::Curve25519FieldElement>();
     }
+
+    #[allow(dead_code)]
+    fn snapshot_lines<T: FieldElement>() -> Vec<String> {
+        let p = T::prime();
+        let values = [
+            BigUint::from(0_u32),
+            BigUint::from(1_u32),
+            BigUint::from(2_u32),
+            BigUint::from(255_u32),
+            BigUint::from(256_u32),
+            BigUint::from(1_u64 << 32),
+            BigUint::from(u64::MAX) % &p,
+            (BigUint::from(1_u32) << 200) % &p,
+            p.clone() - 2_u32,
+            p.clone() - 1_u32,
+        ];
+        values
+            .iter()
+            .map(|v| {
+                let e = T::from_biguint(v);
+                let hex = e
+                    .to_bytes_le()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<String>();
+                format!("{} {v} {} {hex}", T::name_str(), e.serialize())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn format_snapshot() {
+        let fixture = include_str!("../fixtures/format.txt");
+        let mut lines = fixture.lines().filter(|l| !l.starts_with('#'));
+        assert_eq!(
+            lines.next(),
+            Some(format!("version {FORMAT_VERSION}").as_str()),
+            "FORMAT_VERSION changed, regenerate fixtures/format.txt"
+        );
+        #[allow(unused_mut)]
+        let mut expected = std::collections::HashMap::new();
+        for line in lines {
+            let field = line.split(' ').next().unwrap();
+            expected
+                .entry(field)
+                .or_insert_with(Vec::new)
+                .push(line.to_string());
+        }
+        #[allow(unused_variables)]
+        let check = |actual: Vec<String>| {
+            let field = actual[0].split(' ').next().unwrap().to_string();
+            assert_eq!(expected[field.as_str()], actual, "{field} encoding changed");
+        };
+        #[cfg(feature = "alt_bn128")]
+        check(snapshot_lines::<Bn128FieldElement>());
+        #[cfg(feature = "curve25519")]
+        check(snapshot_lines::<Curve25519FieldElement>());
+        #[cfg(feature = "oxfoi")]
+        check(snapshot_lines::<OxfoiFieldElement>());
+    }
+
+    #[cfg(all(feature = "alt_bn128", feature = "curve25519", feature = "oxfoi"))]
+    #[test]
+    #[ignore]
+    fn print_snapshot() {
+        println!("version {FORMAT_VERSION}");
+        for l in snapshot_lines::<Bn128FieldElement>()
+            .into_iter()
+            .chain(snapshot_lines::<Curve25519FieldElement>())
+            .chain(snapshot_lines::<OxfoiFieldElement>())
+        {
+            println!("{l}");
+        }
+    }
 }
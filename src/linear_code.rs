@@ -0,0 +1,375 @@
+//! Linear codes over a `FieldElement`.
+//!
+//! A `LinearCode` is built from a `k x n` generator matrix whose rows
+//! span the code. Messages of length `k` are encoded as `m G`, the
+//! parity-check matrix `H` has `c H^T = 0` exactly for codewords `c`.
+//!
+//! `minimum_distance` enumerates every codeword, it's only practical
+//! for small codes over small fields.
+//!
+//! Evaluation code constructors:
+//!   - `reed_solomon` - `[n, k, n - k + 1]` over any field, the
+//!     evaluations of polynomials of degree `< k` at `n` distinct points
+//!   - `reed_muller` - the evaluations of `m` variate polynomials of
+//!     total degree `<= r` at every point of `F_p^m`, for small fields
+//!
+use super::matrix::Matrix;
+use super::BigUint;
+use super::FieldElement;
+
+/// The maximum number of codewords `minimum_distance` will enumerate.
+pub const MAX_CODEWORDS: u64 = 1 << 20;
+
+/// The maximum block length `p^m` of a Reed–Muller code.
+pub const MAX_REED_MULLER_LENGTH: u64 = 1 << 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearCode<T: FieldElement> {
+    generator: Matrix<T>,
+    parity_check: Matrix<T>,
+}
+
+impl<T: FieldElement> LinearCode<T> {
+    /// Build a code from a `k x n` generator matrix. Returns `None` if
+    /// the matrix isn't 2 dimensional, is empty, or the rows are
+    /// linearly dependent.
+    pub fn new(generator: Matrix<T>) -> Option<Self> {
+        if generator.dimensions.len() != 2 || generator.is_empty() {
+            return None;
+        }
+        let (k, n) = (generator.dimensions[0], generator.dimensions[1]);
+        let (reduced, pivots) = generator.row_echelon();
+        if pivots.len() != k {
+            return None;
+        }
+        // for each free column j: e_j - sum_i R[i][j] e_{pivot_i}
+        let mut values = vec![];
+        for j in (0..n).filter(|j| !pivots.contains(j)) {
+            let mut row = vec![T::zero(); n];
+            row[j] = T::one();
+            for (i, pivot) in pivots.iter().enumerate() {
+                row[*pivot] = -reduced.values[i * n + j].clone();
+            }
+            values.extend(row);
+        }
+        Some(LinearCode {
+            generator,
+            parity_check: Matrix {
+                dimensions: vec![n - k, n],
+                values,
+            },
+        })
+    }
+
+    /// The Reed–Solomon code evaluating polynomials of degree `< k`
+    /// at `points`. Parameters `[n, k, n - k + 1]` with `n = points.len()`,
+    /// the code is MDS. Returns `None` if the points are not distinct
+    /// or `k` is not in `1..=n`.
+    pub fn reed_solomon(points: &[T], k: usize) -> Option<Self> {
+        if k == 0 || k > points.len() {
+            return None;
+        }
+        if (1..points.len()).any(|i| points[..i].contains(&points[i])) {
+            return None;
+        }
+        // row i evaluates x^i, a Vandermonde matrix
+        let values = (0..k)
+            .flat_map(|i| points.iter().map(move |x| x.pow_u64(i as u64)))
+            .collect();
+        Self::new(Matrix {
+            dimensions: vec![k, points.len()],
+            values,
+        })
+    }
+
+    /// The generalized Reed–Muller code `RM_q(r, m)`: evaluations of
+    /// polynomials in `m` variables of total degree `<= r` (each
+    /// variable of degree `< q`) at every point of `F_q^m`, in
+    /// lexicographic order with the first coordinate varying fastest.
+    /// `q = T::prime()` is the field size, coordinates are enumerated
+    /// with `nth_element`.
+    ///
+    /// The length is `n = q^m`. Writing `r = a(q - 1) + b` with
+    /// `0 <= b < q - 1`, the minimum distance is `(q - b) q^(m - a - 1)`.
+    /// Over `F_2` this is the binary code with `k = sum_{i <= r} C(m, i)`
+    /// and `d = 2^(m - r)`.
+    ///
+    /// Returns `None` if `q^m` exceeds `MAX_REED_MULLER_LENGTH`.
+    pub fn reed_muller(r: usize, m: usize) -> Option<Self> {
+        let p = u64::try_from(T::prime()).ok()?;
+        let n = p.checked_pow(u32::try_from(m).ok()?)?;
+        if n > MAX_REED_MULLER_LENGTH {
+            return None;
+        }
+        let points = (0..n)
+            .map(|i| (0..m).map(|j| i / p.pow(j as u32) % p).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // the exponent vectors have the same shape as the points
+        let monomials = points
+            .iter()
+            .filter(|e| e.iter().sum::<u64>() <= r as u64)
+            .collect::<Vec<_>>();
+        // From<u64> only reaches the prime subfield of an extension
+        let elements = (0..p)
+            .map(|x| T::nth_element(&BigUint::from(x)))
+            .collect::<Vec<_>>();
+        let mut values = vec![];
+        for e in &monomials {
+            for x in &points {
+                let v = x.iter().zip(e.iter()).fold(T::one(), |acc, (x, e)| {
+                    acc * elements[*x as usize].pow_u64(*e)
+                });
+                values.push(v);
+            }
+        }
+        Self::new(Matrix {
+            dimensions: vec![monomials.len(), points.len()],
+            values,
+        })
+    }
+
+    pub fn generator(&self) -> &Matrix<T> {
+        &self.generator
+    }
+
+    /// The `(n - k) x n` parity-check matrix.
+    pub fn parity_check(&self) -> &Matrix<T> {
+        &self.parity_check
+    }
+
+    /// The block length `n`.
+    pub fn len(&self) -> usize {
+        self.generator.dimensions[1]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The dimension `k`, the length of a message.
+    pub fn dimension(&self) -> usize {
+        self.generator.dimensions[0]
+    }
+
+    /// The codeword `m G`. Panics if the message length is not `k`.
+    pub fn encode(&self, message: &[T]) -> Vec<T> {
+        if message.len() != self.dimension() {
+            panic!(
+                "message length {} does not match code dimension {}",
+                message.len(),
+                self.dimension()
+            );
+        }
+        let mut out = vec![T::zero(); self.len()];
+        for (m, row) in message.iter().zip(self.generator.rows()) {
+            for (o, g) in out.iter_mut().zip(row) {
+                *o += m.clone() * g.clone();
+            }
+        }
+        out
+    }
+
+    /// The syndrome `w H^T`, zero exactly when `word` is a codeword.
+    /// Panics if the word length is not `n`.
+    pub fn syndrome(&self, word: &[T]) -> Vec<T> {
+        if word.len() != self.len() {
+            panic!(
+                "word length {} does not match code length {}",
+                word.len(),
+                self.len()
+            );
+        }
+        if self.parity_check.is_empty() {
+            return vec![];
+        }
+        self.parity_check
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .zip(word)
+                    .fold(T::zero(), |acc, (h, w)| acc + h.clone() * w.clone())
+            })
+            .collect()
+    }
+
+    pub fn is_codeword(&self, word: &[T]) -> bool {
+        self.syndrome(word).iter().all(|v| v == &T::zero())
+    }
+
+    /// The minimum hamming weight of a non-zero codeword, found by
+    /// enumerating all `q^k` codewords, where `q = T::prime()` is the
+    /// field size. Returns `None` if there are more than `MAX_CODEWORDS`.
+    pub fn minimum_distance(&self) -> Option<usize> {
+        let k = self.dimension();
+        let count = T::prime().pow(u32::try_from(k).ok()?);
+        if count > BigUint::from(MAX_CODEWORDS) {
+            return None;
+        }
+        let p = u64::try_from(T::prime()).ok()?;
+        let mut message = vec![0_u64; k];
+        let mut min = self.len();
+        // count through every non-zero message in base p, symbols
+        // through the bijection so extension fields see every element
+        while let Some(i) = message.iter().position(|v| *v + 1 < p) {
+            message[i] += 1;
+            message[..i].fill(0);
+            let m = message
+                .iter()
+                .map(|v| T::nth_element(&BigUint::from(*v)))
+                .collect::<Vec<_>>();
+            min = min.min(hamming_weight(&self.encode(&m)));
+        }
+        Some(min)
+    }
+}
+
+/// The number of non-zero entries.
+pub fn hamming_weight<T: FieldElement>(word: &[T]) -> usize {
+    word.iter().filter(|v| *v != &T::zero()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F2FieldElement, 2_u128, "f2");
+    scalar_ring!(F3FieldElement, 3_u128, "f3");
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+    crate::quadratic_extension!(F9FieldElement, F9Params, F3FieldElement, 2, "f3_2");
+
+    fn matrix<T: FieldElement>(rows: &[&[u64]]) -> Matrix<T> {
+        Matrix {
+            dimensions: vec![rows.len(), rows[0].len()],
+            values: rows
+                .iter()
+                .flat_map(|row| row.iter().map(|v| T::nth_element(&BigUint::from(*v))))
+                .collect(),
+        }
+    }
+
+    fn hamming_7_4() -> LinearCode<F2FieldElement> {
+        LinearCode::new(matrix(&[
+            &[1, 0, 0, 0, 1, 1, 0],
+            &[0, 1, 0, 0, 1, 0, 1],
+            &[0, 0, 1, 0, 0, 1, 1],
+            &[0, 0, 0, 1, 1, 1, 1],
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn hamming_code() {
+        let code = hamming_7_4();
+        assert_eq!((code.len(), code.dimension()), (7, 4));
+        assert_eq!(code.parity_check().dimensions, vec![3, 7]);
+        assert_eq!(code.minimum_distance(), Some(3));
+        let message = [1, 0, 1, 1].map(F2FieldElement::from);
+        let mut word = code.encode(&message);
+        assert!(code.is_codeword(&word));
+        // every single bit error has a distinct non-zero syndrome
+        let mut syndromes = vec![];
+        for i in 0..7 {
+            word[i] += F2FieldElement::one();
+            let s = code.syndrome(&word);
+            assert!(!code.is_codeword(&word));
+            assert!(!syndromes.contains(&s));
+            syndromes.push(s);
+            word[i] += F2FieldElement::one();
+        }
+    }
+
+    #[test]
+    fn non_systematic_generator() {
+        // columns out of order and a non-trivial pivot
+        let code =
+            LinearCode::<F13FieldElement>::new(matrix(&[&[0, 2, 1, 5], &[3, 4, 0, 1]])).unwrap();
+        for a in 0..13 {
+            let word = code.encode(&[F13FieldElement::from(a), F13FieldElement::from(7)]);
+            assert!(code.is_codeword(&word));
+            assert_eq!(code.syndrome(&word).len(), 2);
+        }
+        assert_eq!(code.minimum_distance(), Some(3));
+        assert!(!code.is_codeword(&[1, 0, 0, 0].map(F13FieldElement::from)));
+        // the whole space has no parity checks
+        let full = LinearCode::<F13FieldElement>::new(Matrix::identity(3)).unwrap();
+        assert!(full.syndrome(&[F13FieldElement::from(4); 3]).is_empty());
+        assert_eq!(full.minimum_distance(), Some(1));
+    }
+
+    #[test]
+    fn extension_field_distance() {
+        // [1, i] over F9, every non-zero message has weight 2
+        let i = F9FieldElement::new(F3FieldElement::zero(), F3FieldElement::one());
+        let code = LinearCode::new(Matrix {
+            dimensions: vec![1, 2],
+            values: vec![F9FieldElement::one(), i],
+        })
+        .unwrap();
+        assert_eq!(code.minimum_distance(), Some(2));
+    }
+
+    #[test]
+    fn invalid_generators() {
+        assert!(LinearCode::<F13FieldElement>::new(matrix(&[&[1, 2], &[2, 4]])).is_none());
+        assert!(LinearCode::<F13FieldElement>::new(Matrix {
+            dimensions: vec![3],
+            values: vec![F13FieldElement::one(); 3],
+        })
+        .is_none());
+        // too many codewords
+        let code = LinearCode::<F13FieldElement>::new(Matrix::identity(6)).unwrap();
+        assert_eq!(code.minimum_distance(), None);
+    }
+
+    #[test]
+    fn reed_solomon() {
+        let points = (1..=6).map(F13FieldElement::from).collect::<Vec<_>>();
+        let code = LinearCode::reed_solomon(&points, 3).unwrap();
+        assert_eq!((code.len(), code.dimension()), (6, 3));
+        assert_eq!(code.minimum_distance(), Some(4));
+        // a codeword is the evaluations of the message polynomial
+        let word = code.encode(&[1, 2, 3].map(F13FieldElement::from));
+        for (x, y) in points.iter().zip(&word) {
+            assert_eq!(
+                *y,
+                F13FieldElement::from(1)
+                    + F13FieldElement::from(2) * *x
+                    + F13FieldElement::from(3) * *x * *x
+            );
+        }
+        assert!(LinearCode::reed_solomon(&points, 0).is_none());
+        assert!(LinearCode::reed_solomon(&points, 7).is_none());
+        let repeated = [1, 2, 2].map(F13FieldElement::from);
+        assert!(LinearCode::reed_solomon(&repeated, 2).is_none());
+    }
+
+    #[test]
+    fn reed_muller() {
+        // [8, 4, 4] extended hamming code
+        let code = LinearCode::<F2FieldElement>::reed_muller(1, 3).unwrap();
+        assert_eq!((code.len(), code.dimension()), (8, 4));
+        assert_eq!(code.minimum_distance(), Some(4));
+        // [16, 11, 4]
+        let code = LinearCode::<F2FieldElement>::reed_muller(2, 4).unwrap();
+        assert_eq!((code.len(), code.dimension()), (16, 11));
+        assert_eq!(code.minimum_distance(), Some(4));
+        // r = 0(p - 1) + 1 over F_3, d = (3 - 1) 3^(2 - 0 - 1) = 6
+        let code = LinearCode::<F3FieldElement>::reed_muller(1, 2).unwrap();
+        assert_eq!((code.len(), code.dimension()), (9, 3));
+        assert_eq!(code.minimum_distance(), Some(6));
+        assert!(LinearCode::<F13FieldElement>::reed_muller(1, 5).is_none());
+        // over F9 RM(1, 1) is the [9, 2, 8] Reed–Solomon code
+        let code = LinearCode::<F9FieldElement>::reed_muller(1, 1).unwrap();
+        assert_eq!((code.len(), code.dimension()), (9, 2));
+        assert_eq!(code.minimum_distance(), Some(8));
+        // the helper maps 3 to i and 4 to 1 + i, not into F3
+        let code = LinearCode::<F9FieldElement>::new(matrix(&[&[1, 3, 4]])).unwrap();
+        assert_eq!(code.minimum_distance(), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match code dimension")]
+    fn encode_wrong_length() {
+        hamming_7_4().encode(&[F2FieldElement::one(); 3]);
+    }
+}
@@ -1,9 +1,11 @@
 //! A vector/matrix structure for doing arithmetic on
-//! sets of `FieldElement`. Matrices can be 1 dimensional
+//! sets of `RingElement`. Matrices can be 1 dimensional
 //! for representing vectors.
 //!
 //! This matrix implementation is designed to represent matrices
-//! of variable dimension.
+//! of variable dimension. Entries can be any `RingElement`, e.g.
+//! `Matrix<Polynomial<T>>` for module lattices. Elimination based
+//! methods (inverses, determinants, solving) require a `FieldElement`.
 //!
 use std::fmt::Display;
 use std::ops::Add;
@@ -16,16 +18,24 @@ use std::ops::Sub;
 use std::ops::SubAssign;
 use std::str::FromStr;
 
+use num_bigint::BigInt;
+use num_integer::Integer;
+
+use super::parallel::for_each_chunk_mut;
+use super::tuning::tuning;
+use super::tuning::Tuning;
 use super::FieldElement;
+use super::RingElement;
+use super::ShapeError;
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
-pub struct Matrix<T: FieldElement> {
+pub struct Matrix<T: RingElement> {
     // scalars should be represented as dimensions: vec![1]
     pub dimensions: Vec<usize>,
     pub values: Vec<T>,
 }
 
-impl<T: FieldElement> Matrix<T> {
+impl<T: RingElement> Matrix<T> {
     pub fn len(&self) -> usize {
         self.values.len()
     }
@@ -35,16 +45,20 @@ impl<T: FieldElement> Matrix<T> {
         self.values.is_empty()
     }
 
-    pub fn mul_scalar(&self, v: T) -> Self {
-        let values = self.values.iter().map(|x| x.clone() * v.clone()).collect();
+    /// The `n x n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        let mut values = vec![T::from(0); n * n];
+        for i in 0..n {
+            values[i * n + i] = T::from(1);
+        }
         Matrix {
-            dimensions: self.dimensions.clone(),
+            dimensions: vec![n, n],
             values,
         }
     }
 
-    pub fn invert(&self) -> Self {
-        let values = self.values.iter().map(|x| T::one() / x.clone()).collect();
+    pub fn mul_scalar(&self, v: T) -> Self {
+        let values = self.values.iter().map(|x| x.clone() * v.clone()).collect();
         Matrix {
             dimensions: self.dimensions.clone(),
             values,
@@ -94,6 +108,62 @@ impl<T: FieldElement> Matrix<T> {
         )
     }
 
+    /// Iterate over the rows of a 2 dimensional matrix.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.values.chunks_exact(self.row_len())
+    }
+
+    /// Iterate mutably over the rows of a 2 dimensional matrix.
+    pub fn rows_mut(&mut self) -> std::slice::ChunksExactMut<'_, T> {
+        let row_len = self.row_len();
+        self.values.chunks_exact_mut(row_len)
+    }
+
+    fn row_len(&self) -> usize {
+        if self.dimensions.len() != 2 {
+            panic!("expected a 2 dimensional matrix: {:?}", self.dimensions);
+        }
+        // chunks_exact panics for 0
+        self.dimensions[1].max(1)
+    }
+
+    /// The transpose of a 2 dimensional matrix.
+    pub fn transpose(&self) -> Result<Self, ShapeError> {
+        if self.dimensions.len() != 2 {
+            return Err(ShapeError::NotTwoDimensional {
+                dimensions: self.dimensions.clone(),
+            });
+        }
+        let (m, n) = (self.dimensions[0], self.dimensions[1]);
+        let values = (0..n)
+            .flat_map(|j| (0..m).map(move |i| self.values[i * n + j].clone()))
+            .collect();
+        Ok(Matrix {
+            dimensions: vec![n, m],
+            values,
+        })
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let cols = self.row_len();
+        for j in 0..cols {
+            self.values.swap(a * cols + j, b * cols + j);
+        }
+    }
+
+    fn square_len(&self, op: &str) -> usize {
+        if self.dimensions.len() != 2 || self.dimensions[0] != self.dimensions[1] {
+            panic!(
+                "{op} requires a square 2 dimensional matrix: {:?}",
+                self.dimensions
+            );
+        }
+        self.dimensions[0]
+    }
+
     pub fn _assert_internal_consistency(&self) {
         assert_eq!(self.values.len(), self.dimensions.iter().product::<usize>());
     }
@@ -113,7 +183,334 @@ impl<T: FieldElement> Matrix<T> {
     }
 }
 
-impl<T: FieldElement> Add for Matrix<T> {
+impl<T: RingElement + Send + Sync> Matrix<T> {
+    /// Apply `f` to each chunk of `chunk_size` values along with the
+    /// chunk index. With the `parallel` feature large matrices are
+    /// processed on the configured thread pool, see `parallel`.
+    pub fn for_each_chunk_mut(
+        &mut self,
+        chunk_size: usize,
+        f: impl Fn(usize, &mut [T]) + Send + Sync,
+    ) {
+        for_each_chunk_mut(&mut self.values, chunk_size, tuning().parallel_cutoff, f);
+    }
+
+    /// Matrix product of two 2 dimensional matrices with dimensions
+    /// `[m, k]` and `[k, n]`. Uses the global `Tuning`, see `matmul_with`.
+    pub fn matmul(&self, other: &Self) -> Result<Self, ShapeError> {
+        self.matmul_with(other, &tuning())
+    }
+
+    /// Matrix product using the supplied thresholds. Large products
+    /// are computed with a cache blocked loop. With the `parallel`
+    /// feature blocks of output rows are computed in parallel.
+    pub fn matmul_with(&self, other: &Self, tuning: &Tuning) -> Result<Self, ShapeError> {
+        for m in [self, other] {
+            if m.dimensions.len() != 2 {
+                return Err(ShapeError::NotTwoDimensional {
+                    dimensions: m.dimensions.clone(),
+                });
+            }
+        }
+        let (m, k, n) = (self.dimensions[0], self.dimensions[1], other.dimensions[1]);
+        trace_span!(
+            "matmul",
+            entry = std::any::type_name::<T>(),
+            m = m,
+            k = k,
+            n = n
+        );
+        if other.dimensions[0] != k {
+            return Err(ShapeError::InnerDimensionMismatch {
+                lhs: self.dimensions.clone(),
+                rhs: other.dimensions.clone(),
+            });
+        }
+        let blocked = m.min(k).min(n) >= tuning.matmul_blocked_threshold;
+        // (rows, inner, columns) handled per block
+        let (block_m, block_k, block_n) = if blocked {
+            let b = tuning.matmul_block_size.max(1);
+            (b, b, b)
+        } else {
+            (1, k.max(1), n.max(1))
+        };
+        let mut values = vec![T::from(0); m * n];
+        if n > 0 {
+            for_each_chunk_mut(
+                &mut values,
+                block_m * n,
+                tuning.parallel_cutoff,
+                |block, out| {
+                    let row_start = block * block_m;
+                    let rows = out.len() / n;
+                    for kk in (0..k).step_by(block_k) {
+                        let k_end = (kk + block_k).min(k);
+                        for jj in (0..n).step_by(block_n) {
+                            let j_end = (jj + block_n).min(n);
+                            for r in 0..rows {
+                                let lhs = &self.values[(row_start + r) * k..];
+                                let out_row = &mut out[r * n + jj..r * n + j_end];
+                                for (i, a) in lhs[kk..k_end].iter().enumerate() {
+                                    let rhs =
+                                        &other.values[(kk + i) * n + jj..(kk + i) * n + j_end];
+                                    for (o, b) in out_row.iter_mut().zip(rhs) {
+                                        *o += a.clone() * b.clone();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            );
+        }
+        Ok(Matrix {
+            dimensions: vec![m, n],
+            values,
+        })
+    }
+}
+
+impl<T: FieldElement> Matrix<T> {
+    pub fn invert(&self) -> Self {
+        trace_span!(
+            "matrix_invert",
+            field = T::name_str(),
+            n = self.values.len()
+        );
+        let values = self.values.iter().map(|x| T::one() / x.clone()).collect();
+        Matrix {
+            dimensions: self.dimensions.clone(),
+            values,
+        }
+    }
+
+    /// Determinant of a square 2 dimensional matrix using the
+    /// fraction-free Bareiss algorithm. Elimination runs on the integer
+    /// lifts, where every division is exact, and the result is reduced
+    /// at the end. No inverses are needed so this is correct for rings
+    /// with a composite modulus, e.g. `Z_q`. `O(n^3)` big integer
+    /// operations, entries grow to at most `n` times their bit length.
+    pub fn determinant_bareiss(&self) -> T {
+        let n = self.square_len("determinant");
+        let mut m = self
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|v| BigInt::from(v.to_biguint()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let zero = BigInt::from(0);
+        let mut negate = false;
+        let mut prev = BigInt::from(1);
+        for k in 0..n.saturating_sub(1) {
+            if m[k][k] == zero {
+                match (k + 1..n).find(|i| m[*i][k] != zero) {
+                    Some(i) => {
+                        m.swap(k, i);
+                        negate = !negate;
+                    }
+                    None => return T::zero(),
+                }
+            }
+            for i in k + 1..n {
+                for j in k + 1..n {
+                    m[i][j] = (&m[i][j] * &m[k][k] - &m[i][k] * &m[k][j]) / &prev;
+                }
+            }
+            prev = m[k][k].clone();
+        }
+        let det = match n {
+            0 => BigInt::from(1),
+            _ => m[n - 1][n - 1].clone(),
+        };
+        let det = det.mod_floor(&BigInt::from(T::prime()));
+        let det = T::from_biguint(&det.to_biguint().unwrap());
+        if negate {
+            -det
+        } else {
+            det
+        }
+    }
+
+    /// The reduced row echelon form of a 2 dimensional matrix and its
+    /// pivot columns, using Gaussian elimination. Requires a field,
+    /// pivots are inverted.
+    pub fn row_echelon(&self) -> (Self, Vec<usize>) {
+        let cols = self.row_len();
+        let rows = self.dimensions[0];
+        let mut m = self.clone();
+        let mut pivots = vec![];
+        if self.dimensions[1] == 0 {
+            return (m, pivots);
+        }
+        for c in 0..cols {
+            let r = pivots.len();
+            if r == rows {
+                break;
+            }
+            let Some(i) = (r..rows).find(|i| m.values[i * cols + c] != T::zero()) else {
+                continue;
+            };
+            m.swap_rows(r, i);
+            let inv = T::one() / m.values[r * cols + c].clone();
+            for v in &mut m.values[r * cols..(r + 1) * cols] {
+                *v *= inv.clone();
+            }
+            let pivot_row = m.values[r * cols..(r + 1) * cols].to_vec();
+            for (i, row) in m.rows_mut().enumerate() {
+                if i == r || row[c] == T::zero() {
+                    continue;
+                }
+                let factor = row[c].clone();
+                for (v, p) in row.iter_mut().zip(&pivot_row) {
+                    *v -= factor.clone() * p.clone();
+                }
+            }
+            pivots.push(c);
+        }
+        (m, pivots)
+    }
+
+    /// The rank of a 2 dimensional matrix over the field.
+    pub fn rank(&self) -> usize {
+        self.row_echelon().1.len()
+    }
+
+    /// Determinant of a square 2 dimensional matrix using Gaussian
+    /// elimination, `O(n^3)` field operations. Requires a field, use
+    /// `determinant_bareiss` for rings with a composite modulus.
+    pub fn determinant(&self) -> T {
+        let n = self.square_len("determinant");
+        let mut m = self.clone();
+        let mut det = T::one();
+        for c in 0..n {
+            let Some(i) = (c..n).find(|i| m.values[i * n + c] != T::zero()) else {
+                return T::zero();
+            };
+            if i != c {
+                m.swap_rows(c, i);
+                det = -det;
+            }
+            let pivot = m.values[c * n + c].clone();
+            let inv = T::one() / pivot.clone();
+            det *= pivot;
+            for i in c + 1..n {
+                let factor = m.values[i * n + c].clone() * inv.clone();
+                if factor == T::zero() {
+                    continue;
+                }
+                for j in c..n {
+                    let v = factor.clone() * m.values[c * n + j].clone();
+                    m.values[i * n + j] -= v;
+                }
+            }
+        }
+        det
+    }
+
+    /// The inverse of a square 2 dimensional matrix, `None` if the
+    /// matrix is singular. See `invert` for element wise inversion.
+    pub fn inverse(&self) -> Option<Self> {
+        let n = self.square_len("inverse");
+        if n == 0 {
+            return Some(self.clone());
+        }
+        // row reduce [A | I]
+        let mut values = Vec::with_capacity(2 * n * n);
+        for (i, row) in self.rows().enumerate().take(n) {
+            values.extend_from_slice(row);
+            values.extend((0..n).map(|j| if i == j { T::one() } else { T::zero() }));
+        }
+        let augmented = Matrix {
+            dimensions: vec![n, 2 * n],
+            values,
+        };
+        let (reduced, pivots) = augmented.row_echelon();
+        if pivots.len() < n || pivots[n - 1] != n - 1 {
+            return None;
+        }
+        Some(Matrix {
+            dimensions: vec![n, n],
+            values: reduced.rows().flat_map(|row| row[n..].to_vec()).collect(),
+        })
+    }
+
+    /// Solve `self * x = b` for a 2 dimensional matrix by row reducing
+    /// the augmented matrix `[self | b]`. Panics if `b` doesn't have
+    /// one entry per row. See `solve` for the unique solution only.
+    pub fn solutions(&self, b: &[T]) -> Solution<T> {
+        if self.dimensions.len() != 2 || self.dimensions[0] != b.len() {
+            panic!(
+                "solve requires a 2 dimensional matrix with one row per entry of b: {:?} {}",
+                self.dimensions,
+                b.len()
+            );
+        }
+        let cols = self.dimensions[1];
+        let mut values = Vec::with_capacity(b.len() * (cols + 1));
+        for (row, v) in self.rows().zip(b) {
+            values.extend_from_slice(row);
+            values.push(v.clone());
+        }
+        let augmented = Matrix {
+            dimensions: vec![b.len(), cols + 1],
+            values,
+        };
+        let (reduced, pivots) = augmented.row_echelon();
+        if pivots.last() == Some(&cols) {
+            return Solution::Inconsistent;
+        }
+        let mut particular = vec![T::zero(); cols];
+        for (row, c) in reduced.rows().zip(&pivots) {
+            particular[*c] = row[cols].clone();
+        }
+        if pivots.len() == cols {
+            return Solution::Unique(particular);
+        }
+        // one kernel vector per free column
+        let kernel = (0..cols)
+            .filter(|c| !pivots.contains(c))
+            .map(|free| {
+                let mut v = vec![T::zero(); cols];
+                v[free] = T::one();
+                for (row, c) in reduced.rows().zip(&pivots) {
+                    v[*c] = -row[free].clone();
+                }
+                v
+            })
+            .collect();
+        Solution::Many { particular, kernel }
+    }
+}
+
+/// The solution set of a linear system `Ax = b`, see
+/// `Matrix::solutions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T: FieldElement> {
+    /// No `x` satisfies the system.
+    Inconsistent,
+    /// Exactly one `x` satisfies the system.
+    Unique(Vec<T>),
+    /// Every `particular + sum c_i * kernel[i]` satisfies the system,
+    /// the kernel vectors are a basis of the null space of `A`.
+    Many {
+        particular: Vec<T>,
+        kernel: Vec<Vec<T>>,
+    },
+}
+
+/// Solve `a * x = b` over the field with Gaussian elimination.
+/// Returns `None` if the system has no solution or more than one,
+/// use `Matrix::solutions` to tell these apart.
+pub fn solve<T: FieldElement>(a: &Matrix<T>, b: &[T]) -> Option<Vec<T>> {
+    match a.solutions(b) {
+        Solution::Unique(x) => Some(x),
+        _ => None,
+    }
+}
+
+impl<T: RingElement> Add for Matrix<T> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
@@ -131,7 +528,7 @@ impl<T: FieldElement> Add for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> AddAssign for Matrix<T> {
+impl<T: RingElement> AddAssign for Matrix<T> {
     fn add_assign(&mut self, other: Self) {
         self.assert_eq_shape(&other);
         for i in 0..self.values.len() {
@@ -140,7 +537,7 @@ impl<T: FieldElement> AddAssign for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> Sub for Matrix<T> {
+impl<T: RingElement> Sub for Matrix<T> {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
@@ -158,7 +555,7 @@ impl<T: FieldElement> Sub for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> SubAssign for Matrix<T> {
+impl<T: RingElement> SubAssign for Matrix<T> {
     fn sub_assign(&mut self, other: Self) {
         self.assert_eq_shape(&other);
         for i in 0..self.values.len() {
@@ -167,7 +564,7 @@ impl<T: FieldElement> SubAssign for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> Mul for Matrix<T> {
+impl<T: RingElement> Mul for Matrix<T> {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
@@ -185,7 +582,7 @@ impl<T: FieldElement> Mul for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> MulAssign for Matrix<T> {
+impl<T: RingElement> MulAssign for Matrix<T> {
     fn mul_assign(&mut self, other: Self) {
         self.assert_eq_shape(&other);
         for i in 0..self.values.len() {
@@ -194,6 +591,23 @@ impl<T: FieldElement> MulAssign for Matrix<T> {
     }
 }
 
+/// Scale every entry by `v`, same as `mul_scalar`.
+impl<T: RingElement> Mul<T> for Matrix<T> {
+    type Output = Self;
+
+    fn mul(self, v: T) -> Self {
+        self.mul_scalar(v)
+    }
+}
+
+impl<T: RingElement> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, v: T) {
+        for x in self.values.iter_mut() {
+            *x *= v.clone();
+        }
+    }
+}
+
 impl<T: FieldElement> Div for Matrix<T> {
     type Output = Self;
 
@@ -212,7 +626,7 @@ impl<T: FieldElement> Div for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> Neg for Matrix<T> {
+impl<T: RingElement> Neg for Matrix<T> {
     type Output = Self;
 
     fn neg(self) -> Self {
@@ -224,7 +638,17 @@ impl<T: FieldElement> Neg for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> From<T> for Matrix<T> {
+/// An empty matrix with a single dimension of size 0.
+impl<T: RingElement> Default for Matrix<T> {
+    fn default() -> Self {
+        Matrix {
+            dimensions: vec![0],
+            values: vec![],
+        }
+    }
+}
+
+impl<T: RingElement> From<T> for Matrix<T> {
     fn from(v: T) -> Self {
         Matrix {
             dimensions: vec![1],
@@ -233,7 +657,7 @@ impl<T: FieldElement> From<T> for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> From<u64> for Matrix<T> {
+impl<T: RingElement> From<u64> for Matrix<T> {
     fn from(v: u64) -> Self {
         Matrix::from(T::from(v))
     }
@@ -247,7 +671,7 @@ impl<T: FieldElement> FromStr for Matrix<T> {
     }
 }
 
-impl<T: FieldElement> Display for Matrix<T> {
+impl<T: RingElement> Display for Matrix<T> {
     // TODO: pretty print the matrix
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         let mut s = String::new();
@@ -266,3 +690,286 @@ impl<T: FieldElement> Display for Matrix<T> {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn matrix(dimensions: Vec<usize>, values: &[u64]) -> Matrix<F13FieldElement> {
+        Matrix {
+            dimensions,
+            values: values.iter().map(|v| F13FieldElement::from(*v)).collect(),
+        }
+    }
+
+    #[test]
+    fn polynomial_entries() {
+        use crate::polynomial::Polynomial;
+        type P = Polynomial<F13FieldElement>;
+        let poly = |c: &[u64]| P::new(c.iter().map(|v| F13FieldElement::from(*v)).collect());
+        // [[1, x], [x + 1, 2]]
+        let m = Matrix {
+            dimensions: vec![2, 2],
+            values: vec![poly(&[1]), poly(&[0, 1]), poly(&[1, 1]), poly(&[2])],
+        };
+        let square = m.matmul(&m).unwrap();
+        assert_eq!(
+            square.values,
+            vec![
+                poly(&[1, 1, 1]),
+                poly(&[0, 3]),
+                poly(&[3, 3]),
+                poly(&[4, 1, 1]),
+            ]
+        );
+        assert_eq!(Matrix::<P>::identity(2).matmul(&m).unwrap(), m);
+        assert_eq!(m.clone() * poly(&[0, 1]), m.mul_scalar(poly(&[0, 1])));
+        assert_eq!(m.transpose().unwrap().values[1], poly(&[1, 1]));
+    }
+
+    #[test]
+    fn scalar_mul() {
+        let m = matrix(vec![2, 2], &[1, 2, 3, 12]);
+        let scaled = matrix(vec![2, 2], &[5, 10, 2, 8]);
+        assert_eq!(m.clone() * F13FieldElement::from(5), scaled);
+        assert_eq!(m.mul_scalar(F13FieldElement::from(5)), scaled);
+        let mut m = m;
+        m *= F13FieldElement::from(5);
+        assert_eq!(m, scaled);
+    }
+
+    #[test]
+    fn matmul() {
+        // [1 2 3]   [1 0]   [10 11]
+        // [4 5 6] * [0 1] = [22 23] mod 13
+        //           [3 3]
+        let a = matrix(vec![2, 3], &[1, 2, 3, 4, 5, 6]);
+        let b = matrix(vec![3, 2], &[1, 0, 0, 1, 3, 3]);
+        assert_eq!(a.matmul(&b), Ok(matrix(vec![2, 2], &[10, 11, 9, 10])));
+        let identity = matrix(vec![3, 3], &[1, 0, 0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(a.matmul(&identity), Ok(a));
+    }
+
+    #[test]
+    fn blocked_matmul() {
+        let a = Matrix {
+            dimensions: vec![7, 9],
+            values: (0..63).map(F13FieldElement::from).collect(),
+        };
+        let b = Matrix {
+            dimensions: vec![9, 5],
+            values: (0..45).map(|v| F13FieldElement::from(v * v)).collect(),
+        };
+        let naive = a.matmul_with(
+            &b,
+            &Tuning {
+                matmul_blocked_threshold: usize::MAX,
+                ..Tuning::DEFAULT
+            },
+        );
+        for block_size in [1, 2, 3, 4, 16] {
+            let tuning = Tuning {
+                matmul_blocked_threshold: 0,
+                matmul_block_size: block_size,
+                parallel_cutoff: 0,
+                ..Tuning::DEFAULT
+            };
+            assert_eq!(a.matmul_with(&b, &tuning), naive);
+        }
+    }
+
+    #[test]
+    fn rows_and_chunks() {
+        let mut m = matrix(vec![2, 3], &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(m.rows().count(), 2);
+        for row in m.rows_mut() {
+            row.reverse();
+        }
+        assert_eq!(m, matrix(vec![2, 3], &[3, 2, 1, 6, 5, 4]));
+        m.for_each_chunk_mut(2, |i, chunk| {
+            for v in chunk {
+                *v += F13FieldElement::from(i as u64);
+            }
+        });
+        assert_eq!(m, matrix(vec![2, 3], &[3, 2, 2, 7, 7, 6]));
+    }
+
+    scalar_ring!(Z12RingElement, 12_u128, "z12");
+
+    // cofactor expansion along the first row
+    fn cofactor_determinant<T: FieldElement>(values: &[T], n: usize) -> T {
+        if n == 0 {
+            return T::one();
+        }
+        let mut det = T::zero();
+        for col in 0..n {
+            let minor = (1..n)
+                .flat_map(|r| (0..n).filter(move |c| *c != col).map(move |c| (r, c)))
+                .map(|(r, c)| values[r * n + c].clone())
+                .collect::<Vec<_>>();
+            let term = values[col].clone() * cofactor_determinant(&minor, n - 1);
+            if col % 2 == 0 {
+                det += term;
+            } else {
+                det -= term;
+            }
+        }
+        det
+    }
+
+    #[test]
+    fn determinant_bareiss() {
+        assert_eq!(
+            matrix(vec![2, 2], &[1, 2, 3, 4]).determinant_bareiss(),
+            -F13FieldElement::from(2)
+        );
+        // a zero pivot requires a row swap
+        assert_eq!(
+            matrix(vec![3, 3], &[0, 1, 2, 1, 0, 3, 4, 5, 6]).determinant_bareiss(),
+            F13FieldElement::from(16)
+        );
+        assert_eq!(
+            matrix(vec![2, 2], &[1, 2, 2, 4]).determinant_bareiss(),
+            F13FieldElement::zero()
+        );
+        assert_eq!(
+            Matrix::<F13FieldElement>::identity(0).determinant_bareiss(),
+            F13FieldElement::one()
+        );
+        // composite modulus, many entries are zero divisors
+        for n in 1..6 {
+            let values = (0..n * n)
+                .map(|i| Z12RingElement::from((i * i * 7 + i / 3) as u64))
+                .collect::<Vec<_>>();
+            let m = Matrix {
+                dimensions: vec![n, n],
+                values: values.clone(),
+            };
+            assert_eq!(m.determinant_bareiss(), cofactor_determinant(&values, n));
+        }
+    }
+
+    #[test]
+    fn gaussian_elimination() {
+        let m = matrix(vec![3, 3], &[0, 1, 2, 1, 0, 3, 4, 5, 6]);
+        assert_eq!(m.determinant(), F13FieldElement::from(16));
+        assert_eq!(m.rank(), 3);
+        let inv = m.inverse().unwrap();
+        assert_eq!(m.matmul(&inv), Ok(Matrix::identity(3)));
+        assert_eq!(inv.matmul(&m), Ok(Matrix::identity(3)));
+        // singular, the last row is the sum of the first two
+        let m = matrix(vec![3, 3], &[1, 2, 3, 4, 5, 6, 5, 7, 9]);
+        assert_eq!(m.determinant(), F13FieldElement::zero());
+        assert_eq!(m.rank(), 2);
+        assert_eq!(m.inverse(), None);
+        // singular only mod 13
+        let m = matrix(vec![2, 2], &[2, 1, 0, 13]);
+        assert_eq!(m.inverse(), None);
+        assert_eq!(matrix(vec![2, 3], &[1, 2, 3, 2, 4, 6]).rank(), 1);
+        assert_eq!(matrix(vec![2, 0], &[]).rank(), 0);
+        assert_eq!(
+            Matrix::<F13FieldElement>::identity(0).inverse(),
+            Some(Matrix::identity(0))
+        );
+        for n in 1..6 {
+            let values = (0..n * n)
+                .map(|i| F13FieldElement::from((i * i * 7 + i / 3) as u64))
+                .collect::<Vec<_>>();
+            let m = Matrix {
+                dimensions: vec![n, n],
+                values,
+            };
+            assert_eq!(m.determinant(), m.determinant_bareiss());
+            assert_eq!(
+                m.inverse().is_some(),
+                m.determinant() != F13FieldElement::zero()
+            );
+        }
+    }
+
+    #[test]
+    fn linear_systems() {
+        let to_f13 = |v: &[u64]| {
+            v.iter()
+                .map(|x| F13FieldElement::from(*x))
+                .collect::<Vec<_>>()
+        };
+        let m = matrix(vec![3, 3], &[0, 1, 2, 1, 0, 3, 4, 5, 6]);
+        let x = to_f13(&[3, 1, 4]);
+        let b = m.matmul(&matrix(vec![3, 1], &[3, 1, 4])).unwrap().values;
+        assert_eq!(solve(&m, &b), Some(x));
+        // the last row is the sum of the first two
+        let m = matrix(vec![3, 3], &[1, 2, 3, 4, 5, 6, 5, 7, 9]);
+        assert_eq!(m.solutions(&to_f13(&[1, 1, 1])), Solution::Inconsistent);
+        let b = to_f13(&[1, 1, 2]);
+        assert_eq!(solve(&m, &b), None);
+        let Solution::Many { particular, kernel } = m.solutions(&b) else {
+            panic!("expected infinitely many solutions");
+        };
+        assert_eq!(kernel.len(), 1);
+        let apply = |v: &[F13FieldElement]| {
+            m.matmul(&Matrix {
+                dimensions: vec![3, 1],
+                values: v.to_vec(),
+            })
+            .unwrap()
+            .values
+        };
+        assert_eq!(apply(&particular), b);
+        assert_eq!(apply(&kernel[0]), to_f13(&[0, 0, 0]));
+        // underdetermined and overdetermined
+        let wide = matrix(vec![1, 3], &[1, 1, 1]);
+        assert!(matches!(
+            wide.solutions(&to_f13(&[5])),
+            Solution::Many { kernel, .. } if kernel.len() == 2
+        ));
+        let tall = matrix(vec![3, 2], &[1, 0, 0, 1, 1, 1]);
+        assert_eq!(solve(&tall, &to_f13(&[2, 3, 5])), Some(to_f13(&[2, 3])));
+        assert_eq!(solve(&tall, &to_f13(&[2, 3, 6])), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "inverse requires a square")]
+    fn inverse_not_square() {
+        matrix(vec![2, 3], &[1, 2, 3, 4, 5, 6]).inverse();
+    }
+
+    #[test]
+    fn matmul_shape_mismatch() {
+        let a = matrix(vec![2, 3], &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            a.matmul(&a),
+            Err(ShapeError::InnerDimensionMismatch {
+                lhs: vec![2, 3],
+                rhs: vec![2, 3],
+            })
+        );
+        let v = matrix(vec![3], &[1, 2, 3]);
+        assert_eq!(
+            a.matmul(&v),
+            Err(ShapeError::NotTwoDimensional {
+                dimensions: vec![3]
+            })
+        );
+        assert!(v.transpose().is_err());
+    }
+
+    #[test]
+    fn transpose() {
+        let a = matrix(vec![2, 3], &[1, 2, 3, 4, 5, 6]);
+        let t = a.transpose().unwrap();
+        assert_eq!(t, matrix(vec![3, 2], &[1, 4, 2, 5, 3, 6]));
+        assert_eq!(t.transpose(), Ok(a.clone()));
+        // (AB)^T = B^T A^T
+        let b = matrix(vec![3, 2], &[1, 0, 7, 1, 3, 3]);
+        let ab_t = a.matmul(&b).unwrap().transpose().unwrap();
+        let b_t_a_t = b.transpose().unwrap().matmul(&t);
+        assert_eq!(Ok(ab_t), b_t_a_t);
+        assert_eq!(
+            matrix(vec![0, 2], &[]).transpose(),
+            Ok(matrix(vec![2, 0], &[]))
+        );
+    }
+}
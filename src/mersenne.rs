@@ -0,0 +1,357 @@
+//! Native prime fields with Mersenne moduli `2^k - 1`. Requires the
+//! `mersenne` feature.
+//!
+//!   - `Mersenne31FieldElement` - `p = 2^31 - 1`, stored in a `u32`
+//!   - `Mersenne61FieldElement` - `p = 2^61 - 1`, stored in a `u64`
+//!   - `Mersenne127FieldElement` - `p = 2^127 - 1`, stored in a `u128`
+//!
+//! Since `2^k = 1 mod p` a product is reduced by adding the bits above
+//! `k` to the low `k` bits, no division or multiplication by a
+//! precomputed constant is needed. All three primes are `3 mod 4`, square
+//! roots use the `(p+1)/4` addition chain.
+//!
+//! The two-adicity of `p - 1` is 1, these fields are not NTT friendly.
+//! Mersenne31 is used by circle STARKs, which work over the circle
+//! group of order `p + 1 = 2^31` instead.
+//!
+use num_bigint::BigUint;
+
+use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
+
+macro_rules! mersenne_field {
+    ( $name: ident, $t: ty, $bits: literal, $name_str: expr ) => {
+        #[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, std::hash::Hash, Default)]
+        pub struct $name($t);
+
+        impl $name {
+            /// The modulus `2^k - 1`.
+            pub const P: $t = (1 << $bits) - 1;
+
+            /// Reduce any value of the storage type.
+            pub fn fold(v: $t) -> $t {
+                let v = (v & Self::P) + (v >> $bits);
+                if v >= Self::P {
+                    v - Self::P
+                } else {
+                    v
+                }
+            }
+
+            /// The canonical integer representation.
+            pub fn value(&self) -> $t {
+                self.0
+            }
+
+            fn inverse(&self) -> Option<Self> {
+                if self.0 == 0 {
+                    return None;
+                }
+                // x^(p-2), p - 2 = 2^k - 3 has every bit set except bit 1
+                let mut out = Self::one();
+                for i in (0..$bits).rev() {
+                    out = out * out;
+                    if i != 1 {
+                        out *= *self;
+                    }
+                }
+                Some(out)
+            }
+        }
+
+        impl FixedByteLen for $name {
+            const BYTE_LEN: usize = std::mem::size_of::<$t>();
+        }
+
+        // 2^bits - 1 has bits bits
+        const _: () = assert!($name::BYTE_LEN == ($bits as usize).div_ceil(8));
+
+        impl FieldElement for $name {
+            fn name_str() -> &'static str {
+                $name_str
+            }
+
+            fn backend_str() -> &'static str {
+                "native-mersenne"
+            }
+
+            fn zero() -> Self {
+                $name(0)
+            }
+
+            fn one() -> Self {
+                $name(1)
+            }
+
+            fn byte_len() -> usize {
+                Self::BYTE_LEN
+            }
+
+            fn prime() -> BigUint {
+                BigUint::from(Self::P)
+            }
+
+            fn inv_strategy() -> InvStrategy {
+                InvStrategy::Fermat
+            }
+
+            fn inv_fermat(&self) -> Option<Self> {
+                self.inverse()
+            }
+
+            fn serialize(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn deserialize(str: &str) -> Self {
+                <Self as std::str::FromStr>::from_str(str).unwrap()
+            }
+
+            fn to_bytes_le(&self) -> Vec<u8> {
+                self.0.to_le_bytes().to_vec()
+            }
+
+            fn from_bytes_le(bytes: &[u8]) -> Self {
+                $crate::assert_byte_len::<Self>(bytes);
+                let mut padded = [0_u8; std::mem::size_of::<$t>()];
+                padded[..bytes.len()].copy_from_slice(bytes);
+                $name(Self::fold(<$t>::from_le_bytes(padded)))
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.starts_with('+') {
+                    return Err(());
+                }
+                let v = BigUint::from_str(s).map_err(|_| ())?;
+                Ok(Self::from_biguint(&v))
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                $name(Self::reduce_u64(value))
+            }
+        }
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                // both < 2^k - 1, the sum doesn't overflow
+                let v = self.0 + other.0;
+                let out = $name(if v >= Self::P { v - Self::P } else { v });
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+                out
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                let out = if self.0 >= other.0 {
+                    $name(self.0 - other.0)
+                } else {
+                    $name(self.0 + (Self::P - other.0))
+                };
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+                out
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                let out = $name(Self::mul_reduce(self.0, other.0));
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+                out
+            }
+        }
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl std::ops::Div for $name {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                let out = match other.inverse() {
+                    Some(inv) => self * inv,
+                    None => panic!("Division by zero"),
+                };
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+                out
+            }
+        }
+
+        impl std::ops::AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl std::ops::MulAssign for $name {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl std::ops::SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl std::ops::Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                let out = if self.0 == 0 {
+                    self
+                } else {
+                    $name(Self::P - self.0)
+                };
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+                out
+            }
+        }
+    };
+}
+
+mersenne_field!(Mersenne31FieldElement, u32, 31, "mersenne31");
+mersenne_field!(Mersenne61FieldElement, u64, 61, "mersenne61");
+mersenne_field!(Mersenne127FieldElement, u128, 127, "mersenne127");
+
+impl Mersenne31FieldElement {
+    fn mul_reduce(a: u32, b: u32) -> u32 {
+        Self::reduce_u64(u64::from(a) * u64::from(b))
+    }
+
+    fn reduce_u64(v: u64) -> u32 {
+        // < 2^33 + 2^31 after one fold, < 2^31 + 7 after two
+        let v = (v & u64::from(Self::P)) + (v >> 31);
+        let v = (v & u64::from(Self::P)) + (v >> 31);
+        Self::fold(v as u32)
+    }
+}
+
+impl Mersenne61FieldElement {
+    fn reduce_u64(v: u64) -> u64 {
+        Self::fold(v)
+    }
+
+    fn mul_reduce(a: u64, b: u64) -> u64 {
+        // the product is < 2^122
+        let v = u128::from(a) * u128::from(b);
+        let folded = (v as u64 & Self::P) + (v >> 61) as u64;
+        Self::fold(folded)
+    }
+}
+
+impl Mersenne127FieldElement {
+    fn reduce_u64(v: u64) -> u128 {
+        // always less than p
+        u128::from(v)
+    }
+
+    fn mul_reduce(a: u128, b: u128) -> u128 {
+        let (hi, lo) = mul_wide(a, b);
+        // hi * 2^128 = 2 * hi mod p, hi < 2^126
+        let folded = (lo & Self::P) + (lo >> 127) + (hi << 1);
+        Self::fold(folded)
+    }
+}
+
+/// The full 256 bit product `(high, low)` of two `u128`.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let (a_hi, a_lo) = (a >> 64, a & u128::from(u64::MAX));
+    let (b_hi, b_lo) = (b >> 64, b & u128::from(u64::MAX));
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+    let cross = (lo_lo >> 64) + (hi_lo & u128::from(u64::MAX)) + (lo_hi & u128::from(u64::MAX));
+    let lo = (cross << 64) | (lo_lo & u128::from(u64::MAX));
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_multiplication() {
+        assert_eq!(mul_wide(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(mul_wide(1 << 64, 1 << 64), (1, 0));
+        let (a, b) = (
+            0x9e3779b97f4a7c15f39cc0605cedc834_u128,
+            0x2545f4914f6cdd1d_u128,
+        );
+        let (hi, lo) = mul_wide(a, b);
+        let expected = BigUint::from(a) * BigUint::from(b);
+        assert_eq!((BigUint::from(hi) << 128) + lo, expected);
+    }
+
+    fn test_mersenne<T: FieldElement>(bits: u64) {
+        let p = T::prime();
+        assert_eq!(p, (BigUint::from(1_u32) << bits) - 1_u32);
+        assert!(crate::model_check::check::<T>(bits, 5_000).is_ok());
+        let max = T::from_biguint(&(&p - 1_u32));
+        assert_eq!(max.clone() * max.clone(), T::one());
+        assert_eq!(max.clone() + T::one(), T::zero());
+        assert_eq!(T::one() / max.clone(), max);
+        let x = T::from(31337);
+        let root = (x.clone() * x.clone()).sqrt();
+        assert!(root == x || root == -x.clone());
+        // reduction of wide and unreduced byte strings
+        assert_eq!(T::from_bytes_le(&p.to_bytes_le()), T::zero());
+        let wide = &p * &p * 3_u32 + 5_u32;
+        assert_eq!(T::from_biguint(&wide), T::from(5));
+        let result = std::panic::catch_unwind(|| T::from_bytes_le(&wide.to_bytes_le()));
+        assert!(result.is_err());
+        let all_ones = vec![0xff; T::byte_len()];
+        assert_eq!(
+            T::from_bytes_le(&all_ones).to_biguint(),
+            BigUint::from_bytes_le(&all_ones) % &p
+        );
+        assert_eq!(T::two_adicity(), 1);
+    }
+
+    #[test]
+    fn mersenne31() {
+        test_mersenne::<Mersenne31FieldElement>(31);
+        assert_eq!(Mersenne31FieldElement::fold(u32::MAX), 1);
+        assert_eq!(
+            Mersenne31FieldElement::from(u64::MAX).to_biguint(),
+            BigUint::from(u64::MAX % ((1 << 31) - 1))
+        );
+    }
+
+    #[test]
+    fn mersenne61() {
+        test_mersenne::<Mersenne61FieldElement>(61);
+        assert_eq!(Mersenne61FieldElement::fold(u64::MAX), 7);
+    }
+
+    #[test]
+    fn mersenne127() {
+        test_mersenne::<Mersenne127FieldElement>(127);
+        assert_eq!(Mersenne127FieldElement::fold(u128::MAX), 1);
+    }
+}
@@ -0,0 +1,157 @@
+//! The [MiMC](https://eprint.iacr.org/2016/492) permutation and hash
+//! over a generic field.
+//!
+//! Each round computes `x -> (x + k + c_i)^d` where `d` is
+//! `FieldElement::smallest_permutation_exponent`, the smallest exponent
+//! `> 1` with `gcd(d, p - 1) = 1`, so the round function is a
+//! permutation. This is `x^3` in fields where `3` doesn't divide `p - 1`
+//! and `x^5` or larger otherwise (e.g. alt_bn128, oxfoi). The number of
+//! rounds is `ceil(log_d(p))`.
+//!
+//! Round constants are derived from a seed string with a simple
+//! counter mode expansion. They are deterministic but don't match other
+//! MiMC implementations (e.g. circomlib), so hashes are not compatible.
+//!
+use super::BigUint;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mimc<T: FieldElement> {
+    exponent: u64,
+    constants: Vec<T>,
+}
+
+impl<T: FieldElement> Default for Mimc<T> {
+    fn default() -> Self {
+        Self::new("mimc")
+    }
+}
+
+impl<T: FieldElement> Mimc<T> {
+    /// Build a permutation with the default number of rounds and
+    /// round constants derived from `seed`.
+    pub fn new(seed: &str) -> Self {
+        let exponent = T::smallest_permutation_exponent();
+        let rounds = (T::prime().bits() as f64 / (exponent as f64).log2()).ceil() as usize;
+        Self::with_rounds(seed, rounds)
+    }
+
+    /// Build a permutation with a custom number of rounds.
+    pub fn with_rounds(seed: &str, rounds: usize) -> Self {
+        // the first round constant is zero by convention
+        let constants = (0..rounds)
+            .map(|i| {
+                if i == 0 {
+                    T::zero()
+                } else {
+                    derive_constant(seed, i)
+                }
+            })
+            .collect();
+        Mimc {
+            exponent: T::smallest_permutation_exponent(),
+            constants,
+        }
+    }
+
+    /// The round exponent `d`.
+    pub fn exponent(&self) -> u64 {
+        self.exponent
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.constants.len()
+    }
+
+    /// Encrypt `x` under `key`.
+    pub fn permute(&self, x: &T, key: &T) -> T {
+        let mut x = x.clone();
+        for c in &self.constants {
+            x = (x + key.clone() + c.clone()).pow_u64(self.exponent);
+        }
+        x + key.clone()
+    }
+
+    /// Hash a sequence of elements using the Miyaguchi-Preneel
+    /// construction, `h_i = E_{h_(i-1)}(m_i) + h_(i-1) + m_i`.
+    pub fn hash(&self, inputs: &[T]) -> T {
+        let mut h = T::zero();
+        for m in inputs {
+            h = self.permute(m, &h) + h.clone() + m.clone();
+        }
+        h
+    }
+}
+
+fn derive_constant<T: FieldElement>(seed: &str, i: usize) -> T {
+    // FNV-1a over seed || i || block, oversampled to keep the bias small
+    let blocks = T::byte_len().div_ceil(8) + 1;
+    let bytes = (0..blocks)
+        .flat_map(|block| {
+            let mut hash = 0xcbf29ce484222325_u64;
+            let input = seed
+                .bytes()
+                .chain((i as u64).to_le_bytes())
+                .chain((block as u64).to_le_bytes());
+            for b in input {
+                hash ^= u64::from(b);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+            hash.to_le_bytes()
+        })
+        .collect::<Vec<_>>();
+    T::from_biguint(&BigUint::from_bytes_le(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // p - 1 = 2^4 * 3^2 * 7, x^3 is not a permutation
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+    // p - 1 = 2^2 * 11 * 23, x^3 is a permutation
+    scalar_ring!(F1013FieldElement, 1013_u128, "f1013");
+
+    fn test_permutation<T: FieldElement>(expected_exponent: u64) {
+        let mimc = Mimc::<T>::default();
+        assert_eq!(mimc.exponent(), expected_exponent);
+        let key = T::from(42);
+        let a = mimc.permute(&T::from(1), &key);
+        assert_eq!(a, mimc.permute(&T::from(1), &key));
+        assert_ne!(a, mimc.permute(&T::from(2), &key));
+        assert_ne!(a, mimc.permute(&T::from(1), &T::from(43)));
+        assert_ne!(mimc.hash(&[T::one(), T::zero()]), mimc.hash(&[T::one()]));
+        assert_ne!(
+            Mimc::<T>::new("other").hash(&[T::one()]),
+            mimc.hash(&[T::one()])
+        );
+    }
+
+    #[test]
+    fn small_fields() {
+        test_permutation::<F1009FieldElement>(5);
+        test_permutation::<F1013FieldElement>(3);
+        // every round function is a bijection on the field
+        let mimc = Mimc::<F1009FieldElement>::default();
+        let mut outputs = (0..1009)
+            .map(|x| mimc.permute(&F1009FieldElement::from(x), &F1009FieldElement::from(7)))
+            .map(|v| v.to_biguint())
+            .collect::<Vec<_>>();
+        outputs.sort();
+        outputs.dedup();
+        assert_eq!(outputs.len(), 1009);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn bn128() {
+        test_permutation::<crate::Bn128FieldElement>(5);
+        assert_eq!(Mimc::<crate::Bn128FieldElement>::default().rounds(), 110);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn foi() {
+        test_permutation::<crate::OxfoiFieldElement>(7);
+    }
+}
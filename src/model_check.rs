@@ -0,0 +1,199 @@
+//! Differential testing of `FieldElement` implementations against
+//! a `BigUint` model of modular arithmetic.
+//!
+//! Randomized sequences of operations are applied to both a field
+//! implementation and the model. After every step the integer lift
+//! of the field element is compared to the model value. Serialization
+//! round trips are checked along the way.
+//!
+//! The sequences are derived from a `u64` seed so divergences can
+//! be reproduced.
+//!
+use std::fmt::Display;
+
+use num_bigint::BigUint;
+
+use super::FieldElement;
+
+/// An operation applied during a model check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Neg,
+    Serialize,
+    Bytes,
+}
+
+/// A difference between a field implementation and the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub field: &'static str,
+    pub seed: u64,
+    pub step: usize,
+    pub op: Op,
+    pub lhs: BigUint,
+    pub rhs: BigUint,
+    pub expected: BigUint,
+    pub actual: BigUint,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} diverged from model at step {} (seed {}): {:?}({}, {}) expected {} got {}",
+            self.field,
+            self.step,
+            self.seed,
+            self.op,
+            self.lhs,
+            self.rhs,
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+// number of elements operated on at once
+const REGISTERS: usize = 8;
+
+/// Run `steps` random operations against field `T` and the model.
+/// Returns the first divergence found.
+pub fn check<T: FieldElement>(seed: u64, steps: usize) -> Result<(), Box<Divergence>> {
+    let p = T::prime();
+    let mut rng = XorShift(seed.max(1));
+    let mut model = vec![
+        BigUint::from(0_u32),
+        BigUint::from(1_u32),
+        p.clone() - 1_u32,
+    ];
+    while model.len() < REGISTERS {
+        model.push(rng.below(&p, T::byte_len()));
+    }
+    let mut elements = model.iter().map(T::from_biguint).collect::<Vec<_>>();
+
+    for step in 0..steps {
+        let i = rng.next() as usize % REGISTERS;
+        let j = rng.next() as usize % REGISTERS;
+        let out = rng.next() as usize % REGISTERS;
+        let (a, b) = (model[i].clone(), model[j].clone());
+        let (x, y) = (elements[i].clone(), elements[j].clone());
+        let op = match rng.next() % 7 {
+            0 => Op::Add,
+            1 => Op::Sub,
+            2 => Op::Mul,
+            3 => Op::Div,
+            4 => Op::Neg,
+            5 => Op::Serialize,
+            _ => Op::Bytes,
+        };
+        let (expected, actual) = match op {
+            Op::Add => ((&a + &b) % &p, x + y),
+            Op::Sub => ((&a + &p - &b) % &p, x - y),
+            Op::Mul => ((&a * &b) % &p, x * y),
+            Op::Div => {
+                if b == BigUint::from(0_u32) {
+                    // division by zero behavior is backend specific
+                    continue;
+                }
+                let b_inv = b.modpow(&(p.clone() - 2_u32), &p);
+                ((&a * b_inv) % &p, x / y)
+            }
+            Op::Neg => ((&p - &a) % &p, -x),
+            Op::Serialize => (a.clone(), T::deserialize(&x.serialize())),
+            Op::Bytes => (a.clone(), T::from_bytes_le(&x.to_bytes_le())),
+        };
+        let actual_lift = actual.to_biguint();
+        if actual_lift != expected {
+            return Err(Box::new(Divergence {
+                field: T::name_str(),
+                seed,
+                step,
+                op,
+                lhs: a,
+                rhs: b,
+                expected,
+                actual: actual_lift,
+            }));
+        }
+        model[out] = expected;
+        elements[out] = actual;
+    }
+    Ok(())
+}
+
+/// Run `check` for every field enabled through crate features.
+/// Returns all divergences found.
+#[allow(unused_variables, unused_mut, clippy::vec_init_then_push)]
+pub fn check_all_enabled(seed: u64, steps: usize) -> Vec<Divergence> {
+    let mut results: Vec<Result<(), Box<Divergence>>> = vec![];
+    #[cfg(feature = "alt_bn128")]
+    results.push(check::<crate::Bn128FieldElement>(seed, steps));
+    #[cfg(feature = "curve25519")]
+    results.push(check::<crate::Curve25519FieldElement>(seed, steps));
+    #[cfg(feature = "oxfoi")]
+    results.push(check::<crate::OxfoiFieldElement>(seed, steps));
+    #[cfg(feature = "babybear")]
+    results.push(check::<crate::BabyBearFieldElement>(seed, steps));
+    #[cfg(feature = "mersenne")]
+    results.push(check::<crate::Mersenne31FieldElement>(seed, steps));
+    #[cfg(feature = "mersenne")]
+    results.push(check::<crate::Mersenne61FieldElement>(seed, steps));
+    #[cfg(feature = "mersenne")]
+    results.push(check::<crate::Mersenne127FieldElement>(seed, steps));
+    #[cfg(feature = "pallas")]
+    results.push(check::<crate::PallasFieldElement>(seed, steps));
+    #[cfg(feature = "vesta")]
+    results.push(check::<crate::VestaFieldElement>(seed, steps));
+    #[cfg(feature = "stark252")]
+    results.push(check::<crate::Stark252FieldElement>(seed, steps));
+    results
+        .into_iter()
+        .filter_map(|r| r.err())
+        .map(|d| *d)
+        .collect()
+}
+
+/// Small deterministic generator, we don't want to depend
+/// on the `random` feature here.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, p: &BigUint, byte_len: usize) -> BigUint {
+        // oversample to keep the modular bias small
+        let bytes = (0..(byte_len + 8))
+            .map(|_| self.next() as u8)
+            .collect::<Vec<_>>();
+        BigUint::from_bytes_le(&bytes) % p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    #[test]
+    fn scalar_ring_matches_model() {
+        check::<F13FieldElement>(1, 10_000).unwrap();
+    }
+
+    #[test]
+    fn enabled_fields_match_model() {
+        for seed in 1..5 {
+            let divergences = check_all_enabled(seed, 2_000);
+            assert!(divergences.is_empty(), "{divergences:?}");
+        }
+    }
+}
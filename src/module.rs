@@ -0,0 +1,238 @@
+//! Finite dimensional vector spaces over a `FieldElement`.
+//!
+//! A `Module` is a subspace of `F^n` together with an ordered basis,
+//! stored as the rows of a `k x n` matrix. Vectors are plain slices of
+//! length `n`, coordinates are slices of length `k`:
+//!   - `vector` - the combination `c B` of the basis with coordinates `c`
+//!   - `coordinates` - the inverse, `None` outside of the subspace
+//!   - `change_of_basis` - the `k x k` matrix taking coordinates in
+//!     one basis to coordinates in another basis of the same subspace
+//!   - `direct_sum` - `U ⊕ W` inside `F^(n + m)`
+//!
+//! Every module over a field is free, the name matches the usual
+//! algebraic terminology.
+//!
+use super::matrix::solve;
+use super::matrix::Matrix;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module<T: FieldElement> {
+    basis: Matrix<T>,
+}
+
+impl<T: FieldElement> Module<T> {
+    /// A subspace with the rows of a `k x n` matrix as its basis.
+    /// Returns `None` if the matrix isn't 2 dimensional or the rows
+    /// are linearly dependent.
+    pub fn new(basis: Matrix<T>) -> Option<Self> {
+        if basis.dimensions.len() != 2 || basis.rank() != basis.dimensions[0] {
+            return None;
+        }
+        Some(Module { basis })
+    }
+
+    /// `F^n` with the standard basis.
+    pub fn standard(n: usize) -> Self {
+        Module {
+            basis: Matrix::identity(n),
+        }
+    }
+
+    /// The subspace spanned by the rows of a 2 dimensional matrix.
+    /// The basis is the non-zero rows of the reduced row echelon form.
+    pub fn span(generators: &Matrix<T>) -> Self {
+        let n = generators.dimensions[1];
+        let (reduced, pivots) = generators.row_echelon();
+        Module {
+            basis: Matrix {
+                dimensions: vec![pivots.len(), n],
+                values: reduced.values[..pivots.len() * n].to_vec(),
+            },
+        }
+    }
+
+    /// The basis vectors as the rows of a `k x n` matrix.
+    pub fn basis(&self) -> &Matrix<T> {
+        &self.basis
+    }
+
+    /// The number of basis vectors `k`.
+    pub fn dim(&self) -> usize {
+        self.basis.dimensions[0]
+    }
+
+    /// The length `n` of the vectors.
+    pub fn ambient_dim(&self) -> usize {
+        self.basis.dimensions[1]
+    }
+
+    /// The vector with the given coordinates. Panics if there
+    /// isn't one coordinate per basis vector.
+    pub fn vector(&self, coordinates: &[T]) -> Vec<T> {
+        if coordinates.len() != self.dim() {
+            panic!(
+                "expected {} coordinates, got {}",
+                self.dim(),
+                coordinates.len()
+            );
+        }
+        let mut out = vec![T::zero(); self.ambient_dim()];
+        if self.ambient_dim() == 0 {
+            return out;
+        }
+        for (c, row) in coordinates.iter().zip(self.basis.rows()) {
+            for (o, b) in out.iter_mut().zip(row) {
+                *o += c.clone() * b.clone();
+            }
+        }
+        out
+    }
+
+    /// The coordinates of `v` with respect to the basis, `None` if `v`
+    /// is not in the subspace. Panics if `v` doesn't have length `n`.
+    pub fn coordinates(&self, v: &[T]) -> Option<Vec<T>> {
+        if self.dim() == 0 {
+            return v.iter().all(|x| x == &T::zero()).then(Vec::new);
+        }
+        // c B = v  <=>  B^T c^T = v^T, unique when the rows are independent
+        solve(&self.basis.transpose().expect("2 dimensional basis"), v)
+    }
+
+    pub fn contains(&self, v: &[T]) -> bool {
+        self.coordinates(v).is_some()
+    }
+
+    /// The `k x k` matrix `P` with `P c = d` whenever `c` are the
+    /// coordinates of a vector in this basis and `d` its coordinates
+    /// in the basis of `to`. Column `j` holds the coordinates of basis
+    /// vector `j` in `to`. Returns `None` if the subspaces differ.
+    pub fn change_of_basis(&self, to: &Self) -> Option<Matrix<T>> {
+        if self.dim() != to.dim() || self.ambient_dim() != to.ambient_dim() {
+            return None;
+        }
+        let k = self.dim();
+        let mut values = Vec::with_capacity(k * k);
+        if k > 0 {
+            for row in self.basis.rows() {
+                values.extend(to.coordinates(row)?);
+            }
+        }
+        Matrix {
+            dimensions: vec![k, k],
+            values,
+        }
+        .transpose()
+        .ok()
+    }
+
+    /// The direct sum `U ⊕ W` in `F^(n + m)`. The basis is the basis
+    /// of `U` padded with `m` zeroes followed by the basis of `W`
+    /// prefixed with `n` zeroes.
+    pub fn direct_sum(&self, other: &Self) -> Self {
+        let (n, m) = (self.ambient_dim(), other.ambient_dim());
+        let mut values = Vec::with_capacity((self.dim() + other.dim()) * (n + m));
+        if n > 0 {
+            for row in self.basis.rows() {
+                values.extend_from_slice(row);
+                values.extend(vec![T::zero(); m]);
+            }
+        }
+        if m > 0 {
+            for row in other.basis.rows() {
+                values.extend(vec![T::zero(); n]);
+                values.extend_from_slice(row);
+            }
+        }
+        Module {
+            basis: Matrix {
+                dimensions: vec![self.dim() + other.dim(), n + m],
+                values,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn vector(values: &[u64]) -> Vec<F13FieldElement> {
+        values.iter().map(|v| F13FieldElement::from(*v)).collect()
+    }
+
+    fn module(rows: &[&[u64]]) -> Option<Module<F13FieldElement>> {
+        Module::new(Matrix {
+            dimensions: vec![rows.len(), rows[0].len()],
+            values: rows.iter().flat_map(|row| vector(row)).collect(),
+        })
+    }
+
+    #[test]
+    fn coordinates() {
+        let u = module(&[&[1, 1, 0], &[0, 1, 1]]).unwrap();
+        assert_eq!((u.dim(), u.ambient_dim()), (2, 3));
+        assert_eq!(u.coordinates(&vector(&[1, 3, 2])), Some(vector(&[1, 2])));
+        assert_eq!(u.vector(&vector(&[1, 2])), vector(&[1, 3, 2]));
+        assert!(!u.contains(&vector(&[1, 0, 0])));
+        assert!(u.contains(&vector(&[0, 0, 0])));
+        assert!(module(&[&[1, 2], &[2, 4]]).is_none());
+        let standard = Module::<F13FieldElement>::standard(3);
+        assert_eq!(
+            standard.coordinates(&vector(&[4, 5, 6])),
+            Some(vector(&[4, 5, 6]))
+        );
+    }
+
+    #[test]
+    fn span() {
+        let generators = Matrix {
+            dimensions: vec![3, 3],
+            values: vector(&[1, 1, 0, 2, 2, 0, 0, 1, 1]),
+        };
+        let u = Module::span(&generators);
+        assert_eq!(u.dim(), 2);
+        for row in generators.rows() {
+            assert!(u.contains(row));
+        }
+        assert!(!u.contains(&vector(&[0, 0, 1])));
+    }
+
+    #[test]
+    fn change_of_basis() {
+        let u = module(&[&[1, 1, 0], &[0, 1, 1]]).unwrap();
+        // the same plane, basis (1, 2, 1), (1, 0, 12)
+        let w = module(&[&[1, 2, 1], &[1, 0, 12]]).unwrap();
+        let p = u.change_of_basis(&w).unwrap();
+        let q = w.change_of_basis(&u).unwrap();
+        assert_eq!(p.matmul(&q).unwrap(), Matrix::identity(2));
+        for c in [[1, 0], [0, 1], [3, 7]] {
+            let c = vector(&c);
+            let v = u.vector(&c);
+            let d = p
+                .matmul(&Matrix {
+                    dimensions: vec![2, 1],
+                    values: c,
+                })
+                .unwrap();
+            assert_eq!(w.vector(&d.values), v);
+        }
+        let other_plane = module(&[&[1, 0, 0], &[0, 1, 0]]).unwrap();
+        assert_eq!(u.change_of_basis(&other_plane), None);
+        assert_eq!(u.change_of_basis(&Module::standard(3)), None);
+    }
+
+    #[test]
+    fn direct_sum() {
+        let u = module(&[&[1, 1]]).unwrap();
+        let w = module(&[&[1, 2, 3], &[0, 0, 1]]).unwrap();
+        let sum = u.direct_sum(&w);
+        assert_eq!((sum.dim(), sum.ambient_dim()), (3, 5));
+        assert_eq!(sum.vector(&vector(&[2, 1, 1])), vector(&[2, 2, 1, 2, 4]));
+        assert!(Module::new(sum.basis().clone()).is_some());
+        let zero = Module::<F13FieldElement>::standard(0);
+        assert_eq!(u.direct_sum(&zero), u);
+    }
+}
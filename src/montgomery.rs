@@ -0,0 +1,578 @@
+//! A native prime field in Montgomery form, generic over the number
+//! of 64 bit limbs.
+//!
+//! `MontFieldElement<P, LIMBS>` stores `x * R mod p` with `R = 2^(64 * LIMBS)`
+//! in little endian limbs. The modulus comes from a `MontParams`
+//! type, the Montgomery constants are computed at compile time. Use
+//! `mont_field!` to define a field:
+//!
+//! ```rust
+//! use scalarff::FieldElement;
+//! scalarff::mont_field!(F1009FieldElement, F1009Params, 1, [1009], "f1009");
+//!
+//! let x = F1009FieldElement::from(1008);
+//! assert_eq!(x * x, F1009FieldElement::one());
+//! ```
+//!
+//! Addition, subtraction, multiplication and inversion run in time
+//! independent of the operand values (branches only depend on the
+//! public modulus and, for inversion, on whether the result is
+//! zero). `inv` and `/` both use `inverse`. Comparison, hashing,
+//! serialization and the default `FieldElement` methods (e.g. `sqrt`)
+//! are not constant time.
+//! This implementation has not been audited.
+//!
+//! The modulus must be an odd prime with a non-zero most significant limb.
+//!
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+
+use super::FieldElement;
+use super::FieldError;
+use super::FixedByteLen;
+use super::InvStrategy;
+
+/// The modulus of a `MontFieldElement`.
+pub trait MontParams<const LIMBS: usize>:
+    Clone + Copy + PartialEq + Eq + Hash + Debug + Default + Send + Sync + 'static
+{
+    /// Little endian 64 bit limbs of the modulus.
+    const MODULUS: [u64; LIMBS];
+
+    /// A short string identifier for the field.
+    const NAME: &'static str;
+}
+
+/// An element of the prime field defined by `P` in Montgomery form.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MontFieldElement<P: MontParams<LIMBS>, const LIMBS: usize> {
+    limbs: [u64; LIMBS],
+    params: PhantomData<P>,
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> MontFieldElement<P, LIMBS> {
+    /// `-p^-1 mod 2^64`.
+    pub const INV: u64 = neg_inv_u64(P::MODULUS[0]);
+
+    /// `R mod p`, the Montgomery form of 1.
+    pub const R: [u64; LIMBS] = pow2_mod::<LIMBS>(64 * LIMBS, &P::MODULUS);
+
+    /// `R^2 mod p`, used to convert into Montgomery form.
+    pub const R2: [u64; LIMBS] = pow2_mod::<LIMBS>(128 * LIMBS, &P::MODULUS);
+
+    const fn from_raw(limbs: [u64; LIMBS]) -> Self {
+        MontFieldElement {
+            limbs,
+            params: PhantomData,
+        }
+    }
+
+    /// Convert the little endian limbs of an integer `< 2^(64 * LIMBS)`,
+    /// the result is reduced mod p.
+    pub fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        Self::from_raw(mont_mul(&limbs, &Self::R2, &P::MODULUS, Self::INV))
+    }
+
+    /// The little endian limbs of the canonical integer representation.
+    pub fn to_limbs(&self) -> [u64; LIMBS] {
+        let mut one = [0; LIMBS];
+        one[0] = 1;
+        mont_mul(&self.limbs, &one, &P::MODULUS, Self::INV)
+    }
+
+    /// The raw Montgomery form limbs `x * R mod p`.
+    pub fn to_montgomery_limbs(&self) -> [u64; LIMBS] {
+        self.limbs
+    }
+
+    /// The multiplicative inverse, `None` for zero. Computed as
+    /// `x^(p-2)` in constant time.
+    pub fn inverse(&self) -> Option<Self> {
+        // p - 2, borrows past the first limb when it's 1
+        let mut exp = [0; LIMBS];
+        let mut borrow = 2;
+        for (e, p) in exp.iter_mut().zip(P::MODULUS) {
+            (*e, borrow) = sbb(p, 0, borrow);
+        }
+        let mut out = Self::from_raw(Self::R);
+        for i in (0..64 * LIMBS).rev() {
+            out = out * out;
+            // the exponent is public
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                out *= *self;
+            }
+        }
+        if out.limbs == [0; LIMBS] {
+            None
+        } else {
+            Some(out)
+        }
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> FixedByteLen for MontFieldElement<P, LIMBS> {
+    const BYTE_LEN: usize = {
+        let top = P::MODULUS[LIMBS - 1];
+        8 * (LIMBS - 1) + (64 - top.leading_zeros() as usize).div_ceil(8)
+    };
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> FieldElement for MontFieldElement<P, LIMBS> {
+    fn name_str() -> &'static str {
+        P::NAME
+    }
+
+    fn backend_str() -> &'static str {
+        "native-montgomery"
+    }
+
+    fn zero() -> Self {
+        Self::from_raw([0; LIMBS])
+    }
+
+    fn one() -> Self {
+        Self::from_raw(Self::R)
+    }
+
+    fn prime() -> BigUint {
+        BigUint::from_slice(
+            &P::MODULUS
+                .iter()
+                .flat_map(|limb| [*limb as u32, (limb >> 32) as u32])
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn byte_len() -> usize {
+        Self::BYTE_LEN
+    }
+
+    /// `inv` goes through the constant time `inverse`.
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    /// `inverse` without the default's comparison against zero, the
+    /// only branch is on the result.
+    fn inv(&self) -> Result<Self, FieldError> {
+        self.inverse().ok_or(FieldError::DivisionByZero)
+    }
+
+    fn inv_fermat(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn serialize(&self) -> String {
+        self.to_biguint().to_string()
+    }
+
+    fn deserialize(str: &str) -> Self {
+        Self::from_str(str).unwrap()
+    }
+
+    fn to_bytes_le(&self) -> Vec<u8> {
+        // the bytes above byte_len are always zero
+        let mut bytes = self
+            .to_limbs()
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect::<Vec<_>>();
+        bytes.truncate(Self::byte_len());
+        bytes
+    }
+
+    fn from_bytes_le(bytes: &[u8]) -> Self {
+        crate::assert_byte_len::<Self>(bytes);
+        let mut limbs = [0_u64; LIMBS];
+        for (i, chunk) in bytes.chunks(8).enumerate() {
+            let mut limb = [0_u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            limbs[i] = u64::from_le_bytes(limb);
+        }
+        Self::from_limbs(limbs)
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Debug for MontFieldElement<P, LIMBS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", P::NAME, self.to_biguint())
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Display for MontFieldElement<P, LIMBS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> FromStr for MontFieldElement<P, LIMBS> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') {
+            return Err(());
+        }
+        let v = BigUint::from_str(s).map_err(|_| ())?;
+        Ok(Self::from_biguint(&v))
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Default for MontFieldElement<P, LIMBS> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> From<u64> for MontFieldElement<P, LIMBS> {
+    fn from(value: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value;
+        Self::from_limbs(limbs)
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Add for MontFieldElement<P, LIMBS> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut sum = [0; LIMBS];
+        let mut carry = 0;
+        for (i, limb) in sum.iter_mut().enumerate() {
+            (*limb, carry) = adc(self.limbs[i], other.limbs[i], carry);
+        }
+        let out = Self::from_raw(sub_mod(&sum, carry, &P::MODULUS));
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+        out
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Sub for MontFieldElement<P, LIMBS> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut diff = [0; LIMBS];
+        let mut borrow = 0;
+        for (i, limb) in diff.iter_mut().enumerate() {
+            (*limb, borrow) = sbb(self.limbs[i], other.limbs[i], borrow);
+        }
+        // add p back if the subtraction borrowed
+        let mask = 0_u64.wrapping_sub(borrow);
+        let mut carry = 0;
+        for (i, limb) in diff.iter_mut().enumerate() {
+            (*limb, carry) = adc(*limb, P::MODULUS[i] & mask, carry);
+        }
+        let out = Self::from_raw(diff);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+        out
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Mul for MontFieldElement<P, LIMBS> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let out = Self::from_raw(mont_mul(&self.limbs, &other.limbs, &P::MODULUS, Self::INV));
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+        out
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Div for MontFieldElement<P, LIMBS> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let out = match other.inverse() {
+            Some(inv) => self * inv,
+            None => panic!("Division by zero"),
+        };
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+        out
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> AddAssign for MontFieldElement<P, LIMBS> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> MulAssign for MontFieldElement<P, LIMBS> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> SubAssign for MontFieldElement<P, LIMBS> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<P: MontParams<LIMBS>, const LIMBS: usize> Neg for MontFieldElement<P, LIMBS> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let out = Self::zero() - self;
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+        out
+    }
+}
+
+/// Define a `MontFieldElement` type alias `$name` with parameters
+/// `$params` from the little endian limbs of a prime modulus. An even
+/// modulus or a zero most significant limb fails to compile:
+///
+/// ```compile_fail
+/// scalarff::mont_field!(F1024FieldElement, F1024Params, 1, [1024], "f1024");
+/// ```
+#[macro_export]
+macro_rules! mont_field {
+    ( $name: ident, $params: ident, $limbs: literal, $modulus: expr, $name_str: expr ) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+        pub struct $params;
+
+        impl $crate::montgomery::MontParams<$limbs> for $params {
+            const MODULUS: [u64; $limbs] = $modulus;
+            const NAME: &'static str = $name_str;
+        }
+
+        pub type $name = $crate::montgomery::MontFieldElement<$params, $limbs>;
+
+        // BYTE_LEN assumes the most significant limb is non-zero
+        const _: () = assert!(
+            <$params as $crate::montgomery::MontParams<$limbs>>::MODULUS[$limbs - 1] != 0,
+            "the most significant modulus limb must be non-zero"
+        );
+        // INV is the inverse of the low limb modulo 2^64
+        const _: () = assert!(
+            <$params as $crate::montgomery::MontParams<$limbs>>::MODULUS[0] & 1 == 1,
+            "the modulus must be odd"
+        );
+        const _: () = assert!(
+            <$name as $crate::FixedByteLen>::BYTE_LEN
+                == $crate::modulus_byte_len(
+                    &<$params as $crate::montgomery::MontParams<$limbs>>::MODULUS
+                ),
+            "BYTE_LEN does not match the modulus"
+        );
+    };
+}
+
+/// `a + b + carry`, returning the low limb and the carry.
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// `a - b - borrow`, returning the low limb and the borrow (0 or 1).
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+/// `a + b * c + carry`, returning the low limb and the carry.
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 * c as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Reduce `hi * 2^(64 * LIMBS) + v` where the value is `< 2p`.
+const fn sub_mod<const LIMBS: usize>(v: &[u64; LIMBS], hi: u64, p: &[u64; LIMBS]) -> [u64; LIMBS] {
+    let mut diff = [0; LIMBS];
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < LIMBS {
+        (diff[i], borrow) = sbb(v[i], p[i], borrow);
+        i += 1;
+    }
+    // keep v only if v < p, i.e. the subtraction borrowed and hi is 0
+    let keep = borrow & !hi & 1;
+    let mask = 0_u64.wrapping_sub(keep);
+    let mut i = 0;
+    while i < LIMBS {
+        diff[i] = (v[i] & mask) | (diff[i] & !mask);
+        i += 1;
+    }
+    diff
+}
+
+/// Montgomery multiplication `a * b * R^-1 mod p` (CIOS). Requires
+/// `a * b < p * R`, the result is fully reduced.
+fn mont_mul<const LIMBS: usize>(
+    a: &[u64; LIMBS],
+    b: &[u64; LIMBS],
+    p: &[u64; LIMBS],
+    inv: u64,
+) -> [u64; LIMBS] {
+    let mut t = [0_u64; LIMBS];
+    let mut t_hi = 0_u64;
+    for b_i in b {
+        let mut carry = 0;
+        for j in 0..LIMBS {
+            (t[j], carry) = mac(t[j], a[j], *b_i, carry);
+        }
+        let (t_n, t_n1) = adc(t_hi, carry, 0);
+        let m = t[0].wrapping_mul(inv);
+        let (_, mut carry) = mac(t[0], m, p[0], 0);
+        for j in 1..LIMBS {
+            (t[j - 1], carry) = mac(t[j], m, p[j], carry);
+        }
+        (t[LIMBS - 1], carry) = adc(t_n, carry, 0);
+        t_hi = t_n1 + carry;
+    }
+    sub_mod(&t, t_hi, p)
+}
+
+/// `-p^-1 mod 2^64` by Newton iteration, `p` must be odd.
+const fn neg_inv_u64(p: u64) -> u64 {
+    // correct to 1 bit, each iteration doubles the precision
+    let mut inv = 1_u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2_u64.wrapping_sub(p.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// `2^exp mod p` by repeated doubling.
+const fn pow2_mod<const LIMBS: usize>(exp: usize, p: &[u64; LIMBS]) -> [u64; LIMBS] {
+    let mut v = [0_u64; LIMBS];
+    v[0] = 1;
+    let mut i = 0;
+    while i < exp {
+        let mut doubled = [0_u64; LIMBS];
+        let mut carry = 0;
+        let mut j = 0;
+        while j < LIMBS {
+            (doubled[j], carry) = adc(v[j], v[j], carry);
+            j += 1;
+        }
+        v = sub_mod(&doubled, carry, p);
+        i += 1;
+    }
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mont_field!(
+        Bn254,
+        Bn254Params,
+        4,
+        [
+            0x43e1f593f0000001,
+            0x2833e84879b97091,
+            0xb85045b68181585d,
+            0x30644e72e131a029
+        ],
+        "bn254-mont"
+    );
+
+    mont_field!(Foi, FoiParams, 1, [0xffffffff00000001], "foi-mont");
+
+    mont_field!(F1009, F1009Params, 1, [1009], "f1009");
+
+    fn test_against_biguint<T: FieldElement>() {
+        let p = T::prime();
+        let mut x = BigUint::from(0x9e3779b97f4a7c15_u64) % &p;
+        let mut y = BigUint::from(0x2545f4914f6cdd1d_u64) % &p;
+        let mut state = 0x1234567_u64;
+        for _ in 0..200 {
+            let (a, b) = (T::from_biguint(&x), T::from_biguint(&y));
+            assert_eq!((a.clone() + b.clone()).to_biguint(), (&x + &y) % &p);
+            assert_eq!((a.clone() - b.clone()).to_biguint(), (&x + &p - &y) % &p);
+            assert_eq!((a.clone() * b.clone()).to_biguint(), (&x * &y) % &p);
+            assert_eq!((-a.clone()).to_biguint(), (&p - &x) % &p);
+            if y != BigUint::from(0_u32) {
+                assert_eq!(
+                    (a.clone() / b.clone()).to_biguint(),
+                    (&x * y.modpow(&(&p - 2_u32), &p)) % &p
+                );
+            }
+            assert_eq!(T::from_bytes_le(&a.to_bytes_le()), a);
+            // step through large values, including p - 1
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            x = (&x * &y + state) % &p;
+            y = (&p - 1_u32 - &x * 3_u32 % &p) % &p;
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        test_against_biguint::<Bn254>();
+        test_against_biguint::<Foi>();
+        test_against_biguint::<F1009>();
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(F1009::R, [((1_u128 << 64) % 1009) as u64]);
+        assert_eq!(F1009::R2, [((1_u128 << 64) % 1009).pow(2) as u64 % 1009]);
+        assert_eq!(Foi::INV.wrapping_mul(FoiParams::MODULUS[0]), u64::MAX);
+        assert_eq!(Bn254::byte_len(), 32);
+        assert_eq!(Foi::byte_len(), 8);
+        assert_eq!(F1009::byte_len(), 2);
+        assert_eq!(F1009::name_str(), "f1009");
+        assert_eq!(Foi::prime(), BigUint::from(0xffffffff00000001_u64));
+    }
+
+    #[test]
+    fn conversions() {
+        let p = Bn254::prime();
+        // values wider than byte_len are reduced by from_biguint only
+        let wide = BigUint::from(7_u32) * &p * &p + 5_u32;
+        assert_eq!(Bn254::from_biguint(&wide), Bn254::from(5));
+        let result = std::panic::catch_unwind(|| Bn254::from_bytes_le(&wide.to_bytes_le()));
+        assert!(result.is_err());
+        assert_eq!(
+            Bn254::from_limbs([u64::MAX; 4]).to_biguint(),
+            (BigUint::from(1_u32) << 256) % &p - 1_u32
+        );
+        assert_eq!(Bn254::deserialize("12345"), Bn254::from(12345));
+        assert_eq!(format!("{:?}", F1009::from(12)), "f1009(12)");
+        assert_eq!(F1009::zero().inverse(), None);
+        assert_eq!(F1009::from(1008).inverse(), Some(F1009::from(1008)));
+        assert_eq!(F1009::zero().inv(), Err(crate::FieldError::DivisionByZero));
+        assert_eq!(Bn254::inv_strategy(), InvStrategy::Fermat);
+        for i in 1..50 {
+            let x = Bn254::from(i * 7919);
+            assert_eq!(x.inv(), Ok(x.inverse().unwrap()));
+            assert_eq!(x.inv_euclid(), x.inverse());
+        }
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn matches_ark_bn254() {
+        use crate::Bn128FieldElement;
+        assert_eq!(Bn254::prime(), Bn128FieldElement::prime());
+        for i in 1..100_u64 {
+            let a = Bn254::from(i).inverse().unwrap() * Bn254::from(i * i + 7);
+            let b = Bn128FieldElement::from(i * i + 7) / Bn128FieldElement::from(i);
+            assert_eq!(a.to_bytes_le(), b.to_bytes_le());
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Multilinear polynomials stored as evaluations on the boolean
+//! hypercube.
+//!
+//! A `MultilinearPoly` in `n` variables is determined by its `2^n`
+//! values on `{0, 1}^n`. Evaluation `b` is the value at the point with
+//! coordinate `i` equal to bit `i` of `b`, the same order as
+//! `hypercube::eq_table`, so variable 0 is the least significant bit.
+//!
+//! `fix_first_variable` binds variable 0 in place, halving the table.
+//! Sumcheck provers call it once per round, see `sumcheck`.
+//!
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Sub;
+
+use super::hypercube::eq_table;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultilinearPoly<T: FieldElement> {
+    evaluations: Vec<T>,
+}
+
+impl<T: FieldElement> MultilinearPoly<T> {
+    /// The polynomial with the given evaluations on the hypercube.
+    /// Returns `None` if the length is not a power of two.
+    pub fn new(evaluations: Vec<T>) -> Option<Self> {
+        if !evaluations.len().is_power_of_two() {
+            return None;
+        }
+        Some(MultilinearPoly { evaluations })
+    }
+
+    /// The polynomial in `num_vars` variables with value `f(b)` at `b`.
+    pub fn from_fn(num_vars: usize, f: impl Fn(usize) -> T) -> Self {
+        MultilinearPoly {
+            evaluations: (0..1 << num_vars).map(f).collect(),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.evaluations.len().trailing_zeros() as usize
+    }
+
+    pub fn evaluations(&self) -> &[T] {
+        &self.evaluations
+    }
+
+    /// The sum of the evaluations over the hypercube.
+    pub fn sum(&self) -> T {
+        self.evaluations
+            .iter()
+            .fold(T::zero(), |acc, v| acc + v.clone())
+    }
+
+    /// Evaluate at any point, `sum_b f(b) eq(b, point)`. Panics if
+    /// the point doesn't have one coordinate per variable.
+    pub fn evaluate(&self, point: &[T]) -> T {
+        if point.len() != self.num_vars() {
+            panic!(
+                "expected a point with {} coordinates, got {}",
+                self.num_vars(),
+                point.len()
+            );
+        }
+        eq_table(point)
+            .into_iter()
+            .zip(&self.evaluations)
+            .fold(T::zero(), |acc, (e, v)| acc + e * v.clone())
+    }
+
+    /// Set variable 0 to `r`, leaving a polynomial in the remaining
+    /// variables. Panics if there are no variables.
+    pub fn fix_first_variable(&mut self, r: &T) {
+        if self.evaluations.len() == 1 {
+            panic!("cannot fix a variable of a constant polynomial");
+        }
+        let half = self.evaluations.len() / 2;
+        for j in 0..half {
+            let lo = self.evaluations[2 * j].clone();
+            let hi = self.evaluations[2 * j + 1].clone();
+            self.evaluations[j] = lo.clone() + r.clone() * (hi - lo);
+        }
+        self.evaluations.truncate(half);
+    }
+
+    /// Set the first `point.len()` variables.
+    pub fn fix_variables(&self, point: &[T]) -> Self {
+        let mut out = self.clone();
+        for r in point {
+            out.fix_first_variable(r);
+        }
+        out
+    }
+
+    pub fn assert_eq_vars(&self, other: &Self) {
+        if self.num_vars() != other.num_vars() {
+            panic!(
+                "multilinear polynomials have different numbers of variables: {} {}",
+                self.num_vars(),
+                other.num_vars()
+            );
+        }
+    }
+}
+
+impl<T: FieldElement> Add for MultilinearPoly<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.assert_eq_vars(&other);
+        MultilinearPoly {
+            evaluations: self
+                .evaluations
+                .into_iter()
+                .zip(other.evaluations)
+                .map(|(a, b)| a + b)
+                .collect(),
+        }
+    }
+}
+
+impl<T: FieldElement> Sub for MultilinearPoly<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.assert_eq_vars(&other);
+        MultilinearPoly {
+            evaluations: self
+                .evaluations
+                .into_iter()
+                .zip(other.evaluations)
+                .map(|(a, b)| a - b)
+                .collect(),
+        }
+    }
+}
+
+impl<T: FieldElement> Mul<T> for MultilinearPoly<T> {
+    type Output = Self;
+
+    fn mul(self, v: T) -> Self {
+        MultilinearPoly {
+            evaluations: self
+                .evaluations
+                .into_iter()
+                .map(|a| a * v.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    fn point(values: &[u64]) -> Vec<F1009FieldElement> {
+        values.iter().map(|v| F1009FieldElement::from(*v)).collect()
+    }
+
+    #[test]
+    fn evaluate() {
+        // f(x0, x1, x2) = 1 + 2 x0 + 3 x1 x2
+        let f = |x: &[F1009FieldElement]| {
+            F1009FieldElement::from(1)
+                + F1009FieldElement::from(2) * x[0]
+                + F1009FieldElement::from(3) * x[1] * x[2]
+        };
+        let bits = |b: usize| point(&[b as u64 & 1, (b as u64 >> 1) & 1, (b as u64 >> 2) & 1]);
+        let poly = MultilinearPoly::from_fn(3, |b| f(&bits(b)));
+        assert_eq!(poly.num_vars(), 3);
+        let r = point(&[5, 700, 33]);
+        assert_eq!(poly.evaluate(&r), f(&r));
+        assert_eq!(poly.fix_variables(&r).evaluations(), &[f(&r)]);
+        // binding a prefix leaves a polynomial in the other variables
+        let partial = poly.fix_variables(&r[..1]);
+        assert_eq!(partial.evaluate(&r[1..]), f(&r));
+        assert_eq!(poly.sum(), F1009FieldElement::from(8 + 8 + 6));
+        assert!(MultilinearPoly::new(point(&[1, 2, 3])).is_none());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = MultilinearPoly::new(point(&[1, 2, 3, 4])).unwrap();
+        let b = MultilinearPoly::new(point(&[5, 6, 7, 8])).unwrap();
+        let r = point(&[10, 20]);
+        let two = F1009FieldElement::from(2);
+        assert_eq!(
+            (a.clone() + b.clone() * two).evaluate(&r),
+            a.evaluate(&r) + b.evaluate(&r) * two
+        );
+        assert_eq!(
+            (a.clone() - b.clone()).evaluate(&r),
+            a.evaluate(&r) - b.evaluate(&r)
+        );
+    }
+}
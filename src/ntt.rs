@@ -0,0 +1,517 @@
+//! Number theoretic transforms over `FieldElement`.
+//!
+//! Both the Cooley-Tukey (decimation in time) and Gentleman-Sande
+//! (decimation in frequency) butterflies are exposed. Each variant
+//! operates in place and has a "native" ordering:
+//!   - Cooley-Tukey takes bit-reversed input and produces natural output
+//!   - Gentleman-Sande takes natural input and produces bit-reversed output
+//!
+//! Protocols like FRI folding consume evaluations in a specific order.
+//! Picking the variant whose native ordering matches avoids a separate
+//! permutation pass. When the requested ordering doesn't match the variant
+//! a bit-reversal permutation is applied.
+//!
+//! All functions accept a primitive `n`-th root of unity `omega` where
+//! `n` is the (power of two) length of the input.
+//!
+//! `lde` performs a coset low degree extension, the composite
+//! operation most STARK provers are built on.
+//!
+//! Domains whose size is not a power of two (e.g. `3*2^k`) can be
+//! transformed with `ntt_mixed_radix` as long as the field contains
+//! a root of unity of the needed order.
+//!
+//! `transform` and `inverse_transform` pick the root of unity and the
+//! algorithm from the field (`FieldElement::root_of_unity`): radix-2
+//! for powers of two, mixed radix for other smooth lengths and a naive
+//! `dft` when the length has a large prime factor.
+//!
+//! With the `parallel` feature large transforms are split across
+//! threads, see the `parallel` module.
+//!
+use super::parallel::for_each_chunk_mut;
+use super::tuning::tuning;
+use super::FieldElement;
+
+/// The butterfly structure used to compute a transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// Decimation in time. Natively bit-reversed -> natural.
+    CooleyTukey,
+    /// Decimation in frequency. Natively natural -> bit-reversed.
+    GentlemanSande,
+}
+
+/// The order of a set of values in the transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Natural,
+    BitReversed,
+}
+
+/// Permute a slice into bit-reversed order. Applying this
+/// twice returns the original order. The slice length must be
+/// a power of two.
+pub fn bit_reverse_permute<T>(values: &mut [T]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "ntt length must be a power of two");
+    let log_n = n.trailing_zeros();
+    if log_n == 0 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Cooley-Tukey transform. Expects the input in bit-reversed order and
+/// leaves the output in natural order.
+pub fn ntt_dit<T: FieldElement>(values: &mut [T], omega: &T) {
+    let n = values.len();
+    trace_span!("ntt_dit", field = T::name_str(), n = n);
+    assert!(n.is_power_of_two(), "ntt length must be a power of two");
+    let cutoff = tuning().parallel_cutoff;
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let w_len = omega.pow_u64((n / len) as u64);
+        for_each_chunk_mut(values, len, cutoff, |_, block| {
+            let (lo, hi) = block.split_at_mut(half);
+            let mut w = T::one();
+            for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                let u = a.clone();
+                let v = b.clone() * w.clone();
+                *a = u.clone() + v.clone();
+                *b = u - v;
+                w *= w_len.clone();
+            }
+        });
+        len <<= 1;
+    }
+}
+
+/// Gentleman-Sande transform. Expects the input in natural order and
+/// leaves the output in bit-reversed order.
+pub fn ntt_dif<T: FieldElement>(values: &mut [T], omega: &T) {
+    let n = values.len();
+    trace_span!("ntt_dif", field = T::name_str(), n = n);
+    assert!(n.is_power_of_two(), "ntt length must be a power of two");
+    let cutoff = tuning().parallel_cutoff;
+    let mut len = n;
+    while len >= 2 {
+        let half = len / 2;
+        let w_len = omega.pow_u64((n / len) as u64);
+        for_each_chunk_mut(values, len, cutoff, |_, block| {
+            let (lo, hi) = block.split_at_mut(half);
+            let mut w = T::one();
+            for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                let u = a.clone();
+                let v = b.clone();
+                *a = u.clone() + v.clone();
+                *b = (u - v) * w.clone();
+                w *= w_len.clone();
+            }
+        });
+        len >>= 1;
+    }
+}
+
+/// Forward transform using the chosen variant. The input and output
+/// orders are permuted only if they differ from the variant's native
+/// ordering.
+pub fn ntt<T: FieldElement>(
+    values: &mut [T],
+    omega: &T,
+    variant: Variant,
+    input: Order,
+    output: Order,
+) {
+    match variant {
+        Variant::CooleyTukey => {
+            if input == Order::Natural {
+                bit_reverse_permute(values);
+            }
+            ntt_dit(values, omega);
+            if output == Order::BitReversed {
+                bit_reverse_permute(values);
+            }
+        }
+        Variant::GentlemanSande => {
+            if input == Order::BitReversed {
+                bit_reverse_permute(values);
+            }
+            ntt_dif(values, omega);
+            if output == Order::Natural {
+                bit_reverse_permute(values);
+            }
+        }
+    }
+}
+
+/// Inverse transform using the chosen variant. `omega` is the same root
+/// of unity passed to the forward transform.
+pub fn intt<T: FieldElement>(
+    values: &mut [T],
+    omega: &T,
+    variant: Variant,
+    input: Order,
+    output: Order,
+) {
+    let omega_inv = T::one() / omega.clone();
+    ntt(values, &omega_inv, variant, input, output);
+    let n_inv = T::one() / T::from_usize(values.len());
+    for v in values.iter_mut() {
+        *v *= n_inv.clone();
+    }
+}
+
+/// Mixed radix forward transform for inputs of any length `n`. The
+/// length is factored into primes and each prime is used as a radix.
+/// `omega` must be a primitive `n`-th root of unity. Radix-2/3/5 domains
+/// are the intended use, larger prime factors fall back to a naive
+/// transform at that level.
+pub fn ntt_mixed_radix<T: FieldElement>(values: &[T], omega: &T) -> Vec<T> {
+    let n = values.len();
+    trace_span!("ntt_mixed_radix", field = T::name_str(), n = n);
+    if n <= 1 {
+        return values.to_vec();
+    }
+    let r = smallest_factor(n);
+    let m = n / r;
+    // transform each of the r decimated subsequences using omega^r
+    let omega_r = omega.pow_u64(r as u64);
+    let sub = (0..r)
+        .map(|k| {
+            let decimated = values
+                .iter()
+                .skip(k)
+                .step_by(r)
+                .cloned()
+                .collect::<Vec<_>>();
+            ntt_mixed_radix(&decimated, &omega_r)
+        })
+        .collect::<Vec<_>>();
+    // combine with a size r transform using the r-th root omega^m
+    let root_r = omega.pow_u64(m as u64);
+    let mut out = vec![T::zero(); n];
+    let mut twiddle_s = T::one();
+    for s in 0..m {
+        let mut twiddled = Vec::with_capacity(r);
+        let mut twiddle = T::one();
+        for sub_k in &sub {
+            twiddled.push(sub_k[s].clone() * twiddle.clone());
+            twiddle *= twiddle_s.clone();
+        }
+        let mut w_t = T::one();
+        for t in 0..r {
+            let mut acc = T::zero();
+            for v in twiddled.iter().rev() {
+                acc = acc * w_t.clone() + v.clone();
+            }
+            out[s + t * m] = acc;
+            w_t *= root_r.clone();
+        }
+        twiddle_s *= omega.clone();
+    }
+    out
+}
+
+/// Mixed radix inverse transform. `omega` is the same root
+/// of unity passed to the forward transform.
+pub fn intt_mixed_radix<T: FieldElement>(values: &[T], omega: &T) -> Vec<T> {
+    let omega_inv = T::one() / omega.clone();
+    let n_inv = T::one() / T::from_usize(values.len());
+    ntt_mixed_radix(values, &omega_inv)
+        .into_iter()
+        .map(|v| v * n_inv.clone())
+        .collect()
+}
+
+/// Naive `O(n^2)` discrete Fourier transform,
+/// `out[k] = sum_j values[j] * omega^(jk)`. Works for any
+/// length given a primitive `n`-th root of unity.
+pub fn dft<T: FieldElement>(values: &[T], omega: &T) -> Vec<T> {
+    let mut out = Vec::with_capacity(values.len());
+    let mut w_k = T::one();
+    for _ in 0..values.len() {
+        // horner evaluation at omega^k
+        let mut acc = T::zero();
+        for v in values.iter().rev() {
+            acc = acc * w_k.clone() + v.clone();
+        }
+        out.push(acc);
+        w_k *= omega.clone();
+    }
+    out
+}
+
+/// Forward transform of any length in natural order, using the
+/// primitive root `T::root_of_unity(n)`. Returns `None` if the field
+/// contains no root of unity of order `n`.
+pub fn transform<T: FieldElement>(values: &[T]) -> Option<Vec<T>> {
+    let n = values.len();
+    if n <= 1 {
+        return Some(values.to_vec());
+    }
+    let omega = T::root_of_unity(n as u64)?;
+    Some(transform_with_root(values, &omega))
+}
+
+/// Inverse of `transform`.
+pub fn inverse_transform<T: FieldElement>(values: &[T]) -> Option<Vec<T>> {
+    let n = values.len();
+    if n <= 1 {
+        return Some(values.to_vec());
+    }
+    let omega = T::root_of_unity(n as u64)?;
+    let n_inv = T::one() / T::from_usize(n);
+    Some(
+        transform_with_root(values, &(T::one() / omega))
+            .into_iter()
+            .map(|v| v * n_inv.clone())
+            .collect(),
+    )
+}
+
+// above this prime factor mixed radix is no better than a dft
+const MAX_RADIX: usize = 7;
+
+fn transform_with_root<T: FieldElement>(values: &[T], omega: &T) -> Vec<T> {
+    let n = values.len();
+    if n.is_power_of_two() {
+        let mut out = values.to_vec();
+        ntt(
+            &mut out,
+            omega,
+            Variant::GentlemanSande,
+            Order::Natural,
+            Order::Natural,
+        );
+        return out;
+    }
+    if largest_factor(n) <= MAX_RADIX {
+        ntt_mixed_radix(values, omega)
+    } else {
+        dft(values, omega)
+    }
+}
+
+/// Coset low degree extension. `values` are evaluations of a polynomial
+/// over the size `n` subgroup (natural order). The output is the
+/// evaluation of the same polynomial over the coset `coset_shift * H`
+/// where `H` is the subgroup of size `n * blowup` (natural order).
+///
+/// Roots of unity are derived from the field, so `n * blowup` must be
+/// a power of two dividing `p - 1`.
+pub fn lde<T: FieldElement>(values: &[T], blowup: usize, coset_shift: T) -> Vec<T> {
+    let n = values.len();
+    let extended_n = n * blowup;
+    let mut out = vec![T::zero(); extended_n];
+    out[..n].clone_from_slice(values);
+    intt(
+        &mut out[..n],
+        &root_of_unity::<T>(n),
+        Variant::GentlemanSande,
+        Order::Natural,
+        Order::Natural,
+    );
+    let mut shift_pow = T::one();
+    for coef in out[..n].iter_mut() {
+        *coef *= shift_pow.clone();
+        shift_pow *= coset_shift.clone();
+    }
+    ntt(
+        &mut out,
+        &root_of_unity::<T>(extended_n),
+        Variant::GentlemanSande,
+        Order::Natural,
+        Order::Natural,
+    );
+    out
+}
+
+/// A primitive `n`-th root of unity where `n` is a power of two.
+fn root_of_unity<T: FieldElement>(n: usize) -> T {
+    assert!(n.is_power_of_two(), "ntt length must be a power of two");
+    T::two_adic_generator(n.trailing_zeros())
+}
+
+fn smallest_factor(n: usize) -> usize {
+    let mut f = 2;
+    while f * f <= n {
+        if n.is_multiple_of(f) {
+            return f;
+        }
+        f += 1;
+    }
+    n
+}
+
+fn largest_factor(mut n: usize) -> usize {
+    let mut largest = 1;
+    while n > 1 {
+        let f = smallest_factor(n);
+        largest = f;
+        n /= f;
+    }
+    largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 is a generator of the multiplicative group mod 17
+    scalar_ring!(F17FieldElement, 17_u128, "f17");
+    // 3 is a generator of the multiplicative group mod 31
+    scalar_ring!(F31FieldElement, 31_u128, "f31");
+    // 2^16 + 1, large enough to cross the parallel cutoff
+    scalar_ring!(F65537FieldElement, 65537_u128, "f65537");
+
+    fn omega(n: usize) -> F17FieldElement {
+        F17FieldElement::from(3).pow_u64((16 / n) as u64)
+    }
+
+    #[test]
+    fn variants_and_orders() {
+        for n in [1, 2, 4, 8, 16] {
+            let input = (0..n)
+                .map(|i| F17FieldElement::from((i * 5 + 1) as u64))
+                .collect::<Vec<_>>();
+            let w = omega(n);
+            let expected = dft(&input, &w);
+            let mut expected_br = expected.clone();
+            bit_reverse_permute(&mut expected_br);
+            let mut input_br = input.clone();
+            bit_reverse_permute(&mut input_br);
+            for variant in [Variant::CooleyTukey, Variant::GentlemanSande] {
+                for input_order in [Order::Natural, Order::BitReversed] {
+                    for output_order in [Order::Natural, Order::BitReversed] {
+                        let mut values = match input_order {
+                            Order::Natural => input.clone(),
+                            Order::BitReversed => input_br.clone(),
+                        };
+                        ntt(&mut values, &w, variant, input_order, output_order);
+                        match output_order {
+                            Order::Natural => assert_eq!(values, expected),
+                            Order::BitReversed => assert_eq!(values, expected_br),
+                        }
+                        intt(&mut values, &w, variant, output_order, Order::Natural);
+                        assert_eq!(values, input);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_transform() {
+        let n = 1 << 13;
+        let input = (0..n)
+            .map(|i| F65537FieldElement::from((i * i + 3) as u64))
+            .collect::<Vec<_>>();
+        let w = root_of_unity::<F65537FieldElement>(n);
+        let mut dit = input.clone();
+        ntt(
+            &mut dit,
+            &w,
+            Variant::CooleyTukey,
+            Order::Natural,
+            Order::Natural,
+        );
+        let mut dif = input.clone();
+        ntt(
+            &mut dif,
+            &w,
+            Variant::GentlemanSande,
+            Order::Natural,
+            Order::Natural,
+        );
+        assert_eq!(dit, dif);
+        // spot check against the definition
+        for i in [0, 1, 77, n - 1] {
+            let x = w.pow_u64(i as u64);
+            let mut acc = F65537FieldElement::zero();
+            for v in input.iter().rev() {
+                acc = acc * x + *v;
+            }
+            assert_eq!(dit[i], acc);
+        }
+        intt(
+            &mut dit,
+            &w,
+            Variant::CooleyTukey,
+            Order::Natural,
+            Order::Natural,
+        );
+        assert_eq!(dit, input);
+    }
+
+    #[test]
+    fn mixed_radix() {
+        for n in [2, 3, 5, 6, 10, 15, 30] {
+            let input = (0..n)
+                .map(|i| F31FieldElement::from((i * 7 + 3) as u64))
+                .collect::<Vec<_>>();
+            let w = F31FieldElement::from(3).pow_u64((30 / n) as u64);
+            let out = ntt_mixed_radix(&input, &w);
+            assert_eq!(out, dft(&input, &w));
+            assert_eq!(intt_mixed_radix(&out, &w), input);
+        }
+    }
+
+    // p - 1 = 2 * 23
+    scalar_ring!(F47FieldElement, 47_u128, "f47");
+
+    #[test]
+    fn generic_transform() {
+        for n in [0, 1, 2, 3, 5, 6, 10, 15, 30] {
+            let input = (0..n)
+                .map(|i| F31FieldElement::from((i * 7 + 3) as u64))
+                .collect::<Vec<_>>();
+            let out = transform(&input).unwrap();
+            if n > 1 {
+                let w = F31FieldElement::root_of_unity(n as u64).unwrap();
+                assert_eq!(out, dft(&input, &w));
+            }
+            assert_eq!(inverse_transform(&out).unwrap(), input);
+        }
+        // 4 doesn't divide 30
+        assert!(transform(&[F31FieldElement::one(); 4]).is_none());
+        // 23 is too large for mixed radix
+        let input = (0..23).map(F47FieldElement::from).collect::<Vec<_>>();
+        let out = transform(&input).unwrap();
+        assert_eq!(inverse_transform(&out).unwrap(), input);
+        let w = F47FieldElement::root_of_unity(23).unwrap();
+        assert_eq!(out, ntt_mixed_radix(&input, &w));
+    }
+
+    #[test]
+    fn low_degree_extension() {
+        let values = (0..4)
+            .map(|i| F17FieldElement::from(i * i + 2))
+            .collect::<Vec<_>>();
+        let shift = F17FieldElement::from(3);
+        let extended = lde(&values, 4, shift);
+        let mut coefs = values.clone();
+        intt(
+            &mut coefs,
+            &omega(4),
+            Variant::CooleyTukey,
+            Order::Natural,
+            Order::Natural,
+        );
+        let w = omega(16);
+        for (i, v) in extended.iter().enumerate() {
+            let x = shift * w.pow_u64(i as u64);
+            let mut acc = F17FieldElement::zero();
+            for c in coefs.iter().rev() {
+                acc = acc * x + *c;
+            }
+            assert_eq!(*v, acc);
+        }
+    }
+}
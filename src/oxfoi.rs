@@ -12,21 +12,134 @@ use std::ops::SubAssign;
 use std::str::FromStr;
 
 use twenty_first::math::b_field_element::BFieldElement;
+use twenty_first::math::traits::PrimitiveRootOfUnity;
 
 use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, Debug)]
 pub struct OxfoiFieldElement(BFieldElement);
 
+// BFieldElement is stored in montgomery form, compare
+// and hash the canonical value
+impl PartialEq for OxfoiFieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.value() == other.0.value()
+    }
+}
+
+impl Hash for OxfoiFieldElement {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.value().hash(state);
+    }
+}
+
+/// Constants and shortcuts specific to the structure of
+/// `p = 2^64 - 2^32 + 1`. `p - 1 = 2^32 (2^32 - 1)`, and `2` has
+/// order 192, so multiplying by a root of unity of order dividing
+/// 192 is a multiplication by a power of two (a shift and a cheap
+/// reduction) instead of a general multiplication.
+impl OxfoiFieldElement {
+    /// A primitive `2^32`-th root of unity, the same root used by
+    /// twenty-first and plonky2. Equal to `two_adic_generator(32)`.
+    pub const TWO_ADIC_ROOT_OF_UNITY: u64 = 1753635133440165772;
+
+    /// `7^(2^32)`, a generator of the multiplicative subgroup of
+    /// order `2^32 - 1`. `7` generates the whole multiplicative group.
+    pub const ODD_SUBGROUP_GENERATOR: u64 = 12275445934081160404;
+
+    pub fn odd_subgroup_generator() -> Self {
+        Self(BFieldElement::new(Self::ODD_SUBGROUP_GENERATOR))
+    }
+
+    /// The root of unity `2^(192/n)` of order `n`. Returns `None`
+    /// unless `n` divides 192, e.g. 64 gives `8`.
+    pub fn small_root_of_unity(n: u32) -> Option<Self> {
+        if n == 0 || 192 % n != 0 {
+            return None;
+        }
+        Some(Self::one().mul_pow2(192 / n))
+    }
+
+    /// Multiply by `small_root_of_unity(n)^k` using shifts.
+    /// Panics unless `n` divides 192.
+    pub fn mul_small_root_pow(&self, n: u32, k: u64) -> Self {
+        if n == 0 || 192 % n != 0 {
+            panic!("oxfoi has no power of two root of unity of order {n}");
+        }
+        let exp = (u64::from(192 / n) * (k % u64::from(n))) as u32;
+        self.mul_pow2(exp)
+    }
+}
+
+impl FixedByteLen for OxfoiFieldElement {
+    const BYTE_LEN: usize = 8;
+}
+
 impl FieldElement for OxfoiFieldElement {
     fn byte_len() -> usize {
-        8
+        Self::BYTE_LEN
     }
 
     fn name_str() -> &'static str {
         "oxfoi"
     }
 
+    fn backend_str() -> &'static str {
+        "twenty-first 0.41"
+    }
+
+    fn modpow_bytes(&self, exp_le: &[u8]) -> Self {
+        let significant = exp_le.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        if significant > 8 {
+            // x^(p-1) = 1, reduce the exponent unless the base is zero
+            if self.0.value() == 0 {
+                return Self::zero();
+            }
+            let e = num_bigint::BigUint::from_bytes_le(exp_le) % (BFieldElement::P - 1);
+            return Self(self.0.mod_pow(e.try_into().unwrap()));
+        }
+        let mut bytes = [0_u8; 8];
+        bytes[..significant].copy_from_slice(&exp_le[..significant]);
+        Self(self.0.mod_pow(u64::from_le_bytes(bytes)))
+    }
+
+    fn inv_strategy() -> InvStrategy {
+        InvStrategy::Fermat
+    }
+
+    fn two_adicity() -> u32 {
+        32
+    }
+
+    fn generator() -> Self {
+        OxfoiFieldElement::from(7)
+    }
+
+    fn two_adic_root_of_unity() -> Self {
+        Self(BFieldElement::new(Self::TWO_ADIC_ROOT_OF_UNITY))
+    }
+
+    fn two_adic_generator(k: u32) -> Self {
+        // twenty-first keeps a table of roots for every power of two
+        match 1_u64
+            .checked_shl(k)
+            .and_then(BFieldElement::primitive_root_of_unity)
+        {
+            Some(g) => OxfoiFieldElement(g),
+            None => panic!("oxfoi does not contain a subgroup of order 2^{k}"),
+        }
+    }
+
+    fn is_small_field() -> bool {
+        true
+    }
+
+    fn fits_in_u64() -> bool {
+        true
+    }
+
     fn prime() -> num_bigint::BigUint {
         num_bigint::BigUint::from(BFieldElement::P)
     }
@@ -36,7 +149,27 @@ impl FieldElement for OxfoiFieldElement {
     }
 
     fn deserialize(str: &str) -> Self {
-        Self(BFieldElement::from_str(str).unwrap())
+        Self::from_str(str).unwrap()
+    }
+
+    // 2^96 = -1 mod p so powers of two have order 192
+    fn mul_pow2(&self, k: u32) -> Self {
+        let mut k = k % 192;
+        let mut v = self.0.value();
+        if k >= 96 {
+            v = (-self.0).value();
+            k -= 96;
+        }
+        while k > 0 {
+            let shift = k.min(64);
+            v = reduce_u128(u128::from(v) << shift);
+            k -= shift;
+        }
+        Self(BFieldElement::new(v))
+    }
+
+    fn div_pow2(&self, k: u32) -> Self {
+        self.mul_pow2(192 - k % 192)
     }
 
     fn to_bytes_le(&self) -> Vec<u8> {
@@ -45,10 +178,8 @@ impl FieldElement for OxfoiFieldElement {
 
     fn from_bytes_le(bytes: &[u8]) -> Self {
         const BYTES_SIZE: usize = 8;
+        crate::assert_byte_len::<Self>(bytes);
         let mut sized_bytes = [0_u8; BYTES_SIZE];
-        if bytes.len() > BYTES_SIZE {
-            panic!("incorrect number of bytes passed to Curve25519FieldElement: expected {BYTES_SIZE} got {}", bytes.len());
-        }
         for x in 0..BYTES_SIZE {
             if x < bytes.len() {
                 sized_bytes[x] = bytes[x];
@@ -58,6 +189,26 @@ impl FieldElement for OxfoiFieldElement {
     }
 }
 
+/// Reduce a u128 using `2^64 = 2^32 - 1` and `2^96 = -1`.
+/// The output is congruent to the input but may be >= p.
+fn reduce_u128(x: u128) -> u64 {
+    const EPSILON: u64 = 0xffff_ffff;
+    let lo = x as u64;
+    let hi = (x >> 64) as u64;
+    let hi_hi = hi >> 32;
+    let hi_lo = hi & EPSILON;
+    let (mut t, borrow) = lo.overflowing_sub(hi_hi);
+    if borrow {
+        // wrapped around 2^64, 2^64 - p = EPSILON
+        t = t.wrapping_sub(EPSILON);
+    }
+    let (mut out, carry) = t.overflowing_add(hi_lo * EPSILON);
+    if carry {
+        out = out.wrapping_add(EPSILON);
+    }
+    out
+}
+
 impl Display for OxfoiFieldElement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -68,7 +219,18 @@ impl FromStr for OxfoiFieldElement {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(OxfoiFieldElement(BFieldElement::from_str(s).unwrap()))
+        if s.starts_with('+') {
+            return Err(());
+        }
+        BFieldElement::from_str(s)
+            .map(OxfoiFieldElement)
+            .map_err(|_| ())
+    }
+}
+
+impl Default for OxfoiFieldElement {
+    fn default() -> Self {
+        Self::zero()
     }
 }
 
@@ -82,7 +244,10 @@ impl Add for OxfoiFieldElement {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        OxfoiFieldElement(self.0 + other.0)
+        let out = OxfoiFieldElement(self.0 + other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_native_oxfoi(crate::model_check::Op::Add, &self, &other, &out);
+        out
     }
 }
 
@@ -90,7 +255,10 @@ impl Sub for OxfoiFieldElement {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        OxfoiFieldElement(self.0 - other.0)
+        let out = OxfoiFieldElement(self.0 - other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_native_oxfoi(crate::model_check::Op::Sub, &self, &other, &out);
+        out
     }
 }
 
@@ -98,7 +266,10 @@ impl Mul for OxfoiFieldElement {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        OxfoiFieldElement(self.0 * other.0)
+        let out = OxfoiFieldElement(self.0 * other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_native_oxfoi(crate::model_check::Op::Mul, &self, &other, &out);
+        out
     }
 }
 
@@ -106,7 +277,10 @@ impl Div for OxfoiFieldElement {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        OxfoiFieldElement(self.0 / other.0)
+        let out = OxfoiFieldElement(self.0 / other.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_native_oxfoi(crate::model_check::Op::Div, &self, &other, &out);
+        out
     }
 }
 
@@ -132,6 +306,48 @@ impl Neg for OxfoiFieldElement {
     type Output = Self;
 
     fn neg(self) -> Self {
-        OxfoiFieldElement(-self.0)
+        let out = OxfoiFieldElement(-self.0);
+        #[cfg(feature = "verify-backend")]
+        crate::verify::check_native_oxfoi(crate::model_check::Op::Neg, &self, &self, &out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_roots() {
+        let root = OxfoiFieldElement::two_adic_root_of_unity();
+        assert_eq!(root, OxfoiFieldElement::two_adic_generator(32));
+
+        let g = OxfoiFieldElement::odd_subgroup_generator();
+        let order = (1_u64 << 32) - 1;
+        assert_eq!(
+            g.modpow_bytes(&order.to_le_bytes()),
+            OxfoiFieldElement::one()
+        );
+        // 2^32 - 1 = 3 * 5 * 17 * 257 * 65537
+        for q in [3, 5, 17, 257, 65537] {
+            let e = order / q;
+            assert_ne!(g.modpow_bytes(&e.to_le_bytes()), OxfoiFieldElement::one());
+        }
+
+        assert_eq!(
+            OxfoiFieldElement::small_root_of_unity(64),
+            Some(OxfoiFieldElement::from(8))
+        );
+        assert_eq!(OxfoiFieldElement::small_root_of_unity(128), None);
+        let x = -OxfoiFieldElement::from(123456789);
+        for n in [1, 2, 3, 4, 6, 8, 64, 96, 192] {
+            let w = OxfoiFieldElement::small_root_of_unity(n).unwrap();
+            assert_eq!(w.modpow_bytes(&n.to_le_bytes()), OxfoiFieldElement::one());
+            let mut expected = x;
+            for k in 0..(2 * n as u64) {
+                assert_eq!(x.mul_small_root_pow(n, k), expected);
+                expected *= w;
+            }
+        }
     }
 }
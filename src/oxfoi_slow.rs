@@ -1,4 +1,3 @@
-use super::scalar_ring;
 use super::FieldElement;
 
 scalar_ring!(OxfoiFieldElement, 18446744069414584321, "oxfoi");
@@ -0,0 +1,100 @@
+//! Control over the threads used by parallel algorithms.
+//!
+//! With the `parallel` feature enabled NTTs and matrix multiplication
+//! split large inputs across a rayon thread pool. By default rayon's
+//! global pool is used. Applications that need to bound CPU usage
+//! (e.g. when embedded in an async runtime) can either size a dedicated
+//! pool with `set_parallelism` or hand over their own pool with
+//! `set_thread_pool`. The size at which work is split across threads
+//! is `Tuning::parallel_cutoff`.
+//!
+//! Without the `parallel` feature everything runs on the calling thread.
+//!
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+#[cfg(feature = "parallel")]
+use std::sync::RwLock;
+
+#[cfg(feature = "parallel")]
+pub use rayon::ThreadPool;
+#[cfg(feature = "parallel")]
+pub use rayon::ThreadPoolBuildError;
+
+#[cfg(feature = "parallel")]
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Run parallel work on a dedicated pool of `threads` threads.
+#[cfg(feature = "parallel")]
+pub fn set_parallelism(threads: usize) -> Result<(), ThreadPoolBuildError> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    set_thread_pool(Arc::new(pool));
+    Ok(())
+}
+
+/// Run parallel work on an existing pool.
+#[cfg(feature = "parallel")]
+pub fn set_thread_pool(pool: Arc<ThreadPool>) {
+    *POOL.write().unwrap() = Some(pool);
+}
+
+/// Go back to using the rayon global pool.
+#[cfg(feature = "parallel")]
+pub fn reset_thread_pool() {
+    *POOL.write().unwrap() = None;
+}
+
+/// Run `f` inside the configured pool so rayon iterators
+/// used by `f` are bounded by it.
+#[cfg(feature = "parallel")]
+pub(crate) fn install<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    let pool = POOL.read().unwrap().clone();
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+/// Apply `f` to each `chunk_size` chunk of `values` along with
+/// the index of the chunk. Runs in parallel when `values` has at
+/// least `cutoff` elements, see `Tuning::parallel_cutoff`.
+#[allow(unused_variables)]
+pub(crate) fn for_each_chunk_mut<T: Send>(
+    values: &mut [T],
+    chunk_size: usize,
+    cutoff: usize,
+    f: impl Fn(usize, &mut [T]) + Send + Sync,
+) {
+    #[cfg(feature = "parallel")]
+    if values.len() >= cutoff && values.len() > chunk_size {
+        use rayon::prelude::*;
+        install(|| {
+            values
+                .par_chunks_mut(chunk_size)
+                .enumerate()
+                .for_each(|(i, chunk)| f(i, chunk))
+        });
+        return;
+    }
+    values
+        .chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(i, chunk)| f(i, chunk));
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_pool_is_used() {
+        set_parallelism(2).unwrap();
+        assert_eq!(install(rayon::current_num_threads), 2);
+        reset_thread_pool();
+        assert_eq!(
+            install(rayon::current_num_threads),
+            rayon::current_num_threads()
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Scalar fields of the Pasta cycle of curves, as used by halo2.
+//! Requires the `pallas` and/or `vesta` feature.
+//!
+//!   - `PallasFieldElement` - the scalar field of Pallas, `Fq`
+//!   - `VestaFieldElement` - the scalar field of Vesta, `Fp`
+//!
+//! The scalar field of each curve is the base field of the other.
+//! Both have two-adicity 32.
+//!
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use ff::Field;
+use ff::PrimeField;
+use num_bigint::BigUint;
+
+use super::FieldElement;
+use super::FixedByteLen;
+use super::InvStrategy;
+
+macro_rules! pasta_field {
+    ( $name: ident, $scalar: ty, $name_str: expr ) => {
+        #[derive(Clone, Copy, Eq, PartialEq)]
+        pub struct $name($scalar);
+
+        // elements are always reduced so the repr is canonical
+        impl Hash for $name {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.to_repr().hash(state);
+            }
+        }
+
+        impl $name {
+            /// Decode a canonical little endian encoding. Returns `None`
+            /// if the encoded value is not less than the modulus.
+            pub fn from_canonical_bytes(bytes: [u8; 32]) -> Option<Self> {
+                Option::from(<$scalar>::from_repr(bytes)).map($name)
+            }
+        }
+
+        impl FixedByteLen for $name {
+            const BYTE_LEN: usize = 32;
+        }
+
+        impl FieldElement for $name {
+            fn name_str() -> &'static str {
+                $name_str
+            }
+
+            fn backend_str() -> &'static str {
+                "pasta_curves 0.5"
+            }
+
+            fn modpow_bytes(&self, exp_le: &[u8]) -> Self {
+                $name(self.0.pow_vartime(super::le_bytes_to_limbs(exp_le)))
+            }
+
+            fn is_small_field() -> bool {
+                false
+            }
+
+            fn fits_in_u64() -> bool {
+                false
+            }
+
+            fn prime() -> BigUint {
+                BigUint::from_bytes_le((-<$scalar>::ONE).to_repr().as_ref()) + 1_u32
+            }
+
+            fn inv_strategy() -> InvStrategy {
+                InvStrategy::Fermat
+            }
+
+            fn two_adicity() -> u32 {
+                <$scalar>::S
+            }
+
+            fn generator() -> Self {
+                $name(<$scalar>::MULTIPLICATIVE_GENERATOR)
+            }
+
+            fn two_adic_generator(k: u32) -> Self {
+                if k > <$scalar>::S {
+                    panic!("{} does not contain a subgroup of order 2^{k}", $name_str);
+                }
+                let mut g = <$scalar>::ROOT_OF_UNITY;
+                for _ in k..<$scalar>::S {
+                    g = g.square();
+                }
+                $name(g)
+            }
+
+            fn checked_sqrt(&self) -> Option<Self> {
+                // ff picks either root, return the smaller one like other fields
+                Option::from(self.0.sqrt()).map(|root| super::sqrt::canonical_root($name(root)))
+            }
+
+            fn serialize(&self) -> String {
+                self.to_string()
+            }
+
+            fn deserialize(str: &str) -> Self {
+                Self::from_str(str).unwrap()
+            }
+
+            fn byte_len() -> usize {
+                Self::BYTE_LEN
+            }
+
+            fn to_bytes_le(&self) -> Vec<u8> {
+                self.0.to_repr().as_ref().to_vec()
+            }
+
+            fn from_bytes_le(bytes: &[u8]) -> Self {
+                $crate::assert_byte_len::<Self>(bytes);
+                // canonical encodings (e.g. from to_bytes_le) skip the reduction
+                let mut repr = [0_u8; 32];
+                repr[..bytes.len()].copy_from_slice(bytes);
+                if let Some(v) = Self::from_canonical_bytes(repr) {
+                    return v;
+                }
+                let v = BigUint::from_bytes_le(bytes) % Self::prime();
+                let mut repr = [0_u8; 32];
+                let v_bytes = v.to_bytes_le();
+                repr[..v_bytes.len()].copy_from_slice(&v_bytes);
+                Self::from_canonical_bytes(repr).unwrap()
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", BigUint::from_bytes_le(self.0.to_repr().as_ref()))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.starts_with('+') {
+                    return Err(());
+                }
+                // from_str_vartime rejects leading zeroes, the other
+                // implementations accept them
+                let trimmed = s.trim_start_matches('0');
+                if s.is_empty() {
+                    Err(())
+                } else if trimmed.is_empty() {
+                    Ok(Self::zero())
+                } else {
+                    <$scalar>::from_str_vartime(trimmed).map($name).ok_or(())
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                $name(<$scalar>::from(value))
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                let out = $name(self.0 + other.0);
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Add, &self, &other, &out);
+                out
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                let out = $name(self.0 - other.0);
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Sub, &self, &other, &out);
+                out
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                let out = $name(self.0 * other.0);
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Mul, &self, &other, &out);
+                out
+            }
+        }
+
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        impl Div for $name {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                let inv: Option<$scalar> = other.0.invert().into();
+                let out = $name(self.0 * inv.expect("Division by zero"));
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Div, &self, &other, &out);
+                out
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl MulAssign for $name {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                let out = $name(-self.0);
+                #[cfg(feature = "verify-backend")]
+                crate::verify::check_model(crate::model_check::Op::Neg, &self, &self, &out);
+                out
+            }
+        }
+    };
+}
+
+#[cfg(feature = "pallas")]
+pasta_field!(PallasFieldElement, pasta_curves::pallas::Scalar, "pallas");
+#[cfg(feature = "vesta")]
+pasta_field!(VestaFieldElement, pasta_curves::vesta::Scalar, "vesta");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pasta<T: FieldElement>(modulus_hex: &str) {
+        let p = BigUint::parse_bytes(modulus_hex.as_bytes(), 16).unwrap();
+        assert_eq!(T::prime(), p);
+        assert!(crate::model_check::check::<T>(7, 2_000).is_ok());
+        assert_eq!(T::two_adicity(), 32);
+        let g = T::two_adic_generator(32);
+        assert_eq!(g.pow(&(BigUint::from(1_u32) << 31)), -T::one());
+        // a generator is a non-residue
+        assert_eq!(T::generator().legendre(), -1);
+        // reduction of the modulus, a wide value and leading zeroes
+        assert_eq!(T::from_bytes_le(&p.to_bytes_le()), T::zero());
+        let wide = &p * &p * 3_u32 + 5_u32;
+        assert_eq!(T::from_biguint(&wide), T::from(5));
+        let result = std::panic::catch_unwind(|| T::from_bytes_le(&wide.to_bytes_le()));
+        assert!(result.is_err());
+        assert_eq!(T::deserialize("0042"), T::from(42));
+        let x = T::from(31337);
+        let root = (x.clone() * x.clone()).sqrt();
+        assert!(root == x || root == -x);
+    }
+
+    #[cfg(feature = "pallas")]
+    #[test]
+    fn pallas() {
+        test_pasta::<PallasFieldElement>(
+            "40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001",
+        );
+    }
+
+    #[cfg(feature = "vesta")]
+    #[test]
+    fn vesta() {
+        test_pasta::<VestaFieldElement>(
+            "40000000000000000000000000000000224698fc094cf91b992d30ed00000001",
+        );
+    }
+}
@@ -0,0 +1,720 @@
+//! A univariate polynomial structure with coefficients
+//! in a `RingElement`.
+//!
+//! Arithmetic (add/sub/mul/eval) only requires ring operations
+//! so polynomials over composite moduli (e.g. `Z_q[x]`) are supported.
+//! Methods that need inverses of arbitrary coefficients are
+//! only implemented when the coefficients are a `FieldElement`.
+//!
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+use super::combinatorics::FactorialTable;
+use super::matrix::Matrix;
+use super::ntt::intt;
+use super::ntt::ntt;
+use super::ntt::Order;
+use super::ntt::Variant;
+use super::tuning::tuning;
+use super::tuning::Tuning;
+use super::FieldElement;
+use super::RingElement;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial<T: RingElement> {
+    // lowest degree first, trailing zeroes are trimmed
+    // the zero polynomial has no coefficients
+    coefficients: Vec<T>,
+}
+
+impl<T: RingElement> Polynomial<T> {
+    /// Build a polynomial from coefficients ordered
+    /// lowest degree first.
+    pub fn new(coefficients: Vec<T>) -> Self {
+        let mut out = Polynomial { coefficients };
+        out.trim();
+        out
+    }
+
+    pub fn zero() -> Self {
+        Polynomial {
+            coefficients: vec![],
+        }
+    }
+
+    /// The monomial `coefficient * x^degree`.
+    pub fn monomial(coefficient: T, degree: usize) -> Self {
+        let mut coefficients = vec![T::from(0); degree];
+        coefficients.push(coefficient);
+        Self::new(coefficients)
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The degree of the polynomial. The zero polynomial
+    /// is considered to have degree 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> T {
+        self.coefficients.last().cloned().unwrap_or(T::from(0))
+    }
+
+    /// Evaluate the polynomial at a point using Horner's method.
+    pub fn eval(&self, x: &T) -> T {
+        let mut out = T::from(0);
+        for c in self.coefficients.iter().rev() {
+            out = out * x.clone() + c.clone();
+        }
+        out
+    }
+
+    pub fn mul_scalar(&self, v: &T) -> Self {
+        Self::new(
+            self.coefficients
+                .iter()
+                .map(|c| c.clone() * v.clone())
+                .collect(),
+        )
+    }
+
+    /// Divide by a monic polynomial. This only needs ring operations
+    /// so it works over rings with non-invertible elements.
+    /// Returns `(quotient, remainder)`.
+    pub fn div_rem_monic(&self, divisor: &Self) -> (Self, Self) {
+        if divisor.leading_coefficient() != T::from(1) {
+            panic!("divisor is not monic: {divisor}");
+        }
+        self.div_rem_by(divisor, |c| c)
+    }
+
+    fn div_rem_by(&self, divisor: &Self, scale: impl Fn(T) -> T) -> (Self, Self) {
+        if divisor.is_zero() {
+            panic!("polynomial division by zero");
+        }
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return (Self::zero(), self.clone());
+        }
+        let divisor_degree = divisor.degree();
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![T::from(0); remainder.len() - divisor_degree];
+        for i in (0..quotient.len()).rev() {
+            let q = scale(remainder[i + divisor_degree].clone());
+            for (j, d) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] -= q.clone() * d.clone();
+            }
+            quotient[i] = q;
+        }
+        remainder.truncate(divisor_degree);
+        (Self::new(quotient), Self::new(remainder))
+    }
+
+    /// Multiply using the supplied thresholds to choose between
+    /// schoolbook and Karatsuba multiplication.
+    pub fn mul_with(&self, other: &Self, tuning: &Tuning) -> Self {
+        Self::new(karatsuba(
+            &self.coefficients,
+            &other.coefficients,
+            tuning.karatsuba_threshold.max(1),
+        ))
+    }
+
+    fn trim(&mut self) {
+        let zero = T::from(0);
+        while self.coefficients.last() == Some(&zero) {
+            self.coefficients.pop();
+        }
+    }
+}
+
+impl<T: FieldElement> Polynomial<T> {
+    /// Divide by an arbitrary non-zero polynomial. Returns
+    /// `(quotient, remainder)`. Panics if the divisor is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        // check before inverting the leading coefficient, backends
+        // panic with their own message on division by zero
+        if divisor.is_zero() {
+            panic!("polynomial division by zero");
+        }
+        let lead_inv = T::one() / divisor.leading_coefficient();
+        self.div_rem_by(divisor, |c| c * lead_inv.clone())
+    }
+
+    /// The unique polynomial of degree less than `n` passing through
+    /// the `n` points `(xs[i], ys[i])`, using Lagrange interpolation.
+    /// Panics if the `xs` are not distinct.
+    pub fn interpolate(xs: &[T], ys: &[T]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "interpolation needs one y per x");
+        let weights = xs
+            .iter()
+            .enumerate()
+            .map(|(i, xi)| {
+                let mut denominator = T::one();
+                for (j, xj) in xs.iter().enumerate() {
+                    if i != j {
+                        denominator *= xi.clone() - xj.clone();
+                    }
+                }
+                if denominator == T::zero() {
+                    panic!("interpolation points are not distinct");
+                }
+                T::one() / denominator
+            })
+            .collect::<Vec<_>>();
+        Self::from_barycentric(xs, &weights, ys)
+    }
+
+    /// Interpolate over the domain `{0, 1, ..., n-1}`, i.e. the
+    /// polynomial `p` of degree less than `n` with `p(i) = values[i]`.
+    /// The Lagrange denominators come from a factorial table instead
+    /// of `O(n^2)` products and inversions.
+    pub fn interpolate_consecutive(values: &[T]) -> Self {
+        let xs = (0..values.len()).map(T::from_usize).collect::<Vec<_>>();
+        let weights = consecutive_weights(values.len());
+        Self::from_barycentric(&xs, &weights, values)
+    }
+
+    /// Evaluate at `x` the polynomial interpolating `values` over
+    /// `{0, 1, ..., n-1}` without computing its coefficients. `O(n)`.
+    pub fn eval_consecutive(values: &[T], x: &T) -> T {
+        let n = values.len();
+        let weights = consecutive_weights::<T>(n);
+        // prefix[i] = (x - 0)...(x - (i-1)), suffix[i] = (x - (i+1))...(x - (n-1))
+        let mut prefix = vec![T::one(); n + 1];
+        for i in 0..n {
+            prefix[i + 1] = prefix[i].clone() * (x.clone() - T::from_usize(i));
+        }
+        let mut suffix = T::one();
+        let mut out = T::zero();
+        for i in (0..n).rev() {
+            out += values[i].clone() * weights[i].clone() * prefix[i].clone() * suffix.clone();
+            suffix *= x.clone() - T::from_usize(i);
+        }
+        out
+    }
+
+    /// `sum_i ys[i] * weights[i] * prod_{j != i} (x - xs[j])`
+    fn from_barycentric(xs: &[T], weights: &[T], ys: &[T]) -> Self {
+        // the vanishing polynomial of the xs
+        let vanishing = xs.iter().fold(Self::from(1), |acc, x| {
+            acc * Self::new(vec![-x.clone(), T::one()])
+        });
+        let mut coefficients = vec![T::zero(); xs.len()];
+        for ((x, w), y) in xs.iter().zip(weights).zip(ys) {
+            let (basis, _) = vanishing.div_rem_monic(&Self::new(vec![-x.clone(), T::one()]));
+            let scale = w.clone() * y.clone();
+            for (c, b) in coefficients.iter_mut().zip(basis.coefficients) {
+                *c += b * scale.clone();
+            }
+        }
+        Self::new(coefficients)
+    }
+
+    /// Evaluate the polynomial at a square matrix using Horner's
+    /// method, `c_0 I + c_1 M + c_2 M^2 + ...`. Panics if `m` is
+    /// not a square 2 dimensional matrix.
+    pub fn eval_matrix(&self, m: &Matrix<T>) -> Matrix<T> {
+        if m.dimensions.len() != 2 || m.dimensions[0] != m.dimensions[1] {
+            panic!(
+                "cannot evaluate a polynomial at a non-square matrix: {:?}",
+                m.dimensions
+            );
+        }
+        let n = m.dimensions[0];
+        let mut out = Matrix {
+            dimensions: vec![n, n],
+            values: vec![T::zero(); n * n],
+        };
+        for c in self.coefficients.iter().rev() {
+            out = out.matmul(m).expect("square matrices of equal size");
+            for i in 0..n {
+                out.values[i * n + i] += c.clone();
+            }
+        }
+        out
+    }
+
+    /// Multiply using the global `Tuning`, see `fast_mul_with`.
+    pub fn fast_mul(&self, other: &Self) -> Self {
+        self.fast_mul_with(other, &tuning())
+    }
+
+    /// Multiply choosing between schoolbook, Karatsuba and NTT
+    /// multiplication. The `*` operator only uses ring operations
+    /// so it never uses an NTT.
+    pub fn fast_mul_with(&self, other: &Self, tuning: &Tuning) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        let out_len = self.coefficients.len() + other.coefficients.len() - 1;
+        let n = out_len.next_power_of_two();
+        if out_len < tuning.ntt_threshold || n.trailing_zeros() > T::two_adicity() {
+            return self.mul_with(other, tuning);
+        }
+        let omega = T::two_adic_generator(n.trailing_zeros());
+        let transform = |coefficients: &[T]| {
+            let mut values = coefficients.to_vec();
+            values.resize(n, T::zero());
+            ntt(
+                &mut values,
+                &omega,
+                Variant::GentlemanSande,
+                Order::Natural,
+                Order::BitReversed,
+            );
+            values
+        };
+        let mut values = transform(&self.coefficients);
+        for (v, w) in values.iter_mut().zip(transform(&other.coefficients)) {
+            *v *= w;
+        }
+        intt(
+            &mut values,
+            &omega,
+            Variant::CooleyTukey,
+            Order::BitReversed,
+            Order::Natural,
+        );
+        values.truncate(out_len);
+        Self::new(values)
+    }
+}
+
+/// The soundness, in bits, targeted by `check_identity`.
+pub const IDENTITY_SOUNDNESS_BITS: u32 = 128;
+
+/// The number of independent uniform evaluations needed to tell two
+/// distinct polynomials of degree at most `degree` apart except with
+/// probability `2^-soundness_bits`. By Schwartz–Zippel a non-zero
+/// difference vanishes at a uniform point with probability at most
+/// `degree / p`.
+///
+/// Returns `None` if a single evaluation gives less than one bit,
+/// i.e. `degree > p / 2`. Random evaluation is useless in such small
+/// fields, e.g. `x^p - x` vanishes everywhere.
+pub fn identity_repetitions<T: FieldElement>(degree: usize, soundness_bits: u32) -> Option<usize> {
+    if degree == 0 {
+        // the difference is a constant
+        return Some(1);
+    }
+    // degree / p <= 1 / floor(p / degree) <= 2^-floor(log2(p / degree))
+    let per_round = (T::prime() / degree).bits().saturating_sub(1);
+    if per_round == 0 {
+        return None;
+    }
+    Some(u64::from(soundness_bits).div_ceil(per_round).max(1) as usize)
+}
+
+/// Probabilistically check `lhs == rhs` by comparing evaluations at
+/// random points, with enough repetitions for `IDENTITY_SOUNDNESS_BITS`
+/// of soundness. Falls back to comparing coefficients when the field
+/// is too small for evaluation to be meaningful, see
+/// `identity_repetitions`. Requires the `random` feature.
+#[cfg(feature = "random")]
+pub fn check_identity<T: FieldElement, R: rand::Rng>(
+    lhs: &Polynomial<T>,
+    rhs: &Polynomial<T>,
+    src: &mut R,
+) -> bool {
+    let degree = lhs.degree().max(rhs.degree());
+    match identity_repetitions::<T>(degree, IDENTITY_SOUNDNESS_BITS) {
+        Some(repetitions) => check_identity_with(lhs, rhs, repetitions, src),
+        None => lhs == rhs,
+    }
+}
+
+/// `check_identity` with an explicit number of evaluations. Distinct
+/// polynomials pass with probability at most `(degree / p)^repetitions`.
+/// Requires the `random` feature.
+#[cfg(feature = "random")]
+pub fn check_identity_with<T: FieldElement, R: rand::Rng>(
+    lhs: &Polynomial<T>,
+    rhs: &Polynomial<T>,
+    repetitions: usize,
+    src: &mut R,
+) -> bool {
+    (0..repetitions).all(|_| {
+        let x = T::sample_uniform(src);
+        lhs.eval(&x) == rhs.eval(&x)
+    })
+}
+
+/// Barycentric weights `1 / prod_{j != i} (i - j)` of the
+/// domain `{0, 1, ..., n-1}`, equal to `(-1)^(n-1-i) / (i! (n-1-i)!)`.
+fn consecutive_weights<T: FieldElement>(n: usize) -> Vec<T> {
+    if n == 0 {
+        return vec![];
+    }
+    let table = FactorialTable::<T>::new(n - 1);
+    (0..n)
+        .map(|i| {
+            let w = table.inv_factorial(i) * table.inv_factorial(n - 1 - i);
+            if (n - 1 - i).is_multiple_of(2) {
+                w
+            } else {
+                -w
+            }
+        })
+        .collect()
+}
+
+/// Multiply coefficient slices, recursing with Karatsuba until
+/// the shorter input has at most `threshold` coefficients.
+fn karatsuba<T: RingElement>(a: &[T], b: &[T], threshold: usize) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![T::from(0); a.len() + b.len() - 1];
+    if a.len().min(b.len()) <= threshold {
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                out[i + j] += x.clone() * y.clone();
+            }
+        }
+        return out;
+    }
+    // a = a0 + x^m a1, b = b0 + x^m b1
+    let m = a.len().min(b.len()) / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+    let z0 = karatsuba(a0, b0, threshold);
+    let z2 = karatsuba(a1, b1, threshold);
+    let z1 = karatsuba(&add_slices(a0, a1), &add_slices(b0, b1), threshold);
+    for (i, c) in z0.iter().enumerate() {
+        out[i] += c.clone();
+        out[i + m] -= c.clone();
+    }
+    for (i, c) in z2.iter().enumerate() {
+        out[i + 2 * m] += c.clone();
+        out[i + m] -= c.clone();
+    }
+    for (i, c) in z1.into_iter().enumerate() {
+        out[i + m] += c;
+    }
+    out
+}
+
+fn add_slices<T: RingElement>(a: &[T], b: &[T]) -> Vec<T> {
+    let (mut longer, shorter) = if a.len() >= b.len() {
+        (a.to_vec(), b)
+    } else {
+        (b.to_vec(), a)
+    };
+    for (i, c) in shorter.iter().enumerate() {
+        longer[i] += c.clone();
+    }
+    longer
+}
+
+impl<T: RingElement> Add for Polynomial<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (mut longer, shorter) = if self.coefficients.len() >= other.coefficients.len() {
+            (self.coefficients, other.coefficients)
+        } else {
+            (other.coefficients, self.coefficients)
+        };
+        for (i, c) in shorter.into_iter().enumerate() {
+            longer[i] += c;
+        }
+        Self::new(longer)
+    }
+}
+
+impl<T: RingElement> AddAssign for Polynomial<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl<T: RingElement> Sub for Polynomial<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<T: RingElement> SubAssign for Polynomial<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+impl<T: RingElement> Mul for Polynomial<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.mul_with(&other, &tuning())
+    }
+}
+
+impl<T: RingElement> MulAssign for Polynomial<T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
+
+/// Scale every coefficient by `v`, same as `mul_scalar`.
+impl<T: RingElement> Mul<T> for Polynomial<T> {
+    type Output = Self;
+
+    fn mul(self, v: T) -> Self {
+        self.mul_scalar(&v)
+    }
+}
+
+impl<T: RingElement> MulAssign<T> for Polynomial<T> {
+    fn mul_assign(&mut self, v: T) {
+        *self = self.mul_scalar(&v);
+    }
+}
+
+impl<T: RingElement> Neg for Polynomial<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Polynomial {
+            coefficients: self.coefficients.into_iter().map(|c| -c).collect(),
+        }
+    }
+}
+
+impl<T: RingElement> Default for Polynomial<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: RingElement> From<u64> for Polynomial<T> {
+    fn from(v: u64) -> Self {
+        Self::new(vec![T::from(v)])
+    }
+}
+
+impl<T: RingElement> RingElement for Polynomial<T> {}
+
+impl<T: RingElement> Display for Polynomial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| **c != T::from(0))
+            .map(|(i, c)| match i {
+                0 => format!("{c}"),
+                1 => format!("{c}x"),
+                _ => format!("{c}x^{i}"),
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // composite modulus, not a field
+    scalar_ring!(Z12RingElement, 12_u128, "z12");
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+    scalar_ring!(F17FieldElement, 17_u128, "f17");
+
+    fn poly<T: RingElement>(coefs: &[u64]) -> Polynomial<T> {
+        Polynomial::new(coefs.iter().map(|c| T::from(*c)).collect())
+    }
+
+    #[test]
+    fn scalar_mul() {
+        let p = poly::<F13FieldElement>(&[1, 2, 12]);
+        assert_eq!(p.clone() * F13FieldElement::from(3), poly(&[3, 6, 10]));
+        let mut q = p.clone();
+        q *= F13FieldElement::from(3);
+        assert_eq!(q, p.mul_scalar(&F13FieldElement::from(3)));
+        // zero divisors can lower the degree
+        let mut r = poly::<Z12RingElement>(&[1, 6]);
+        r *= Z12RingElement::from(2);
+        assert_eq!(r, poly(&[2]));
+        assert_eq!(r.degree(), 0);
+    }
+
+    #[test]
+    fn ring_arithmetic() {
+        // (2x + 3) * (6x + 4) = 12x^2 + 26x + 12 = 2x in z12
+        let a = poly::<Z12RingElement>(&[3, 2]);
+        let b = poly::<Z12RingElement>(&[4, 6]);
+        let c = a.clone() * b.clone();
+        assert_eq!(c, poly(&[0, 2]));
+        assert_eq!(c.degree(), 1);
+        assert_eq!(a.clone() - a.clone(), Polynomial::zero());
+        assert_eq!(a + b, poly(&[7, 8]));
+        for x in 0..12 {
+            let x = Z12RingElement::from(x);
+            assert_eq!(c.eval(&x), x + x);
+        }
+    }
+
+    #[test]
+    fn multiplication_algorithms() {
+        let a = Polynomial::new((0..70).map(|i| Z12RingElement::from(i * 5 + 1)).collect());
+        let b = Polynomial::new((0..45).map(|i| Z12RingElement::from(i * i)).collect());
+        let schoolbook = Tuning {
+            karatsuba_threshold: usize::MAX,
+            ..Tuning::DEFAULT
+        };
+        let expected = a.mul_with(&b, &schoolbook);
+        assert_eq!(expected.degree(), 69 + 44);
+        for threshold in [0, 1, 2, 5, 32] {
+            let tuning = Tuning {
+                karatsuba_threshold: threshold,
+                ..Tuning::DEFAULT
+            };
+            assert_eq!(a.mul_with(&b, &tuning), expected);
+        }
+    }
+
+    #[test]
+    fn ntt_multiplication() {
+        // p - 1 = 2^4, products with up to 16 coefficients use an ntt
+        let a = poly::<F17FieldElement>(&[1, 2, 3, 4, 5, 6, 7]);
+        let b = poly::<F17FieldElement>(&[9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        let tuning = Tuning {
+            ntt_threshold: 0,
+            ..Tuning::DEFAULT
+        };
+        assert_eq!(a.fast_mul_with(&b, &tuning), a.clone() * b.clone());
+        // too large for the two-adic subgroup, falls back
+        let c = poly::<F17FieldElement>(&[3; 12]);
+        assert_eq!(a.fast_mul_with(&c, &tuning), a * c);
+    }
+
+    #[test]
+    fn eval_matrix() {
+        // fibonacci matrix, annihilated by its characteristic polynomial x^2 - x - 1
+        let m = Matrix {
+            dimensions: vec![2, 2],
+            values: [1, 1, 1, 0].map(F13FieldElement::from).to_vec(),
+        };
+        let charpoly = Polynomial::new(vec![
+            -F13FieldElement::one(),
+            -F13FieldElement::one(),
+            F13FieldElement::one(),
+        ]);
+        let zero = Matrix {
+            dimensions: vec![2, 2],
+            values: vec![F13FieldElement::zero(); 4],
+        };
+        assert_eq!(charpoly.eval_matrix(&m), zero);
+        // x^5 gives fibonacci numbers F6 F5 F5 F4
+        let x5 = Polynomial::monomial(F13FieldElement::one(), 5);
+        assert_eq!(
+            x5.eval_matrix(&m).values,
+            [8, 5, 5, 3].map(F13FieldElement::from)
+        );
+        assert_eq!(
+            Polynomial::from(7).eval_matrix(&m),
+            Matrix::identity(2).mul_scalar(F13FieldElement::from(7))
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let f = poly::<F13FieldElement>(&[4, 0, 9, 1, 2]);
+        let xs = [3, 5, 7, 9, 11].map(F13FieldElement::from);
+        let ys = xs.map(|x| f.eval(&x));
+        assert_eq!(Polynomial::interpolate(&xs, &ys), f);
+        let values = (0..5)
+            .map(|i| f.eval(&F13FieldElement::from(i)))
+            .collect::<Vec<_>>();
+        assert_eq!(Polynomial::interpolate_consecutive(&values), f);
+        for x in 0..13 {
+            let x = F13FieldElement::from(x);
+            assert_eq!(Polynomial::eval_consecutive(&values, &x), f.eval(&x));
+        }
+        assert!(Polynomial::<F13FieldElement>::interpolate_consecutive(&[]).is_zero());
+    }
+
+    #[test]
+    fn ring_monic_division() {
+        let divisor = poly::<Z12RingElement>(&[5, 1]);
+        let dividend = poly::<Z12RingElement>(&[1, 3, 4, 6]);
+        let (q, r) = dividend.div_rem_monic(&divisor);
+        assert_eq!(q * divisor + r.clone(), dividend);
+        assert!(r.degree() < 1);
+    }
+
+    #[test]
+    fn field_division() {
+        let divisor = poly::<F13FieldElement>(&[5, 0, 7]);
+        let dividend = poly::<F13FieldElement>(&[1, 3, 4, 6, 9, 11]);
+        let (q, r) = dividend.div_rem(&divisor);
+        assert_eq!(q * divisor + r.clone(), dividend);
+        assert!(r.degree() < 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "polynomial division by zero")]
+    fn field_division_by_zero() {
+        poly::<F13FieldElement>(&[1, 2]).div_rem(&Polynomial::zero());
+    }
+
+    #[test]
+    fn identity_repetition_counts() {
+        scalar_ring!(Goldilocks, 18446744069414584321_u128, "goldilocks");
+        // floor(log2(13)) = 3 bits per evaluation of a degree 1 difference
+        assert_eq!(identity_repetitions::<F13FieldElement>(1, 128), Some(43));
+        // one bit up to p / 2
+        assert_eq!(identity_repetitions::<F13FieldElement>(6, 128), Some(128));
+        assert_eq!(identity_repetitions::<F13FieldElement>(7, 128), None);
+        assert_eq!(identity_repetitions::<F13FieldElement>(0, 128), Some(1));
+        scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+        assert_eq!(
+            identity_repetitions::<F1009FieldElement>(300, 128),
+            Some(128)
+        );
+        assert_eq!(identity_repetitions::<F1009FieldElement>(505, 128), None);
+        assert_eq!(identity_repetitions::<Goldilocks>(1 << 20, 128), Some(3));
+        assert_eq!(identity_repetitions::<Goldilocks>(1 << 20, 40), Some(1));
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn identity_checks() {
+        scalar_ring!(Goldilocks, 18446744069414584321_u128, "goldilocks");
+        let mut rng = rand::thread_rng();
+        // (x + 1)^3 = x^3 + 3x^2 + 3x + 1
+        let x_plus_one = poly::<Goldilocks>(&[1, 1]);
+        let lhs = x_plus_one.clone() * x_plus_one.clone() * x_plus_one;
+        assert!(check_identity(&lhs, &poly(&[1, 3, 3, 1]), &mut rng));
+        assert!(!check_identity(&lhs, &poly(&[1, 3, 3, 2]), &mut rng));
+        assert!(!check_identity_with(
+            &lhs,
+            &poly(&[2, 3, 3, 1]),
+            1,
+            &mut rng
+        ));
+        // x^13 - x vanishes on all of F13, evaluation can't see it
+        let x13 = Polynomial::monomial(F13FieldElement::one(), 13);
+        let x = Polynomial::monomial(F13FieldElement::one(), 1);
+        assert!(check_identity_with(&x13, &x, 20, &mut rng));
+        assert!(!check_identity(&x13, &x, &mut rng));
+    }
+}
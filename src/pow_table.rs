@@ -0,0 +1,148 @@
+//! Fixed-base exponentiation with precomputed windows.
+//!
+//! Protocols often raise the same base (a generator, a commitment key
+//! element) to many different exponents. `PowTable::new(base, w)`
+//! stores `base^(d 2^(i w))` for every window `i` and digit
+//! `d < 2^w`, after which `pow` costs one multiplication per non-zero
+//! digit of the exponent and no squarings, e.g. 32 multiplications
+//! for a 254 bit exponent with `w = 8` instead of about 380 with
+//! square and multiply.
+//!
+//! The table holds `ceil(bits / w) 2^w` elements where `bits` is the
+//! bit length of `prime() - 1`. Exponents are reduced mod
+//! `prime() - 1`, the order of the multiplicative group.
+//!
+use num_bigint::BigUint;
+
+use super::FieldElement;
+
+/// The largest supported window, `2^16` elements per window.
+pub const MAX_WINDOW_BITS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowTable<T: FieldElement> {
+    base: T,
+    window_bits: usize,
+    // windows[i][d] = base^(d 2^(i w))
+    windows: Vec<Vec<T>>,
+}
+
+impl<T: FieldElement> PowTable<T> {
+    /// Precompute the powers of `base` for windows of `window_bits`
+    /// bits. Panics unless `1 <= window_bits <= MAX_WINDOW_BITS`.
+    pub fn new(base: T, window_bits: usize) -> Self {
+        if !(1..=MAX_WINDOW_BITS).contains(&window_bits) {
+            panic!("window_bits must be in 1..={MAX_WINDOW_BITS}, got {window_bits}");
+        }
+        let bits = (T::prime() - 1_u32).bits() as usize;
+        let count = bits.div_ceil(window_bits).max(1);
+        let mut windows = Vec::with_capacity(count);
+        // base^(2^(i w))
+        let mut b = base.clone();
+        for _ in 0..count {
+            let mut window = Vec::with_capacity(1 << window_bits);
+            window.push(T::one());
+            for d in 1..1 << window_bits {
+                window.push(window[d - 1].clone() * b.clone());
+            }
+            b = window[(1 << window_bits) - 1].clone() * b;
+            windows.push(window);
+        }
+        PowTable {
+            base,
+            window_bits,
+            windows,
+        }
+    }
+
+    pub fn base(&self) -> &T {
+        &self.base
+    }
+
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// The number of precomputed elements.
+    pub fn len(&self) -> usize {
+        self.windows.len() << self.window_bits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// `base^exp`, equal to `base.pow(exp)`.
+    pub fn pow(&self, exp: &BigUint) -> T {
+        if self.base == T::zero() {
+            return if exp.bits() == 0 { T::one() } else { T::zero() };
+        }
+        let exp = exp % (T::prime() - 1_u32);
+        let w = self.window_bits;
+        let mut out = T::one();
+        for (i, window) in self.windows.iter().enumerate() {
+            let digit = (0..w).fold(0, |d, j| {
+                d | (usize::from(exp.bit((i * w + j) as u64)) << j)
+            });
+            if digit != 0 {
+                out *= window[digit].clone();
+            }
+        }
+        out
+    }
+
+    /// `base^exp` for a `u64` exponent.
+    pub fn pow_u64(&self, exp: u64) -> T {
+        self.pow(&BigUint::from(exp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    fn test_pow_table<T: FieldElement>(window_bits: &[usize]) {
+        let base = T::from(123456789);
+        let p = T::prime();
+        let exponents = [0_u64, 1, 2, 255, 256, 1 << 40, u64::MAX]
+            .map(BigUint::from)
+            .into_iter()
+            .chain([&p - 2_u32, &p - 1_u32, p.clone(), &p * 3_u32 + 7_u32]);
+        for w in window_bits {
+            let table = PowTable::new(base.clone(), *w);
+            for e in exponents.clone() {
+                assert_eq!(table.pow(&e), base.pow(&e), "window {w} exponent {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn pow_table_scalar_ring() {
+        test_pow_table::<F1009FieldElement>(&[1, 2, 3, 4, 10, 11]);
+        let zero = PowTable::new(F1009FieldElement::zero(), 4);
+        assert_eq!(zero.pow_u64(0), F1009FieldElement::one());
+        assert_eq!(zero.pow_u64(1008), F1009FieldElement::zero());
+        // 1008 has 10 bits, 3 windows of 4 bits
+        assert_eq!(zero.len(), 3 * 16);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn pow_table_bn128() {
+        test_pow_table::<crate::Bn128FieldElement>(&[1, 4, 8]);
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn pow_table_foi() {
+        test_pow_table::<crate::OxfoiFieldElement>(&[5, 16]);
+    }
+
+    #[test]
+    #[should_panic(expected = "window_bits")]
+    fn zero_window() {
+        PowTable::new(F1009FieldElement::from(2), 0);
+    }
+}
@@ -0,0 +1,167 @@
+//! Precomputed quadratic residue bitmaps for small moduli.
+//!
+//! `ResidueTable` stores one bit per element of a field or ring with
+//! modulus below `MAX_RESIDUE_TABLE_MODULUS`, so a table for the largest
+//! supported modulus takes 2 MiB. Building it squares half of the
+//! elements, after which `is_qr` is a bit lookup instead of an
+//! exponentiation. Useful for exhaustive analyses of small fields.
+//!
+//! The table records which elements are squares, it works for composite
+//! moduli where the Legendre symbol is undefined. Fields that are not
+//! prime order (binary and extension fields) are squared with field
+//! arithmetic and indexed by `element_index`.
+//!
+use std::marker::PhantomData;
+
+use num_bigint::BigUint;
+
+use super::FieldElement;
+
+/// Moduli must be less than this to build a `ResidueTable`.
+pub const MAX_RESIDUE_TABLE_MODULUS: u64 = 1 << 24;
+
+/// A bitmap of the non-zero squares modulo `T::prime()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResidueTable<T: FieldElement> {
+    modulus: u64,
+    bits: Vec<u64>,
+    count: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: FieldElement> ResidueTable<T> {
+    /// Build the table. Returns `None` if the modulus is not less
+    /// than `MAX_RESIDUE_TABLE_MODULUS`.
+    pub fn new() -> Option<Self> {
+        let modulus = u64::try_from(T::prime())
+            .ok()
+            .filter(|p| *p < MAX_RESIDUE_TABLE_MODULUS)?;
+        let mut bits = vec![0_u64; modulus.div_ceil(64) as usize];
+        let mut set = |square: u64| {
+            if square != 0 {
+                bits[(square / 64) as usize] |= 1 << (square % 64);
+            }
+        };
+        if T::characteristic() == T::prime() {
+            // (p - y)^2 = y^2, half of the elements cover every square
+            for y in 1..=modulus / 2 {
+                // y^2 < 2^48
+                set((y * y) % modulus);
+            }
+        } else {
+            // the integer lift is not a ring map, square in the field
+            for y in 1..modulus {
+                let y = T::nth_element(&BigUint::from(y));
+                set(Self::index(&(y.clone() * y)));
+            }
+        }
+        let count = bits.iter().map(|w| w.count_ones() as usize).sum();
+        Some(ResidueTable {
+            modulus,
+            bits,
+            count,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The modulus the table was built for.
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// The number of non-zero squares, `(p - 1) / 2` for an odd prime.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Whether `x` is a non-zero square.
+    pub fn is_qr(&self, x: &T) -> bool {
+        self.is_qr_u64(Self::index(x))
+    }
+
+    /// Whether the element with index `x`, reduced modulo the modulus,
+    /// is a non-zero square. For prime order fields and rings the index
+    /// is the integer lift, see `FieldElement::element_index`.
+    pub fn is_qr_u64(&self, x: u64) -> bool {
+        let x = x % self.modulus;
+        self.bits[(x / 64) as usize] >> (x % 64) & 1 == 1
+    }
+
+    /// `0` for zero, `1` for a non-zero square and `-1` otherwise.
+    /// Equal to the Legendre symbol when the modulus is an odd prime.
+    pub fn symbol(&self, x: &T) -> i32 {
+        let i = Self::index(x);
+        if i == 0 {
+            0
+        } else if self.is_qr_u64(i) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    fn index(x: &T) -> u64 {
+        let mut bytes = [0_u8; 8];
+        let le = x.to_bytes_le();
+        let len = le.len().min(8);
+        bytes[..len].copy_from_slice(&le[..len]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+    scalar_ring!(Z12RingElement, 12_u128, "z12");
+    scalar_ring!(Goldilocks, 18446744069414584321_u128, "goldilocks");
+
+    #[test]
+    fn matches_legendre() {
+        let table = ResidueTable::<F1009FieldElement>::new().unwrap();
+        assert_eq!(table.modulus(), 1009);
+        assert_eq!(table.count(), 504);
+        for i in 0..1009_u64 {
+            let x = F1009FieldElement::from(i);
+            assert_eq!(table.symbol(&x), x.legendre());
+            assert_eq!(table.is_qr(&x), x.legendre() == 1);
+            assert_eq!(table.is_qr_u64(i + 1009), table.is_qr(&x));
+        }
+    }
+
+    #[test]
+    fn composite_and_large_moduli() {
+        // the non-zero squares mod 12 are 1, 4 and 9
+        let table = ResidueTable::<Z12RingElement>::new().unwrap();
+        let squares = (0..12).filter(|i| table.is_qr_u64(*i)).collect::<Vec<_>>();
+        assert_eq!(squares, vec![1, 4, 9]);
+        assert_eq!(table.count(), 3);
+        assert_eq!(ResidueTable::<Goldilocks>::new(), None);
+    }
+
+    #[test]
+    fn extension_field() {
+        scalar_ring!(F3FieldElement, 3_u128, "f3");
+        crate::quadratic_extension!(F9FieldElement, F9Params, F3FieldElement, 2, "f3_2");
+        let table = ResidueTable::<F9FieldElement>::new().unwrap();
+        assert_eq!(table.count(), 4);
+        for i in 0..9_u64 {
+            let x = F9FieldElement::nth_element(&BigUint::from(i));
+            assert_eq!(table.symbol(&x), x.legendre());
+        }
+    }
+
+    #[cfg(feature = "binary_field")]
+    #[test]
+    fn binary_field() {
+        use crate::binary_field::Binary8FieldElement;
+        // every non-zero element of GF(2^8) is a square
+        let table = ResidueTable::<Binary8FieldElement>::new().unwrap();
+        assert_eq!(table.count(), 255);
+        for i in 0..256_u64 {
+            let x = Binary8FieldElement::from(i);
+            assert_eq!(table.symbol(&x), x.legendre());
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Square root algorithms for odd prime fields.
+//!
+//! `FieldElement::sqrt` picks the cheapest method for the modulus:
+//!   - `p = 3 mod 4` - `a^((p+1)/4)` with a precomputed addition chain
+//!   - `p = 5 mod 8` - Atkin's method, one exponentiation, e.g.
+//!     curve25519
+//!   - otherwise `tonelli_shanks`
+//!
+//! Everything is computed with field arithmetic. The exponents are
+//! cached addition chains (see `addition_chain`), the 2-adic root of
+//! unity used by Tonelli–Shanks is computed once per field.
+//!
+//! `FieldElement::sqrt_with` selects a generic algorithm explicitly,
+//! `SqrtAlgorithm::CrossCheck` runs both and panics if they disagree.
+//! The two algorithms share no code beyond field arithmetic, so
+//! differential testing catches edge case bugs in either.
+//!
+//! The functions here expect a non-zero element and return `None` if
+//! it is not a square, without a separate `legendre` call. Either
+//! root may be returned, callers pick the canonical (smaller) one.
+//!
+use std::any::Any;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use num_bigint::BigUint;
+
+use super::addition_chain;
+use super::FieldElement;
+
+/// The algorithm used by `FieldElement::sqrt_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqrtAlgorithm {
+    /// Tonelli–Shanks, uses a primitive `2^s`-th root of unity.
+    TonelliShanks,
+    /// Cipolla, exponentiation in a quadratic extension.
+    Cipolla,
+    /// Run both and panic if the roots differ.
+    CrossCheck,
+}
+
+/// The smaller of `root` and `-root` by integer lift.
+pub(crate) fn canonical_root<T: FieldElement>(root: T) -> T {
+    let other_root = -root.clone();
+    if root.to_biguint() > other_root.to_biguint() {
+        other_root
+    } else {
+        root
+    }
+}
+
+type RootCache = RwLock<Option<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>;
+
+static ROOTS: RootCache = RwLock::new(None);
+
+/// `T::two_adic_root_of_unity()`, cached because the default
+/// implementation searches for a non-residue.
+fn two_adic_root<T: FieldElement>() -> T {
+    let key = TypeId::of::<T>();
+    if let Some(root) = ROOTS.read().unwrap().as_ref().and_then(|m| m.get(&key)) {
+        return root.downcast_ref::<T>().expect("cached root type").clone();
+    }
+    let root = T::two_adic_root_of_unity();
+    ROOTS
+        .write()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(key, Arc::new(root.clone()));
+    root
+}
+
+/// The square root with the fastest method for the modulus, see the
+/// module documentation. `None` if `a` is not a square.
+pub fn sqrt<T: FieldElement>(a: &T) -> Option<T> {
+    if let Some(chain) = addition_chain::sqrt_chain::<T>() {
+        let root = chain.apply(a);
+        return (root.clone() * root.clone() == *a).then_some(root);
+    }
+    if addition_chain::atkin_chain::<T>().is_some() {
+        return atkin(a);
+    }
+    tonelli_shanks(a)
+}
+
+/// Atkin's square root for `p = 5 mod 8`. With `v = (2a)^((p-5)/8)`
+/// and `i = 2 a v^2`, `i^2 = -1` and `a v (i - 1)` is a root.
+/// Panics for other moduli.
+pub fn atkin<T: FieldElement>(a: &T) -> Option<T> {
+    let chain = addition_chain::atkin_chain::<T>().expect("atkin requires p = 5 mod 8");
+    let two_a = a.clone() + a.clone();
+    let v = chain.apply(&two_a);
+    let i = two_a * v.clone() * v.clone();
+    let root = a.clone() * v * (i - T::one());
+    (root.clone() * root.clone() == *a).then_some(root)
+}
+
+/// Tonelli–Shanks. With `p - 1 = q 2^s`, `x = a^((q+1)/2)` is a root
+/// of `a b` where `b = a^q` lies in the subgroup of order `2^s`. Each
+/// step multiplies `x` by a power of a `2^s`-th root of unity to lower
+/// the order of `b` until `b = 1`.
+pub fn tonelli_shanks<T: FieldElement>(a: &T) -> Option<T> {
+    // a^((q-1)/2), then x = a^((q+1)/2) and b = a^q
+    let t = addition_chain::tonelli_shanks_chain::<T>().apply(a);
+    let mut x = a.clone() * t.clone();
+    let mut b = x.clone() * t;
+    let mut c = two_adic_root::<T>();
+    let mut m = T::two_adicity();
+    while b != T::one() {
+        // the least i with b^(2^i) = 1
+        let mut i = 0;
+        let mut b2 = b.clone();
+        while b2 != T::one() {
+            b2 = b2.clone() * b2;
+            i += 1;
+            if i == m {
+                // b has order 2^s, a is a non-residue
+                return None;
+            }
+        }
+        let mut g = c;
+        for _ in i + 1..m {
+            g = g.clone() * g;
+        }
+        x *= g.clone();
+        c = g.clone() * g;
+        b *= c.clone();
+        m = i;
+    }
+    Some(x)
+}
+
+/// A square root with Cipolla's algorithm. Finds `t` with `t^2 - a`
+/// a non-residue `w` and computes `(t + sqrt(w))^((p+1)/2)` in
+/// `F_p[sqrt(w)]`, the result lies in `F_p` when `a` is a square.
+pub fn cipolla<T: FieldElement>(a: &T) -> Option<T> {
+    let mut t = T::zero();
+    let w = loop {
+        let w = t.clone() * t.clone() - a.clone();
+        if w.legendre() == -1 {
+            break w;
+        }
+        t += T::one();
+    };
+    // (x0 + x1 s)(y0 + y1 s) with s^2 = w
+    let mul = |x: &(T, T), y: &(T, T)| {
+        (
+            x.0.clone() * y.0.clone() + x.1.clone() * y.1.clone() * w.clone(),
+            x.0.clone() * y.1.clone() + x.1.clone() * y.0.clone(),
+        )
+    };
+    let exp: BigUint = (T::prime() + 1_u32) >> 1;
+    let base = (t, T::one());
+    let mut out = (T::one(), T::zero());
+    for i in (0..exp.bits()).rev() {
+        out = mul(&out, &out);
+        if exp.bit(i) {
+            out = mul(&out, &base);
+        }
+    }
+    (out.1 == T::zero() && out.0.clone() * out.0.clone() == *a).then_some(out.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+    // 2^4 * 3 * 5 + 1, two-adicity 4
+    scalar_ring!(F241FieldElement, 241_u128, "f241");
+    scalar_ring!(F1019FieldElement, 1019_u128, "f1019");
+    // 5 mod 8
+    scalar_ring!(F1013FieldElement, 1013_u128, "f1013");
+    // 2^16 + 1, two-adicity 16
+    scalar_ring!(F65537FieldElement, 65537_u128, "f65537");
+
+    fn test_algorithms<T: FieldElement>() {
+        let p = T::prime().to_u64_digits()[0];
+        for i in 1..p.min(2_000) {
+            let x = T::from(i);
+            let square = x.clone() * x.clone();
+            for algorithm in [
+                SqrtAlgorithm::TonelliShanks,
+                SqrtAlgorithm::Cipolla,
+                SqrtAlgorithm::CrossCheck,
+            ] {
+                let root = square.sqrt_with(algorithm);
+                assert!(root == x || root == -x.clone());
+                assert!(root.to_biguint() <= (-root.clone()).to_biguint());
+                assert_eq!(root, square.sqrt());
+            }
+        }
+        assert_eq!(T::zero().sqrt_with(SqrtAlgorithm::Cipolla), T::zero());
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        test_algorithms::<F1009FieldElement>();
+        test_algorithms::<F241FieldElement>();
+        test_algorithms::<F1019FieldElement>();
+        test_algorithms::<F1013FieldElement>();
+        test_algorithms::<F65537FieldElement>();
+    }
+
+    fn test_non_residues<T: FieldElement>() {
+        let p = T::prime().to_u64_digits()[0];
+        for i in 1..p.min(2_000) {
+            let x = T::from(i);
+            let is_square = x.legendre() == 1;
+            assert_eq!(sqrt(&x).is_some(), is_square);
+            assert_eq!(tonelli_shanks(&x).is_some(), is_square);
+            assert_eq!(cipolla(&x).is_some(), is_square);
+            if addition_chain::atkin_chain::<T>().is_some() {
+                assert_eq!(atkin(&x).is_some(), is_square);
+            }
+        }
+    }
+
+    #[test]
+    fn non_residues() {
+        test_non_residues::<F1009FieldElement>();
+        test_non_residues::<F1019FieldElement>();
+        test_non_residues::<F1013FieldElement>();
+        test_non_residues::<F65537FieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn atkin_curve25519() {
+        type T = crate::Curve25519FieldElement;
+        assert!(addition_chain::atkin_chain::<T>().is_some());
+        let x = T::from(987654321);
+        let root = atkin(&(x * x)).unwrap();
+        assert!(root == x || root == -x);
+        // 2 generates the multiplicative group
+        assert_eq!(atkin(&T::from(2)), None);
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn algorithms_agree_bn128() {
+        let x = crate::Bn128FieldElement::from(987654321);
+        let root = (x * x).sqrt_with(SqrtAlgorithm::CrossCheck);
+        assert!(root == x || root == -x);
+    }
+
+    #[test]
+    #[should_panic(expected = "root does not exist")]
+    fn non_residue() {
+        // 11 is a non-residue mod 1009
+        assert_eq!(F1009FieldElement::from(11).legendre(), -1);
+        F1009FieldElement::from(11).sqrt_with(SqrtAlgorithm::Cipolla);
+    }
+}
@@ -0,0 +1,262 @@
+//! Square matrices with a size known at compile time, as a ring.
+//!
+//! Unlike `Matrix`, where `*` is element wise and scalars have their
+//! own shape, `SquareMatrix<T, N>` multiplies with the matrix product
+//! and `From<u64>` gives scalar multiples of the identity. This makes
+//! it a (non-commutative) `RingElement`, so it can be used as the
+//! coefficient type of a `Polynomial`:
+//!
+//! ```
+//! use scalarff::FieldElement;
+//! use scalarff::polynomial::Polynomial;
+//! use scalarff::square_matrix::SquareMatrix;
+//! scalarff::scalar_ring!(F13FieldElement, 13_u128, "f13");
+//!
+//! type M = SquareMatrix<F13FieldElement, 2>;
+//! // A + B x with matrix coefficients
+//! let a = M::from_fn(|i, j| F13FieldElement::from((i + j) as u64));
+//! let p = Polynomial::new(vec![a.clone(), M::from(1)]);
+//! assert_eq!(p.eval(&M::from(0)), a);
+//! ```
+//!
+//! Entries can be any `RingElement`, e.g. matrices over a polynomial
+//! ring for module lattices.
+//!
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+use super::matrix::Matrix;
+use super::RingElement;
+
+/// An `N x N` matrix stored row major.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SquareMatrix<T: RingElement, const N: usize>(pub [[T; N]; N]);
+
+impl<T: RingElement, const N: usize> SquareMatrix<T, N> {
+    pub fn new(rows: [[T; N]; N]) -> Self {
+        SquareMatrix(rows)
+    }
+
+    /// Build a matrix from the entry at each `(row, column)`.
+    pub fn from_fn(f: impl Fn(usize, usize) -> T) -> Self {
+        SquareMatrix(std::array::from_fn(|i| std::array::from_fn(|j| f(i, j))))
+    }
+
+    pub fn zero() -> Self {
+        Self::from(0)
+    }
+
+    pub fn identity() -> Self {
+        Self::from(1)
+    }
+
+    /// `v` times the identity.
+    pub fn scalar(v: T) -> Self {
+        Self::from_fn(|i, j| if i == j { v.clone() } else { T::from(0) })
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(|i, j| self.0[j][i].clone())
+    }
+
+    /// Convert a 2 dimensional `N x N` matrix.
+    pub fn from_matrix(m: &Matrix<T>) -> Option<Self> {
+        if m.dimensions != [N, N] {
+            return None;
+        }
+        Some(Self::from_fn(|i, j| m.values[i * N + j].clone()))
+    }
+}
+
+impl<T: RingElement, const N: usize> From<SquareMatrix<T, N>> for Matrix<T> {
+    fn from(m: SquareMatrix<T, N>) -> Self {
+        Matrix {
+            dimensions: vec![N, N],
+            values: m.0.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl<T: RingElement, const N: usize> Index<(usize, usize)> for SquareMatrix<T, N> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.0[i][j]
+    }
+}
+
+impl<T: RingElement, const N: usize> IndexMut<(usize, usize)> for SquareMatrix<T, N> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.0[i][j]
+    }
+}
+
+impl<T: RingElement, const N: usize> Add for SquareMatrix<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from_fn(|i, j| self.0[i][j].clone() + other.0[i][j].clone())
+    }
+}
+
+impl<T: RingElement, const N: usize> AddAssign for SquareMatrix<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+impl<T: RingElement, const N: usize> Sub for SquareMatrix<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::from_fn(|i, j| self.0[i][j].clone() - other.0[i][j].clone())
+    }
+}
+
+impl<T: RingElement, const N: usize> SubAssign for SquareMatrix<T, N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.clone() - other;
+    }
+}
+
+/// The matrix product.
+impl<T: RingElement, const N: usize> Mul for SquareMatrix<T, N> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_fn(|i, j| {
+            (0..N).fold(T::from(0), |acc, k| {
+                acc + self.0[i][k].clone() * other.0[k][j].clone()
+            })
+        })
+    }
+}
+
+impl<T: RingElement, const N: usize> MulAssign for SquareMatrix<T, N> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.clone() * other;
+    }
+}
+
+impl<T: RingElement, const N: usize> Neg for SquareMatrix<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        SquareMatrix(self.0.map(|row| row.map(|v| -v)))
+    }
+}
+
+impl<T: RingElement, const N: usize> Default for SquareMatrix<T, N> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+/// `v` times the identity.
+impl<T: RingElement, const N: usize> From<u64> for SquareMatrix<T, N> {
+    fn from(v: u64) -> Self {
+        Self::scalar(T::from(v))
+    }
+}
+
+impl<T: RingElement, const N: usize> RingElement for SquareMatrix<T, N> {}
+
+impl<T: RingElement, const N: usize> Display for SquareMatrix<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let rows = self
+            .0
+            .iter()
+            .map(|row| {
+                let entries = row.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                format!("[{}]", entries.join(", "))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "[{}]", rows.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polynomial::Polynomial;
+    use crate::FieldElement;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    type M = SquareMatrix<F13FieldElement, 2>;
+
+    fn matrix(rows: [[u64; 2]; 2]) -> M {
+        SquareMatrix(rows.map(|row| row.map(F13FieldElement::from)))
+    }
+
+    #[test]
+    fn ring_arithmetic() {
+        let a = matrix([[1, 2], [3, 4]]);
+        let b = matrix([[0, 1], [1, 0]]);
+        assert_eq!(a.clone() * b.clone(), matrix([[2, 1], [4, 3]]));
+        assert_eq!(b.clone() * a.clone(), matrix([[3, 4], [1, 2]]));
+        assert_eq!(a.clone() * M::from(1), a);
+        assert_eq!(a.clone() * M::from(2), a.clone() + a.clone());
+        assert_eq!(a.clone() - a.clone(), M::zero());
+        assert_eq!(a.transpose()[(0, 1)], F13FieldElement::from(3));
+        let m = Matrix::from(a.clone());
+        assert_eq!(
+            m.matmul(&Matrix::from(b.clone())).unwrap(),
+            Matrix::from(a.clone() * b)
+        );
+        assert_eq!(M::from_matrix(&m), Some(a));
+        assert_eq!(M::from_matrix(&Matrix::identity(3)), None);
+    }
+
+    #[test]
+    fn matrix_coefficients() {
+        let a = matrix([[1, 2], [3, 4]]);
+        let b = matrix([[0, 1], [1, 0]]);
+        // p = a + b x, q = b + x
+        let p = Polynomial::new(vec![a.clone(), b.clone()]);
+        let q = Polynomial::new(vec![b.clone(), M::from(1)]);
+        let pq = p.clone() * q.clone();
+        assert_eq!(pq.degree(), 2);
+        // coefficients multiply in order, a * b != b * a
+        assert_eq!(pq.coefficients()[0], a.clone() * b.clone());
+        // evaluation at central points is a ring homomorphism
+        for c in 0..13 {
+            let x = M::from(c);
+            assert_eq!(pq.eval(&x), p.eval(&x) * q.eval(&x));
+        }
+        // b^2 = 1, so b + x and -b + x multiply to x^2 - 1
+        let r = Polynomial::new(vec![-b.clone(), M::from(1)]);
+        let expected = Polynomial::new(vec![-M::from(1), M::zero(), M::from(1)]);
+        assert_eq!(q * r, expected);
+    }
+
+    #[test]
+    fn karatsuba_keeps_order() {
+        use crate::tuning::Tuning;
+        let coefficients = |seed: u64, n: u64| {
+            (0..n)
+                .map(|i| matrix([[i + seed, 1], [i * i, seed]]))
+                .collect::<Vec<_>>()
+        };
+        let a = Polynomial::new(coefficients(1, 9));
+        let b = Polynomial::new(coefficients(5, 7));
+        let schoolbook = Tuning {
+            karatsuba_threshold: usize::MAX,
+            ..Tuning::DEFAULT
+        };
+        let karatsuba = Tuning {
+            karatsuba_threshold: 1,
+            ..Tuning::DEFAULT
+        };
+        assert_eq!(a.mul_with(&b, &karatsuba), a.mul_with(&b, &schoolbook));
+        assert_ne!(a.mul_with(&b, &schoolbook), b.mul_with(&a, &schoolbook));
+    }
+}
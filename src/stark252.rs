@@ -0,0 +1,42 @@
+//! The Starknet/Cairo prime field, `p = 2^251 + 17 * 2^192 + 1`.
+//! Requires the `stark252` feature.
+//!
+//! A native Montgomery implementation, see `montgomery`.
+//! `p - 1 = 2^192 * 5 * 7 * 98714381 * 166848103`, `3` generates the
+//! multiplicative group.
+//!
+crate::mont_field!(
+    Stark252FieldElement,
+    Stark252Params,
+    4,
+    [1, 0, 0, 0x0800000000000011],
+    "stark252"
+);
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::FieldElement;
+
+    #[test]
+    fn modulus() {
+        let p = (BigUint::from(1_u32) << 251) + (BigUint::from(17_u32) << 192) + 1_u32;
+        assert_eq!(Stark252FieldElement::prime(), p);
+        assert!(crate::model_check::check::<Stark252FieldElement>(252, 2_000).is_ok());
+        assert_eq!(Stark252FieldElement::two_adicity(), 192);
+        let g = Stark252FieldElement::two_adic_generator(192);
+        assert_eq!(
+            g.pow(&(BigUint::from(1_u32) << 191)),
+            -Stark252FieldElement::one()
+        );
+        let x = Stark252FieldElement::from(31337);
+        let root = (x * x).sqrt();
+        assert!(root == x || root == -x);
+        assert_eq!(
+            Stark252FieldElement::deserialize(&(p - 1_u32).to_string()),
+            -Stark252FieldElement::one()
+        );
+    }
+}
@@ -0,0 +1,490 @@
+//! The sumcheck protocol for products of multilinear polynomials.
+//!
+//! The prover claims `sum_b f_1(b) f_2(b) ... f_d(b) = c` over
+//! `b` in `{0, 1}^n`. In round `i` it sends the univariate polynomial
+//!
+//!   `g_i(X) = sum_b prod_k f_k(r_0, ..., r_(i-1), X, b)`
+//!
+//! as its evaluations at the first `d + 1` elements of the canonical
+//! bijection, `nth_element(0), ..., nth_element(d)`. In a prime field
+//! of characteristic greater than `d` these are `0, 1, ..., d`, in
+//! small characteristic (e.g. `binary_field`) they are still `d + 1`
+//! distinct points starting with `0` and `1`. The field needs more
+//! than `d` elements. The verifier checks
+//! `g_i(0) + g_i(1)` against the current claim, samples `r_i` and
+//! continues with the claim `g_i(r_i)`. After `n` rounds the claim is
+//! `prod_k f_k(r)`, which the caller checks against the polynomials
+//! (e.g. with commitment openings).
+//!
+//! `SumcheckProver` and `SumcheckVerifier` run the protocol one round
+//! at a time. `SumcheckProver::prove` and `verify` make it non
+//! interactive with a `Transcript` (Fiat–Shamir). With the `hash`
+//! feature `HashTranscript` is a SHA-256 based transcript.
+//!
+//! The prover binds each variable in place, so round `i` costs
+//! `O(d^2 2^(n-i))` field operations and memory shrinks every round.
+//!
+use num_bigint::BigUint;
+
+use super::error::SumcheckError;
+use super::multilinear::MultilinearPoly;
+use super::polynomial::Polynomial;
+use super::FieldElement;
+
+/// The source of verifier challenges in a non interactive proof.
+/// Both sides must absorb the same values in the same order.
+pub trait Transcript<T: FieldElement> {
+    fn absorb(&mut self, values: &[T]);
+
+    /// A challenge depending on everything absorbed so far.
+    fn challenge(&mut self) -> T;
+}
+
+/// The DST used by `HashTranscript` challenges.
+#[cfg(feature = "hash")]
+pub const TRANSCRIPT_DST: &[u8] = b"SCALARFF-V01-TRANSCRIPT";
+
+/// A transcript hashing absorbed elements into a SHA-256 state.
+/// Challenges are derived with `FieldElement::hash_to_field` and are
+/// absorbed themselves. Requires the `hash` feature.
+#[cfg(feature = "hash")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashTranscript {
+    state: [u8; 32],
+}
+
+#[cfg(feature = "hash")]
+impl HashTranscript {
+    /// A transcript starting from a protocol label.
+    pub fn new(label: &[u8]) -> Self {
+        use sha2::Digest;
+        HashTranscript {
+            state: sha2::Sha256::digest(label).into(),
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+impl<T: FieldElement> Transcript<T> for HashTranscript {
+    fn absorb(&mut self, values: &[T]) {
+        use sha2::Digest;
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(self.state);
+        for v in values {
+            hasher.update(v.to_bytes_le_fixed());
+        }
+        self.state = hasher.finalize().into();
+    }
+
+    fn challenge(&mut self) -> T {
+        let c = T::hash_to_field(&self.state, TRANSCRIPT_DST);
+        self.absorb(std::slice::from_ref(&c));
+        c
+    }
+}
+
+/// A non interactive sumcheck proof, one message per variable and
+/// the evaluations of each factor at the final point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumcheckProof<T: FieldElement> {
+    pub rounds: Vec<Vec<T>>,
+    pub evaluations: Vec<T>,
+}
+
+/// What a successful verification reduces the claim to: each factor
+/// evaluates to `evaluations[k]` at `point`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumcheckClaim<T: FieldElement> {
+    pub point: Vec<T>,
+    pub evaluations: Vec<T>,
+}
+
+impl<T: FieldElement> SumcheckClaim<T> {
+    /// Whether the factors evaluate as claimed, for verifiers with
+    /// direct access to the polynomials.
+    pub fn check(&self, factors: &[MultilinearPoly<T>]) -> bool {
+        factors.len() == self.evaluations.len()
+            && factors
+                .iter()
+                .zip(&self.evaluations)
+                .all(|(f, v)| f.evaluate(&self.point) == *v)
+    }
+}
+
+/// The prover state, the factors with the variables bound so far.
+#[derive(Debug, Clone)]
+pub struct SumcheckProver<T: FieldElement> {
+    factors: Vec<MultilinearPoly<T>>,
+}
+
+impl<T: FieldElement> SumcheckProver<T> {
+    /// Prove the sum of the product of the factors. Panics if there
+    /// are no factors, they have different numbers of variables or
+    /// the field has no more elements than factors.
+    pub fn new(factors: Vec<MultilinearPoly<T>>) -> Self {
+        if factors.is_empty() {
+            panic!("sumcheck needs at least one factor");
+        }
+        assert_field_size::<T>(factors.len());
+        for f in &factors[1..] {
+            factors[0].assert_eq_vars(f);
+        }
+        SumcheckProver { factors }
+    }
+
+    /// The degree of each round polynomial, the number of factors.
+    pub fn degree(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// The number of variables not bound yet.
+    pub fn num_vars(&self) -> usize {
+        self.factors[0].num_vars()
+    }
+
+    /// `sum_b prod_k f_k(b)` over the unbound variables.
+    pub fn sum(&self) -> T {
+        (0..self.factors[0].evaluations().len()).fold(T::zero(), |acc, b| {
+            acc + self
+                .factors
+                .iter()
+                .fold(T::one(), |p, f| p * f.evaluations()[b].clone())
+        })
+    }
+
+    /// The round polynomial for the next unbound variable as its
+    /// evaluations at `nth_element(0), ..., nth_element(degree)`.
+    /// Panics if every variable is bound.
+    pub fn round_message(&self) -> Vec<T> {
+        if self.num_vars() == 0 {
+            panic!("every sumcheck variable is bound");
+        }
+        let half = self.factors[0].evaluations().len() / 2;
+        let points = round_points::<T>(self.degree());
+        let mut out = vec![T::zero(); self.degree() + 1];
+        let mut products = vec![T::one(); self.degree() + 1];
+        for j in 0..half {
+            products.fill(T::one());
+            for f in &self.factors {
+                let lo = f.evaluations()[2 * j].clone();
+                let step = f.evaluations()[2 * j + 1].clone() - lo.clone();
+                // f(X) = lo + X step at each round point
+                for (p, x) in products.iter_mut().zip(&points) {
+                    *p *= lo.clone() + x.clone() * step.clone();
+                }
+            }
+            for (o, p) in out.iter_mut().zip(&products) {
+                *o += p.clone();
+            }
+        }
+        out
+    }
+
+    /// Bind the next variable to the verifier challenge `r`.
+    pub fn bind(&mut self, r: &T) {
+        for f in self.factors.iter_mut() {
+            f.fix_first_variable(r);
+        }
+    }
+
+    /// The evaluation of each factor once every variable is bound.
+    pub fn evaluations(&self) -> Vec<T> {
+        self.factors
+            .iter()
+            .map(|f| f.evaluations()[0].clone())
+            .collect()
+    }
+
+    /// Run every round, absorbing each message into the transcript
+    /// before drawing its challenge. The claimed sum is not absorbed,
+    /// callers should absorb it (and any commitments) first.
+    pub fn prove(mut self, transcript: &mut impl Transcript<T>) -> SumcheckProof<T> {
+        let mut rounds = Vec::with_capacity(self.num_vars());
+        while self.num_vars() > 0 {
+            let message = self.round_message();
+            transcript.absorb(&message);
+            self.bind(&transcript.challenge());
+            rounds.push(message);
+        }
+        SumcheckProof {
+            rounds,
+            evaluations: self.evaluations(),
+        }
+    }
+}
+
+/// The verifier state, the current claim and the challenges so far.
+#[derive(Debug, Clone)]
+pub struct SumcheckVerifier<T: FieldElement> {
+    claim: T,
+    degree: usize,
+    num_vars: usize,
+    point: Vec<T>,
+}
+
+impl<T: FieldElement> SumcheckVerifier<T> {
+    /// Verify that a product of `degree` multilinear polynomials in
+    /// `num_vars` variables sums to `claim`. Panics if `degree` is
+    /// zero or the field has no more than `degree` elements.
+    pub fn new(claim: T, degree: usize, num_vars: usize) -> Self {
+        if degree == 0 {
+            panic!("sumcheck needs at least one factor");
+        }
+        assert_field_size::<T>(degree);
+        SumcheckVerifier {
+            claim,
+            degree,
+            num_vars,
+            point: Vec::with_capacity(num_vars),
+        }
+    }
+
+    /// The current claim, `g(r)` for the last round polynomial `g`.
+    pub fn claim(&self) -> &T {
+        &self.claim
+    }
+
+    /// Check a round message and move to the next round with the
+    /// challenge `r`.
+    pub fn receive(&mut self, message: &[T], r: T) -> Result<(), SumcheckError> {
+        let round = self.point.len();
+        if round == self.num_vars {
+            return Err(SumcheckError::RoundCount {
+                rounds: round + 1,
+                expected: self.num_vars,
+            });
+        }
+        if message.len() != self.degree + 1 {
+            return Err(SumcheckError::MessageLength {
+                round,
+                len: message.len(),
+                expected: self.degree + 1,
+            });
+        }
+        if message[0].clone() + message[1].clone() != self.claim {
+            return Err(SumcheckError::SumMismatch { round });
+        }
+        self.claim = if T::characteristic() > BigUint::from(self.degree) {
+            Polynomial::eval_consecutive(message, &r)
+        } else {
+            Polynomial::interpolate(&round_points::<T>(self.degree), message).eval(&r)
+        };
+        self.point.push(r);
+        Ok(())
+    }
+
+    /// Check the claimed factor evaluations at the final point.
+    pub fn finish(self, evaluations: Vec<T>) -> Result<SumcheckClaim<T>, SumcheckError> {
+        if self.point.len() != self.num_vars {
+            return Err(SumcheckError::RoundCount {
+                rounds: self.point.len(),
+                expected: self.num_vars,
+            });
+        }
+        if evaluations.len() != self.degree
+            || evaluations.iter().fold(T::one(), |p, v| p * v.clone()) != self.claim
+        {
+            return Err(SumcheckError::FinalMismatch);
+        }
+        Ok(SumcheckClaim {
+            point: self.point,
+            evaluations,
+        })
+    }
+}
+
+/// Verify a proof from `SumcheckProver::prove` with the same
+/// transcript state. On success the caller still has to check the
+/// returned evaluations against the factors.
+pub fn verify<T: FieldElement>(
+    proof: &SumcheckProof<T>,
+    claim: T,
+    degree: usize,
+    num_vars: usize,
+    transcript: &mut impl Transcript<T>,
+) -> Result<SumcheckClaim<T>, SumcheckError> {
+    if proof.rounds.len() != num_vars {
+        return Err(SumcheckError::RoundCount {
+            rounds: proof.rounds.len(),
+            expected: num_vars,
+        });
+    }
+    let mut verifier = SumcheckVerifier::new(claim, degree, num_vars);
+    for message in &proof.rounds {
+        transcript.absorb(message);
+        verifier.receive(message, transcript.challenge())?;
+    }
+    verifier.finish(proof.evaluations.clone())
+}
+
+/// The points `nth_element(0), ..., nth_element(degree)` the round
+/// polynomials are evaluated at.
+fn round_points<T: FieldElement>(degree: usize) -> Vec<T> {
+    (0..=degree)
+        .map(|k| T::nth_element(&BigUint::from(k)))
+        .collect()
+}
+
+/// Panics unless the field has the `degree + 1` distinct points a
+/// round polynomial of `degree` is sent as.
+fn assert_field_size<T: FieldElement>(degree: usize) {
+    if T::prime() <= BigUint::from(degree) {
+        panic!(
+            "sumcheck of degree {degree} needs more than {degree} elements in field {}",
+            T::name_str()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hypercube::eq_table;
+
+    scalar_ring!(F1009FieldElement, 1009_u128, "f1009");
+
+    type F = F1009FieldElement;
+
+    /// Challenges from a linear recurrence over the absorbed values,
+    /// deterministic and dependency free.
+    #[derive(Default)]
+    struct TestTranscript {
+        state: F,
+    }
+
+    impl Transcript<F> for TestTranscript {
+        fn absorb(&mut self, values: &[F]) {
+            for v in values {
+                self.state = self.state * F::from(31) + *v;
+            }
+        }
+
+        fn challenge(&mut self) -> F {
+            self.state = self.state * F::from(17) + F::from(5);
+            self.state
+        }
+    }
+
+    fn poly(seed: u64, num_vars: usize) -> MultilinearPoly<F> {
+        MultilinearPoly::from_fn(num_vars, |b| F::from(seed * 7 + (b as u64) * seed + 3))
+    }
+
+    #[test]
+    fn interactive() {
+        let factors = vec![poly(1, 4), poly(2, 4), poly(5, 4)];
+        let mut prover = SumcheckProver::new(factors.clone());
+        let mut verifier = SumcheckVerifier::new(prover.sum(), 3, 4);
+        for r in [10, 200, 3, 999] {
+            let message = prover.round_message();
+            assert_eq!(message.len(), 4);
+            verifier.receive(&message, F::from(r)).unwrap();
+            prover.bind(&F::from(r));
+        }
+        let claim = verifier.finish(prover.evaluations()).unwrap();
+        assert_eq!(
+            claim.point,
+            vec![F::from(10), F::from(200), F::from(3), F::from(999)]
+        );
+        assert!(claim.check(&factors));
+    }
+
+    #[test]
+    fn non_interactive() {
+        // the multilinear extension of f at r: sum_b f(b) eq(b, r)
+        let f = poly(3, 5);
+        let r = (1..=5).map(F::from).collect::<Vec<_>>();
+        let eq = MultilinearPoly::new(eq_table(&r)).unwrap();
+        let claim = f.evaluate(&r);
+        let proof =
+            SumcheckProver::new(vec![f.clone(), eq.clone()]).prove(&mut TestTranscript::default());
+        let result = verify(&proof, claim, 2, 5, &mut TestTranscript::default()).unwrap();
+        assert!(result.check(&[f.clone(), eq.clone()]));
+        // wrong sum
+        assert_eq!(
+            verify(
+                &proof,
+                claim + F::one(),
+                2,
+                5,
+                &mut TestTranscript::default()
+            ),
+            Err(SumcheckError::SumMismatch { round: 0 })
+        );
+        // tampered message
+        let mut bad = proof.clone();
+        bad.rounds[2][0] += F::one();
+        bad.rounds[2][1] -= F::one();
+        assert!(verify(&bad, claim, 2, 5, &mut TestTranscript::default()).is_err());
+        // tampered evaluations
+        let mut bad = proof.clone();
+        bad.evaluations[0] += F::one();
+        assert_eq!(
+            verify(&bad, claim, 2, 5, &mut TestTranscript::default()),
+            Err(SumcheckError::FinalMismatch)
+        );
+        assert_eq!(
+            verify(&proof, claim, 2, 4, &mut TestTranscript::default()),
+            Err(SumcheckError::RoundCount {
+                rounds: 5,
+                expected: 4
+            })
+        );
+        assert!(matches!(
+            verify(&proof, claim, 3, 5, &mut TestTranscript::default()),
+            Err(SumcheckError::MessageLength { round: 0, .. })
+        ));
+    }
+
+    #[cfg(feature = "binary_field")]
+    #[test]
+    fn binary_field() {
+        use crate::binary_field::Binary8FieldElement as B;
+        // characteristic 2, the round points are 0, 1, x, x + 1, ...
+        for num_factors in 1..=4 {
+            let factors = (0..num_factors)
+                .map(|k| MultilinearPoly::from_fn(3, |b| B::from(k * 29 + b as u64 * 7 + 3)))
+                .collect::<Vec<_>>();
+            let mut prover = SumcheckProver::new(factors.clone());
+            let mut verifier = SumcheckVerifier::new(prover.sum(), num_factors as usize, 3);
+            for r in [0x53, 0xca, 0x11] {
+                let message = prover.round_message();
+                verifier.receive(&message, B::from(r)).unwrap();
+                prover.bind(&B::from(r));
+            }
+            let claim = verifier.finish(prover.evaluations()).unwrap();
+            assert!(claim.check(&factors));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one factor")]
+    fn zero_degree() {
+        SumcheckVerifier::new(F::zero(), 0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "needs more than")]
+    fn small_field() {
+        scalar_ring!(F3FieldElement, 3_u128, "f3");
+        SumcheckVerifier::new(F3FieldElement::zero(), 3, 3);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn hash_transcript() {
+        let factors = vec![poly(2, 6), poly(9, 6)];
+        let prover = SumcheckProver::new(factors.clone());
+        let claim = prover.sum();
+        let mut transcript = HashTranscript::new(b"sumcheck test");
+        Transcript::<F>::absorb(&mut transcript, &[claim]);
+        let proof = prover.prove(&mut transcript);
+        let mut transcript = HashTranscript::new(b"sumcheck test");
+        Transcript::<F>::absorb(&mut transcript, &[claim]);
+        let result = verify(&proof, claim, 2, 6, &mut transcript).unwrap();
+        assert!(result.check(&factors));
+        // a different label gives different challenges
+        let mut other = HashTranscript::new(b"other");
+        Transcript::<F>::absorb(&mut other, &[claim]);
+        assert!(verify(&proof, claim, 2, 6, &mut other).is_err());
+    }
+}
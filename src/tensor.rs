@@ -0,0 +1,478 @@
+//! Multi-dimensional arrays of `RingElement`.
+//!
+//! A `Tensor` stores its values in a `Vec` together with a shape and
+//! the stride of each axis, so `permute_axes` and `transpose` only
+//! reorder metadata. Operations that need a row major layout (e.g.
+//! `reshape`) copy the values first, see `is_contiguous`.
+//!
+//! Elementwise arithmetic uses the standard operators and panics on
+//! a shape mismatch, like `Matrix`. `contract` sums the product of two
+//! tensors over a shared axis, e.g. a matrix product or the partial
+//! evaluation of a multilinear polynomial stored as a `2 x 2 x ... x 2`
+//! tensor of evaluations.
+//!
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use super::matrix::Matrix;
+use super::RingElement;
+use super::ShapeError;
+
+#[derive(Debug, Clone)]
+pub struct Tensor<T: RingElement> {
+    shape: Vec<usize>,
+    // distance in `values` between consecutive indices of each axis
+    strides: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// Strides of a row major layout, the last axis varies fastest.
+fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+impl<T: RingElement> Tensor<T> {
+    /// A tensor with values in row major order. Returns an error if
+    /// the number of values isn't the product of the shape. The
+    /// empty shape holds a single scalar.
+    pub fn new(shape: Vec<usize>, values: Vec<T>) -> Result<Self, ShapeError> {
+        if values.len() != shape.iter().product::<usize>() {
+            return Err(ShapeError::LengthMismatch {
+                shape,
+                len: values.len(),
+            });
+        }
+        Ok(Tensor {
+            strides: row_major_strides(&shape),
+            shape,
+            values,
+        })
+    }
+
+    pub fn zeros(shape: Vec<usize>) -> Self {
+        Self::from_fn(shape, |_| T::from(0))
+    }
+
+    /// Build a tensor from the value at each index.
+    pub fn from_fn(shape: Vec<usize>, f: impl Fn(&[usize]) -> T) -> Self {
+        let values = Indices::new(&shape).map(|i| f(&i)).collect();
+        Tensor {
+            strides: row_major_strides(&shape),
+            shape,
+            values,
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// The number of axes.
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Whether the values are stored in row major order.
+    pub fn is_contiguous(&self) -> bool {
+        self.strides == row_major_strides(&self.shape)
+    }
+
+    /// The value at `index`, `None` if the index has the wrong
+    /// number of axes or is out of bounds.
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        self.offset(index).map(|i| &self.values[i])
+    }
+
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        self.offset(index).map(|i| &mut self.values[i])
+    }
+
+    fn offset(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.rank() || index.iter().zip(&self.shape).any(|(i, n)| i >= n) {
+            return None;
+        }
+        Some(index.iter().zip(&self.strides).map(|(i, s)| i * s).sum())
+    }
+
+    /// Iterate over the values in row major order of the indices.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        Indices::new(&self.shape).map(|i| &self.values[self.offset(&i).unwrap()])
+    }
+
+    /// The values in row major order.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    /// Copy the values into row major order.
+    pub fn into_contiguous(self) -> Self {
+        if self.is_contiguous() {
+            return self;
+        }
+        Tensor {
+            values: self.to_vec(),
+            strides: row_major_strides(&self.shape),
+            shape: self.shape,
+        }
+    }
+
+    /// The same values in row major order with a new shape. Returns an
+    /// error if the number of values differs.
+    pub fn reshape(self, shape: Vec<usize>) -> Result<Self, ShapeError> {
+        Self::new(shape, self.into_contiguous().values)
+    }
+
+    /// Reorder the axes, axis `i` of the output is axis `axes[i]` of
+    /// the input. Only the strides change. Returns an error if `axes`
+    /// is not a permutation of `0..rank`.
+    pub fn permute_axes(self, axes: &[usize]) -> Result<Self, ShapeError> {
+        let mut seen = vec![false; self.rank()];
+        for a in axes {
+            if *a >= self.rank() || seen[*a] {
+                return Err(ShapeError::InvalidPermutation {
+                    axes: axes.to_vec(),
+                });
+            }
+            seen[*a] = true;
+        }
+        if axes.len() != self.rank() {
+            return Err(ShapeError::InvalidPermutation {
+                axes: axes.to_vec(),
+            });
+        }
+        Ok(Tensor {
+            shape: axes.iter().map(|a| self.shape[*a]).collect(),
+            strides: axes.iter().map(|a| self.strides[*a]).collect(),
+            values: self.values,
+        })
+    }
+
+    /// Reverse the order of the axes.
+    pub fn transpose(self) -> Self {
+        let axes = (0..self.rank()).rev().collect::<Vec<_>>();
+        self.permute_axes(&axes).unwrap()
+    }
+
+    /// Move `axis` to the end and return the values in row major order.
+    fn axis_last(&self, axis: usize) -> Result<(Vec<usize>, Vec<T>), ShapeError> {
+        if axis >= self.rank() {
+            return Err(ShapeError::AxisOutOfRange {
+                axis,
+                rank: self.rank(),
+            });
+        }
+        let axes = (0..self.rank())
+            .filter(|a| *a != axis)
+            .chain([axis])
+            .collect::<Vec<_>>();
+        let moved = self.clone().permute_axes(&axes)?.into_contiguous();
+        let mut outer = moved.shape;
+        outer.pop();
+        Ok((outer, moved.values))
+    }
+
+    /// Sum over `axis`, the output has one axis fewer.
+    pub fn sum_axis(&self, axis: usize) -> Result<Self, ShapeError> {
+        let (outer, values) = self.axis_last(axis)?;
+        let n = self.shape[axis];
+        let sums = if n == 0 {
+            vec![T::from(0); outer.iter().product()]
+        } else {
+            values
+                .chunks_exact(n)
+                .map(|chunk| chunk.iter().fold(T::from(0), |acc, v| acc + v.clone()))
+                .collect()
+        };
+        Self::new(outer, sums)
+    }
+
+    /// Sum the products over `axis` of `self` and `other_axis` of
+    /// `other`. The output axes are the remaining axes of `self`
+    /// followed by the remaining axes of `other`, e.g. the matrix
+    /// product is `a.contract(&b, 1, 0)`. Returns an error if an axis
+    /// is out of range or the contracted axes have different lengths.
+    pub fn contract(
+        &self,
+        other: &Self,
+        axis: usize,
+        other_axis: usize,
+    ) -> Result<Self, ShapeError> {
+        let (lhs_shape, lhs) = self.axis_last(axis)?;
+        let (rhs_shape, rhs) = other.axis_last(other_axis)?;
+        let k = self.shape[axis];
+        if other.shape[other_axis] != k {
+            return Err(ShapeError::InnerDimensionMismatch {
+                lhs: self.shape.clone(),
+                rhs: other.shape.clone(),
+            });
+        }
+        let (m, n) = (
+            lhs_shape.iter().product::<usize>(),
+            rhs_shape.iter().product::<usize>(),
+        );
+        let mut values = Vec::with_capacity(m * n);
+        for i in 0..m {
+            for j in 0..n {
+                let mut acc = T::from(0);
+                for l in 0..k {
+                    acc += lhs[i * k + l].clone() * rhs[j * k + l].clone();
+                }
+                values.push(acc);
+            }
+        }
+        Self::new([lhs_shape, rhs_shape].concat(), values)
+    }
+
+    fn assert_eq_shape(&self, other: &Self) {
+        if self.shape != other.shape {
+            panic!(
+                "lhs and rhs shapes are not equal: {:?} {:?}",
+                self.shape, other.shape
+            );
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(T, T) -> T) -> Self {
+        self.assert_eq_shape(other);
+        let values = self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| f(a.clone(), b.clone()))
+            .collect();
+        Self::new(self.shape.clone(), values).unwrap()
+    }
+}
+
+/// Row major iteration over every index of a shape.
+struct Indices {
+    shape: Vec<usize>,
+    next: Option<Vec<usize>>,
+}
+
+impl Indices {
+    fn new(shape: &[usize]) -> Self {
+        let empty = shape.contains(&0);
+        Indices {
+            shape: shape.to_vec(),
+            next: (!empty).then(|| vec![0; shape.len()]),
+        }
+    }
+}
+
+impl Iterator for Indices {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let current = self.next.take()?;
+        let mut following = current.clone();
+        for axis in (0..self.shape.len()).rev() {
+            following[axis] += 1;
+            if following[axis] < self.shape[axis] {
+                self.next = Some(following);
+                break;
+            }
+            following[axis] = 0;
+        }
+        Some(current)
+    }
+}
+
+/// Tensors are equal if they have the same shape and values,
+/// regardless of the strides.
+impl<T: RingElement> PartialEq for Tensor<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: RingElement> Add for Tensor<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a + b)
+    }
+}
+
+impl<T: RingElement> Sub for Tensor<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a - b)
+    }
+}
+
+/// Elementwise product.
+impl<T: RingElement> Mul for Tensor<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a * b)
+    }
+}
+
+/// Scale every value by `v`.
+impl<T: RingElement> Mul<T> for Tensor<T> {
+    type Output = Self;
+
+    fn mul(mut self, v: T) -> Self {
+        for x in self.values.iter_mut() {
+            *x *= v.clone();
+        }
+        self
+    }
+}
+
+impl<T: RingElement> Neg for Tensor<T> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.values = self.values.into_iter().map(|v| -v).collect();
+        self
+    }
+}
+
+impl<T: RingElement> From<Matrix<T>> for Tensor<T> {
+    fn from(m: Matrix<T>) -> Self {
+        Self::new(m.dimensions, m.values).expect("consistent matrix")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FieldElement;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn tensor(shape: Vec<usize>, values: &[u64]) -> Tensor<F13FieldElement> {
+        Tensor::new(
+            shape,
+            values.iter().map(|v| F13FieldElement::from(*v)).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn layout() {
+        let t = tensor(vec![2, 3, 2], &(0..12).collect::<Vec<_>>());
+        assert_eq!(t.strides(), &[6, 2, 1]);
+        assert_eq!(t.get(&[1, 2, 0]), Some(&F13FieldElement::from(10)));
+        assert_eq!(t.get(&[2, 0, 0]), None);
+        assert_eq!(t.get(&[0, 0]), None);
+        let p = t.clone().permute_axes(&[2, 0, 1]).unwrap();
+        assert_eq!(p.shape(), &[2, 2, 3]);
+        assert!(!p.is_contiguous());
+        assert_eq!(p.get(&[0, 1, 2]), t.get(&[1, 2, 0]));
+        assert_eq!(p.clone().into_contiguous(), p);
+        assert!(p.clone().into_contiguous().is_contiguous());
+        assert_eq!(t.clone().transpose().transpose(), t);
+        // reshape reads in row major order of the current axes
+        let r = p.reshape(vec![4, 3]).unwrap();
+        assert_eq!(r.to_vec()[..4], tensor(vec![4], &[0, 2, 4, 6]).to_vec()[..]);
+        assert_eq!(
+            t.clone().reshape(vec![5]),
+            Err(ShapeError::LengthMismatch {
+                shape: vec![5],
+                len: 12
+            })
+        );
+        assert!(t.clone().permute_axes(&[0, 0, 1]).is_err());
+        assert!(t.permute_axes(&[0, 1]).is_err());
+        let scalar = tensor(vec![], &[7]);
+        assert_eq!((scalar.rank(), scalar.len()), (0, 1));
+        assert_eq!(scalar.get(&[]), Some(&F13FieldElement::from(7)));
+        assert!(Tensor::<F13FieldElement>::zeros(vec![3, 0]).is_empty());
+    }
+
+    #[test]
+    fn elementwise() {
+        let a = tensor(vec![2, 2], &[1, 2, 3, 4]);
+        let b = tensor(vec![2, 2], &[5, 6, 7, 8]);
+        assert_eq!(a.clone() + b.clone(), tensor(vec![2, 2], &[6, 8, 10, 12]));
+        assert_eq!(b.clone() - a.clone(), tensor(vec![2, 2], &[4, 4, 4, 4]));
+        assert_eq!(a.clone() * b.clone(), tensor(vec![2, 2], &[5, 12, 8, 6]));
+        assert_eq!(a.clone() * F13FieldElement::from(2), a.clone() + a.clone());
+        assert_eq!(-a.clone() + a.clone(), Tensor::zeros(vec![2, 2]));
+        // strides don't matter, only the logical layout
+        let at = a.clone().transpose();
+        assert_eq!(at.clone() + at, (a.clone() + a).transpose());
+    }
+
+    #[test]
+    #[should_panic(expected = "shapes are not equal")]
+    fn elementwise_shape_mismatch() {
+        let _ = tensor(vec![2, 2], &[1, 2, 3, 4]) + tensor(vec![4], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn contraction() {
+        let a = tensor(vec![2, 3], &[1, 2, 3, 4, 5, 6]);
+        let b = tensor(vec![3, 2], &[7, 8, 9, 10, 11, 12]);
+        let product = a.contract(&b, 1, 0).unwrap();
+        let matrix = |t: &Tensor<F13FieldElement>| Matrix {
+            dimensions: t.shape().to_vec(),
+            values: t.to_vec(),
+        };
+        assert_eq!(matrix(&product), matrix(&a).matmul(&matrix(&b)).unwrap());
+        assert_eq!(
+            a.contract(&b, 0, 0),
+            Err(ShapeError::InnerDimensionMismatch {
+                lhs: vec![2, 3],
+                rhs: vec![3, 2]
+            })
+        );
+        assert_eq!(
+            a.contract(&b, 2, 0),
+            Err(ShapeError::AxisOutOfRange { axis: 2, rank: 2 })
+        );
+        // vectors contract to a scalar
+        let v = tensor(vec![3], &[1, 2, 3]);
+        assert_eq!(v.contract(&v, 0, 0).unwrap(), tensor(vec![], &[14]));
+        assert_eq!(a.sum_axis(0).unwrap(), tensor(vec![3], &[5, 7, 9]));
+        assert_eq!(a.sum_axis(1).unwrap(), tensor(vec![2], &[6, 15]));
+    }
+
+    #[test]
+    fn multilinear_partial_evaluation() {
+        // evaluations of f(x0, x1, x2) on {0, 1}^3, x0 is the first axis
+        let f = |x: &[usize]| F13FieldElement::from((1 + 2 * x[0] + 3 * x[1] * x[2]) as u64);
+        let evals = Tensor::from_fn(vec![2, 2, 2], f);
+        // fix x0 = r by contracting with (1 - r, r)
+        let r = F13FieldElement::from(5);
+        let weights = Tensor::new(vec![2], vec![F13FieldElement::one() - r, r]).unwrap();
+        let folded = weights.contract(&evals, 0, 0).unwrap();
+        assert_eq!(folded.shape(), &[2, 2]);
+        for (x1, x2) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let expected =
+                F13FieldElement::from((1 + 3 * x1 * x2) as u64) + r * F13FieldElement::from(2);
+            assert_eq!(folded.get(&[x1, x2]), Some(&expected));
+        }
+        // the sum over the hypercube
+        let total = evals
+            .sum_axis(0)
+            .unwrap()
+            .sum_axis(0)
+            .unwrap()
+            .sum_axis(0)
+            .unwrap();
+        assert_eq!(total, tensor(vec![], &[(8 + 8 + 6) % 13]));
+    }
+}
@@ -19,6 +19,8 @@ pub fn stat_exec(f: &mut dyn Fn() -> String) {
     let now = Instant::now();
     let name = f();
     let elapsed = now.elapsed();
+    #[cfg(feature = "tracing")]
+    tracing::info!(name = %name, elapsed_ms = elapsed.as_millis() as u64, "function executed");
     let time_str = format!("{} ms", elapsed.as_millis()).bold().italic();
     println!(
         "{}",
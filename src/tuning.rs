@@ -0,0 +1,57 @@
+//! Thresholds used to select between algorithms.
+//!
+//! The best crossover points depend on the machine and the field.
+//! The defaults are reasonable for a laptop. Override them globally
+//! with `set_tuning`, or per call using the `*_with` variants of
+//! functions that take a `Tuning` (e.g. `Polynomial::mul_with`,
+//! `Matrix::matmul_with`).
+//!
+use std::sync::RwLock;
+
+/// Algorithm selection thresholds. Sizes are counted in elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tuning {
+    /// Polynomials with at most this many coefficients are
+    /// multiplied with the schoolbook method, larger ones
+    /// use Karatsuba.
+    pub karatsuba_threshold: usize,
+    /// Products with at least this many coefficients are computed
+    /// with an NTT when the field has a large enough two-adic subgroup.
+    pub ntt_threshold: usize,
+    /// Matrix products with every dimension at least this large
+    /// use a cache blocked loop.
+    pub matmul_blocked_threshold: usize,
+    /// Side length of the blocks used by blocked matrix products.
+    pub matmul_block_size: usize,
+    /// Inputs with fewer elements than this are processed on the
+    /// calling thread. Only used with the `parallel` feature.
+    pub parallel_cutoff: usize,
+}
+
+impl Tuning {
+    pub const DEFAULT: Tuning = Tuning {
+        karatsuba_threshold: 32,
+        ntt_threshold: 256,
+        matmul_blocked_threshold: 128,
+        matmul_block_size: 32,
+        parallel_cutoff: 1 << 12,
+    };
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static TUNING: RwLock<Tuning> = RwLock::new(Tuning::DEFAULT);
+
+/// The thresholds used by functions that aren't passed a `Tuning`.
+pub fn tuning() -> Tuning {
+    *TUNING.read().unwrap()
+}
+
+/// Replace the global thresholds.
+pub fn set_tuning(tuning: Tuning) {
+    *TUNING.write().unwrap() = tuning;
+}
@@ -0,0 +1,240 @@
+//! Vectors with a length known at runtime.
+//!
+//! `Vector<T>` wraps a `Vec<T>` with the elementwise operations used
+//! by lattice and commitment code: `dot`, `scale`, `hadamard` (the
+//! elementwise product) and `sum`. Binary operations panic if the
+//! lengths differ, like the elementwise `Matrix` operations. Use
+//! `FixedVector` when the length is known at compile time.
+//!
+use std::ops::Add;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use super::fixed_vector::FixedVector;
+use super::matrix::Matrix;
+use super::FieldElement;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Vector<T: FieldElement>(pub Vec<T>);
+
+impl<T: FieldElement> Vector<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        Vector(values)
+    }
+
+    pub fn zero(len: usize) -> Self {
+        Vector(vec![T::zero(); len])
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    /// Convert a 1 dimensional matrix.
+    pub fn from_matrix(m: &Matrix<T>) -> Option<Self> {
+        if m.dimensions.len() != 1 {
+            return None;
+        }
+        Some(Vector(m.values.clone()))
+    }
+
+    pub fn assert_eq_len(&self, other: &Self) {
+        if self.len() != other.len() {
+            panic!(
+                "vectors have different lengths: {} {}",
+                self.len(),
+                other.len()
+            );
+        }
+    }
+
+    /// `sum_i a_i b_i`. Panics if the lengths differ.
+    pub fn dot(&self, other: &Self) -> T {
+        self.assert_eq_len(other);
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+    }
+
+    pub fn scale(&self, v: &T) -> Self {
+        Vector(self.0.iter().map(|a| a.clone() * v.clone()).collect())
+    }
+
+    /// The elementwise product. Panics if the lengths differ.
+    pub fn hadamard(&self, other: &Self) -> Self {
+        self.assert_eq_len(other);
+        Vector(
+            self.0
+                .iter()
+                .zip(other.0.iter())
+                .map(|(a, b)| a.clone() * b.clone())
+                .collect(),
+        )
+    }
+
+    pub fn sum(&self) -> T {
+        self.0.iter().fold(T::zero(), |acc, a| acc + a.clone())
+    }
+}
+
+impl<T: FieldElement> From<Vec<T>> for Vector<T> {
+    fn from(values: Vec<T>) -> Self {
+        Vector(values)
+    }
+}
+
+impl<T: FieldElement> From<Vector<T>> for Vec<T> {
+    fn from(v: Vector<T>) -> Self {
+        v.0
+    }
+}
+
+impl<T: FieldElement> From<Vector<T>> for Matrix<T> {
+    fn from(v: Vector<T>) -> Self {
+        Matrix {
+            dimensions: vec![v.len()],
+            values: v.0,
+        }
+    }
+}
+
+impl<T: FieldElement, const N: usize> From<FixedVector<T, N>> for Vector<T> {
+    fn from(v: FixedVector<T, N>) -> Self {
+        Vector(v.0.into())
+    }
+}
+
+impl<T: FieldElement> FromIterator<T> for Vector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Vector(iter.into_iter().collect())
+    }
+}
+
+impl<T: FieldElement> Index<usize> for Vector<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T: FieldElement> IndexMut<usize> for Vector<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+impl<T: FieldElement> Add for Vector<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.assert_eq_len(&other);
+        self.0
+            .into_iter()
+            .zip(other.0)
+            .map(|(a, b)| a + b)
+            .collect()
+    }
+}
+
+impl<T: FieldElement> Sub for Vector<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.assert_eq_len(&other);
+        self.0
+            .into_iter()
+            .zip(other.0)
+            .map(|(a, b)| a - b)
+            .collect()
+    }
+}
+
+/// Scalar multiplication, see `scale`.
+impl<T: FieldElement> Mul<T> for Vector<T> {
+    type Output = Self;
+
+    fn mul(self, v: T) -> Self {
+        self.scale(&v)
+    }
+}
+
+impl<T: FieldElement> Neg for Vector<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.0.into_iter().map(|v| -v).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    fn vector(values: &[u64]) -> Vector<F13FieldElement> {
+        values.iter().map(|v| F13FieldElement::from(*v)).collect()
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = vector(&[1, 2, 3]);
+        let b = vector(&[4, 5, 6]);
+        // 4 + 10 + 18 = 32 = 6 mod 13
+        assert_eq!(a.dot(&b), F13FieldElement::from(6));
+        assert_eq!(a.hadamard(&b), vector(&[4, 10, 5]));
+        assert_eq!(a.hadamard(&b).sum(), a.dot(&b));
+        assert_eq!(a.clone() + b.clone(), vector(&[5, 7, 9]));
+        assert_eq!(b.clone() - a.clone(), vector(&[3, 3, 3]));
+        assert_eq!(a.clone() * F13FieldElement::from(5), vector(&[5, 10, 2]));
+        assert_eq!(-a.clone() + a.clone(), Vector::zero(3));
+        assert_eq!(a[2], F13FieldElement::from(3));
+        assert!(Vector::<F13FieldElement>::default().is_empty());
+        assert_eq!(
+            Vector::<F13FieldElement>::zero(0).sum(),
+            F13FieldElement::zero()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "vectors have different lengths")]
+    fn length_mismatch() {
+        vector(&[1, 2]).dot(&vector(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn conversions() {
+        let a = vector(&[1, 2, 3]);
+        let m = Matrix::from(a.clone());
+        assert_eq!(m.dimensions, vec![3]);
+        assert_eq!(Vector::from_matrix(&m), Some(a.clone()));
+        assert_eq!(
+            Vector::from_matrix(&Matrix::<F13FieldElement>::identity(3)),
+            None
+        );
+        let fixed = FixedVector([1, 2, 3].map(F13FieldElement::from));
+        assert_eq!(Vector::from(fixed), a);
+        assert_eq!(Vec::from(a.clone()), a.as_slice().to_vec());
+    }
+}
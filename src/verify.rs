@@ -0,0 +1,161 @@
+//! Cross checking of backend arithmetic, enabled with the
+//! `verify-backend` feature.
+//!
+//! Every `+`, `-`, `*`, `/` and unary `-` on a concrete field element
+//! is recomputed with a reference implementation and the results are
+//! compared. A mismatch panics with the operands. `OxfoiFieldElement`
+//! is checked against the native `u128` implementation in `oxfoi_slow`,
+//! the other fields against `BigUint` modular arithmetic.
+//!
+//! This is very slow. It's meant for running a downstream test suite
+//! while migrating between backends, not for production builds.
+//!
+use num_bigint::BigUint;
+
+use super::model_check::Op;
+use super::FieldElement;
+
+/// Compare the result of `op` against `BigUint` modular arithmetic.
+/// `b` is ignored for `Op::Neg`.
+pub(crate) fn check_model<T: FieldElement>(op: Op, a: &T, b: &T, actual: &T) {
+    let p = T::prime();
+    let (x, y) = (a.to_biguint(), b.to_biguint());
+    let expected = match op {
+        Op::Add => (&x + &y) % &p,
+        Op::Sub => (&x + &p - &y) % &p,
+        Op::Mul => (&x * &y) % &p,
+        Op::Div => {
+            // division by zero behavior is backend specific
+            if y == BigUint::from(0_u32) {
+                return;
+            }
+            (&x * y.modpow(&(&p - 2_u32), &p)) % &p
+        }
+        Op::Neg => (&p - &x) % &p,
+        Op::Serialize | Op::Bytes => unreachable!("not an arithmetic operation"),
+    };
+    compare::<T>(op, a, b, actual.to_biguint(), expected);
+}
+
+/// Compare the result of `op` against the native oxfoi implementation.
+#[cfg(feature = "oxfoi")]
+pub(crate) fn check_native_oxfoi<T: FieldElement>(op: Op, a: &T, b: &T, actual: &T) {
+    use super::oxfoi_slow::OxfoiFieldElement as Native;
+    let x = Native::from_biguint(&a.to_biguint());
+    let y = Native::from_biguint(&b.to_biguint());
+    let expected = match op {
+        Op::Add => x + y,
+        Op::Sub => x - y,
+        Op::Mul => x * y,
+        Op::Div => {
+            if y == Native::zero() {
+                return;
+            }
+            x / y
+        }
+        Op::Neg => -x,
+        Op::Serialize | Op::Bytes => unreachable!("not an arithmetic operation"),
+    };
+    compare::<T>(op, a, b, actual.to_biguint(), expected.to_biguint());
+}
+
+fn compare<T: FieldElement>(op: Op, a: &T, b: &T, actual: BigUint, expected: BigUint) {
+    if actual != expected {
+        panic!(
+            "{} backend {} diverged from reference: {op:?}({}, {}) expected {expected} got {actual}",
+            T::name_str(),
+            T::backend_str(),
+            a.to_biguint(),
+            b.to_biguint(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    scalar_ring!(F13FieldElement, 13_u128, "f13");
+
+    #[allow(dead_code)]
+    fn test_operations<T: FieldElement>() {
+        // every operation is verified as it runs
+        let mut x = T::from(3);
+        for i in 1..200_u64 {
+            let y = T::from(i);
+            x = (x.clone() * y.clone() + y.clone()) / y.clone() - -x;
+        }
+        assert_ne!(x, T::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged from reference")]
+    fn add_mismatch_panics() {
+        // 5 + 9 = 1
+        let (a, b) = (F13FieldElement::from(5), F13FieldElement::from(9));
+        check_model(Op::Add, &a, &b, &F13FieldElement::from(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "diverged from reference")]
+    fn mul_mismatch_panics() {
+        // 5 * 9 = 6
+        let (a, b) = (F13FieldElement::from(5), F13FieldElement::from(9));
+        check_model(Op::Mul, &a, &b, &F13FieldElement::from(1));
+    }
+
+    #[cfg(feature = "alt_bn128")]
+    #[test]
+    fn verified_bn128() {
+        test_operations::<crate::Bn128FieldElement>();
+    }
+
+    #[cfg(feature = "babybear")]
+    #[test]
+    fn verified_babybear() {
+        test_operations::<crate::BabyBearFieldElement>();
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn verified_curve25519() {
+        test_operations::<crate::Curve25519FieldElement>();
+    }
+
+    #[cfg(feature = "mersenne")]
+    #[test]
+    fn verified_mersenne() {
+        test_operations::<crate::Mersenne31FieldElement>();
+        test_operations::<crate::Mersenne61FieldElement>();
+        test_operations::<crate::Mersenne127FieldElement>();
+    }
+
+    #[cfg(feature = "pallas")]
+    #[test]
+    fn verified_pallas() {
+        test_operations::<crate::PallasFieldElement>();
+    }
+
+    #[cfg(feature = "vesta")]
+    #[test]
+    fn verified_vesta() {
+        test_operations::<crate::VestaFieldElement>();
+    }
+
+    #[cfg(feature = "stark252")]
+    #[test]
+    fn verified_stark252() {
+        test_operations::<crate::Stark252FieldElement>();
+    }
+
+    #[cfg(feature = "oxfoi")]
+    #[test]
+    fn verified_foi() {
+        test_operations::<crate::OxfoiFieldElement>();
+        let (a, b) = (
+            crate::OxfoiFieldElement::from(5),
+            -crate::OxfoiFieldElement::one(),
+        );
+        check_native_oxfoi(Op::Mul, &a, &b, &-a);
+    }
+}